@@ -0,0 +1,64 @@
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use icalendar::{
+    parser::{read_calendar, read_calendar_folded, unfold},
+    Calendar, Component, Event, EventLike,
+};
+
+/// A feed with folded and escaped descriptions, like the ones exported by most calendar servers.
+fn feed(events: usize) -> String {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    (0..events)
+        .map(|i| {
+            Event::new()
+                .uid(&format!("{}@example.com", i))
+                .summary(&format!("Meeting number {}", i))
+                .description(
+                    "Agenda: status, planning; retro.\nPlease bring your notes, \
+                     the dial-in details are in the invitation that was sent out last week.",
+                )
+                .location("Room 1, Building 2")
+                .starts(start + Duration::hours(i as i64))
+                .ends(start + Duration::hours(i as i64 + 1))
+                .done()
+        })
+        .collect::<Calendar>()
+        .to_string()
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for events in [10, 1000] {
+        let input = feed(events);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("unfold", events), &input, |b, input| {
+            b.iter(|| {
+                read_calendar(&unfold(black_box(input)))
+                    .unwrap()
+                    .components
+                    .len()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("folded", events), &input, |b, input| {
+            b.iter(|| {
+                read_calendar_folded(black_box(input))
+                    .unwrap()
+                    .components
+                    .len()
+            })
+        });
+        #[cfg(feature = "rayon")]
+        group.bench_with_input(BenchmarkId::new("parallel", events), &input, |b, input| {
+            b.iter(|| {
+                icalendar::parser::read_calendar_parallel(black_box(input))
+                    .unwrap()
+                    .components
+                    .len()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -1,4 +1,21 @@
-use icalendar::*;
+use icalendar::{typed::TypedProperty, *};
+
+/// `X-IMPORTANCE` declared once, so it can be set and read as a number
+struct Importance;
+
+impl TypedProperty for Importance {
+    const NAME: &'static str = "X-IMPORTANCE";
+    const VALUE_TYPE: ValueType = ValueType::Integer;
+    type Value = u8;
+
+    fn to_property(importance: u8) -> Property {
+        Property::new(Self::NAME, importance.to_string())
+    }
+
+    fn from_property(property: &Property) -> Option<u8> {
+        property.value().parse().ok()
+    }
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let event = Event::new()
@@ -9,9 +26,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .add_parameter("DUE", "tomorrow")
                 .done(),
         )
+        .set::<Importance>(3)
         .uid("my.own.id")
         .done();
 
+    assert_eq!(event.get::<Importance>(), Some(3));
+
     let calendar = Calendar::from([event]);
 
     calendar.print()?;
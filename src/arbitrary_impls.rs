@@ -0,0 +1,393 @@
+//! [`Arbitrary`] implementations that generate valid iCalendar
+//!
+//! Names come from a fixed set of properties whose value types are known,
+//! text is arbitrary apart from control characters,
+//! and every `TZID` that is used is defined by a `VTIMEZONE`.
+
+use std::collections::BTreeSet;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+use crate::{
+    components::{InnerComponent, Other},
+    Alarm, Calendar, CalendarDateTime, Class, Component, DatePerhapsTime, Event, EventLike,
+    EventStatus, Property, Todo, TodoStatus, Trigger,
+};
+
+/// Time zones with their standard offset, used for `TZID`s and their `VTIMEZONE`s
+const TIMEZONES: [(&str, &str); 5] = [
+    ("Europe/Berlin", "+0100"),
+    ("America/New_York", "-0500"),
+    ("Asia/Tokyo", "+0900"),
+    ("Australia/Sydney", "+1000"),
+    ("America/Sao_Paulo", "-0300"),
+];
+
+const LANGUAGES: [&str; 4] = ["en", "de", "fr-CA", "ja"];
+
+/// Days from CE of 1970-01-01 and 2099-12-31
+const DAYS: std::ops::RangeInclusive<i32> = 719_163..=766_644;
+
+/// Text without control characters other than newlines
+fn text(u: &mut Unstructured<'_>) -> Result<String> {
+    let text: String = u.arbitrary()?;
+    Ok(text
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\n')
+        .take(200)
+        .collect())
+}
+
+/// A non-empty sequence of `alphabet`
+fn word(u: &mut Unstructured<'_>, alphabet: &[u8]) -> Result<String> {
+    let len = u.int_in_range(1..=12)?;
+    (0..len)
+        .map(|_| u.choose(alphabet).map(|c| char::from(*c)))
+        .collect()
+}
+
+fn name(u: &mut Unstructured<'_>) -> Result<String> {
+    word(u, b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-")
+}
+
+fn token(u: &mut Unstructured<'_>) -> Result<String> {
+    word(u, b"abcdefghijklmnopqrstuvwxyz0123456789-.")
+}
+
+fn uid(u: &mut Unstructured<'_>) -> Result<String> {
+    Ok(format!("{}@example.com", token(u)?))
+}
+
+fn date(u: &mut Unstructured<'_>) -> Result<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(u.int_in_range(DAYS)?)
+        .ok_or(arbitrary::Error::IncorrectFormat)
+}
+
+fn date_time(u: &mut Unstructured<'_>) -> Result<NaiveDateTime> {
+    let time = NaiveTime::from_num_seconds_from_midnight_opt(u.int_in_range(0..=86_399)?, 0)
+        .ok_or(arbitrary::Error::IncorrectFormat)?;
+    Ok(date(u)?.and_time(time))
+}
+
+fn utc(u: &mut Unstructured<'_>) -> Result<chrono::DateTime<Utc>> {
+    Ok(Utc.from_utc_datetime(&date_time(u)?))
+}
+
+impl<'a> Arbitrary<'a> for CalendarDateTime {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let date_time = date_time(u)?;
+        Ok(match u.int_in_range(0..=2)? {
+            0 => CalendarDateTime::Floating(date_time),
+            1 => CalendarDateTime::Utc(Utc.from_utc_datetime(&date_time)),
+            _ => CalendarDateTime::WithTimezone {
+                date_time,
+                tzid: u.choose(&TIMEZONES)?.0.to_owned(),
+            },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for DatePerhapsTime {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.arbitrary()? {
+            Ok(DatePerhapsTime::Date(date(u)?))
+        } else {
+            Ok(DatePerhapsTime::DateTime(u.arbitrary()?))
+        }
+    }
+}
+
+/// A value of the same form that is up to a month after `start`
+fn later(u: &mut Unstructured<'_>, start: &DatePerhapsTime) -> Result<DatePerhapsTime> {
+    let minutes = Duration::minutes(u.int_in_range(1..=44_640)?);
+    Ok(match start {
+        DatePerhapsTime::Date(date) => {
+            DatePerhapsTime::Date(*date + Duration::days(u.int_in_range(1..=31)?))
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(date_time)) => {
+            CalendarDateTime::Floating(*date_time + minutes).into()
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(date_time)) => {
+            CalendarDateTime::Utc(*date_time + minutes).into()
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => {
+            CalendarDateTime::WithTimezone {
+                date_time: *date_time + minutes,
+                tzid: tzid.clone(),
+            }
+            .into()
+        }
+    })
+}
+
+/// Properties that may occur once in both events and to-dos
+impl<'a> Arbitrary<'a> for Property {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=3)? {
+            0 => {
+                let key = *u.choose(&["SUMMARY", "DESCRIPTION", "LOCATION"])?;
+                let mut property = Property::new(key, text(u)?);
+                if u.arbitrary()? {
+                    property.add_parameter("LANGUAGE", u.choose(&LANGUAGES)?);
+                }
+                property
+            }
+            1 => Property::new("URL", format!("https://example.com/{}", token(u)?)),
+            2 => Property::new("PRIORITY", u.int_in_range(0..=9_u8)?.to_string()),
+            _ => {
+                let mut property = Property::new(format!("X-{}", name(u)?), token(u)?);
+                if u.arbitrary()? {
+                    // the parser does not tell `X-KEY=` from `X-KEY`, so this is never empty
+                    let value = Some(text(u)?).filter(|value| !value.is_empty());
+                    let value = value.map_or_else(|| token(u), Ok)?;
+                    property.add_parameter(&format!("X-{}", name(u)?), &value);
+                }
+                property
+            }
+        })
+    }
+}
+
+/// An alarm whose trigger is only relative to a `DTSTART` or `DUE`/`DTEND` its parent has
+fn alarm(u: &mut Unstructured<'_>, has_start: bool, has_end: bool) -> Result<Alarm> {
+    let before = Duration::minutes(u.int_in_range(0..=10_080)?);
+    let trigger = match u.int_in_range(0..=2_u8)? {
+        0 if has_start => Trigger::before_start(before),
+        1 if has_end => Trigger::before_end(before),
+        _ => Trigger::DateTime(CalendarDateTime::Utc(utc(u)?)),
+    };
+    let alarm = if u.arbitrary()? {
+        Alarm::display(&text(u)?, trigger)
+    } else {
+        Alarm::audio(trigger)
+    };
+    if u.arbitrary()? {
+        Ok(alarm.duration_and_repeat(
+            Duration::minutes(u.int_in_range(1..=60)?),
+            u.int_in_range(1..=5_u32)?,
+        ))
+    } else {
+        Ok(alarm)
+    }
+}
+
+/// Alarms on their own have nothing to be relative to, so their triggers are absolute
+impl<'a> Arbitrary<'a> for Alarm {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        alarm(u, false, false)
+    }
+}
+
+/// Adds alarms once the start and end of `component` are known
+fn alarms<C: EventLike>(u: &mut Unstructured<'_>, component: &mut C, has_end: bool) -> Result<()> {
+    let has_start = component.get_start().is_some();
+    for _ in 0..u.int_in_range(0..=2)? {
+        component.alarm(alarm(u, has_start, has_end)?);
+    }
+    Ok(())
+}
+
+/// The parts events and to-dos have in common
+fn event_like<C: EventLike>(u: &mut Unstructured<'_>, component: &mut C) -> Result<()> {
+    component.uid(&uid(u)?).timestamp(utc(u)?);
+    if u.arbitrary()? {
+        component.summary(&text(u)?);
+    }
+    if u.arbitrary()? {
+        component.description(&text(u)?);
+    }
+    if u.arbitrary()? {
+        component.class(*u.choose(&[Class::Public, Class::Private, Class::Confidential])?);
+    }
+    for _ in 0..u.int_in_range(0..=3)? {
+        component.append_property(Property::arbitrary(u)?);
+    }
+    Ok(())
+}
+
+impl<'a> Arbitrary<'a> for Event {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut event = Event::new();
+        event_like(u, &mut event)?;
+        if u.arbitrary()? {
+            let start = DatePerhapsTime::arbitrary(u)?;
+            if u.arbitrary()? {
+                event.ends(later(u, &start)?);
+            }
+            event.starts(start);
+        }
+        if u.arbitrary()? {
+            event.status(*u.choose(&[
+                EventStatus::Tentative,
+                EventStatus::Confirmed,
+                EventStatus::Cancelled,
+            ])?);
+        }
+        let has_end = event.get_end().is_some();
+        alarms(u, &mut event, has_end)?;
+        Ok(event.done())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Todo {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut todo = Todo::new();
+        event_like(u, &mut todo)?;
+        if u.arbitrary()? {
+            todo.due(DatePerhapsTime::arbitrary(u)?);
+        }
+        if u.arbitrary()? {
+            todo.percent_complete(u.int_in_range(0..=100)?);
+        }
+        if u.arbitrary()? {
+            todo.status(*u.choose(&[
+                TodoStatus::NeedsAction,
+                TodoStatus::Completed,
+                TodoStatus::InProcess,
+                TodoStatus::Cancelled,
+            ])?);
+        }
+        let has_due = todo.get_due().is_some();
+        alarms(u, &mut todo, has_due)?;
+        Ok(todo.done())
+    }
+}
+
+/// Collects the `TZID`s used anywhere in `component`.
+fn collect_tzids<C: Component + ?Sized>(component: &C, tzids: &mut BTreeSet<String>) {
+    let properties = component
+        .properties()
+        .values()
+        .chain(component.multi_properties().values().flatten());
+    for property in properties {
+        if let Some(tzid) = property.params().get("TZID") {
+            tzids.insert(tzid.value().to_owned());
+        }
+    }
+    for child in component.components() {
+        collect_tzids(child, tzids);
+    }
+}
+
+/// A minimal `VTIMEZONE` with only the standard offset.
+fn vtimezone(tzid: &str) -> Other {
+    let offset = TIMEZONES
+        .iter()
+        .find(|(id, _)| *id == tzid)
+        .map_or("+0000", |(_, offset)| offset);
+    let mut standard = Other::from(("STANDARD".to_owned(), InnerComponent::default()));
+    standard
+        .add_property("DTSTART", "19700101T000000")
+        .add_property("TZOFFSETFROM", offset)
+        .add_property("TZOFFSETTO", offset);
+    Other::from(("VTIMEZONE".to_owned(), InnerComponent::default()))
+        .add_property("TZID", tzid)
+        .append_component(standard)
+        .clone()
+}
+
+impl<'a> Arbitrary<'a> for Calendar {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut calendar = Calendar::new();
+        let mut tzids = BTreeSet::new();
+        let mut components = Vec::new();
+        for _ in 0..u.int_in_range(0..=8)? {
+            if u.ratio(2, 3)? {
+                let event = Event::arbitrary(u)?;
+                collect_tzids(&event, &mut tzids);
+                components.push(event.into());
+            } else {
+                let todo = Todo::arbitrary(u)?;
+                collect_tzids(&todo, &mut tzids);
+                components.push(todo.into());
+            }
+        }
+        for tzid in tzids {
+            calendar.push(vtimezone(&tzid));
+        }
+        calendar.components.extend(components);
+        Ok(calendar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Related;
+
+    #[test]
+    fn generates_from_little_data() {
+        let bytes = (0..=255_u8).cycle().take(4096).collect::<Vec<_>>();
+        let calendar = Calendar::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert_eq!(calendar.property_value("VERSION"), Some("2.0"));
+
+        let calendar = Calendar::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert!(calendar.components.is_empty());
+    }
+
+    #[test]
+    fn timezones_are_defined() {
+        let bytes = (0..=255_u8).rev().cycle().take(8192).collect::<Vec<_>>();
+        let mut u = Unstructured::new(&bytes);
+        while !u.is_empty() {
+            let calendar = Calendar::arbitrary(&mut u).unwrap();
+            let mut used = BTreeSet::new();
+            for component in &calendar.components {
+                if let Some(event) = component.as_event() {
+                    collect_tzids(event, &mut used);
+                }
+                if let Some(todo) = component.as_todo() {
+                    collect_tzids(todo, &mut used);
+                }
+            }
+            let defined = calendar
+                .components
+                .iter()
+                .filter_map(|component| component.inner().properties.get("TZID"))
+                .map(|tzid| tzid.value().to_owned())
+                .collect::<BTreeSet<_>>();
+            assert_eq!(used, defined);
+        }
+    }
+
+    #[test]
+    fn relative_triggers_have_an_anchor() {
+        let bytes = (0..=255_u8)
+            .cycle()
+            .skip(7)
+            .take(16_384)
+            .collect::<Vec<_>>();
+        let mut u = Unstructured::new(&bytes);
+        let mut relative = 0;
+        while !u.is_empty() {
+            let calendar = Calendar::arbitrary(&mut u).unwrap();
+            for component in &calendar.components {
+                let (alarms, has_start, has_end) = match (component.as_event(), component.as_todo())
+                {
+                    (Some(event), _) => (
+                        event.alarms(),
+                        event.get_start().is_some(),
+                        event.get_end().is_some(),
+                    ),
+                    (_, Some(todo)) => (
+                        todo.alarms(),
+                        todo.get_start().is_some(),
+                        todo.get_due().is_some(),
+                    ),
+                    _ => continue,
+                };
+                for alarm in alarms {
+                    if let Some(Trigger::Duration(_, related)) = alarm.get_trigger() {
+                        relative += 1;
+                        match related {
+                            Some(Related::End) => assert!(has_end),
+                            _ => assert!(has_start),
+                        }
+                    }
+                }
+            }
+        }
+        assert!(relative > 0);
+    }
+}
@@ -0,0 +1,152 @@
+/// Options that take a value, all others are flags
+const WITH_VALUE: [&str; 4] = ["from", "to", "text", "ignore"];
+
+/// Options and operands of a subcommand
+///
+/// Options may be given as `--name value` or `--name=value`, everything after `--` is an operand.
+/// Subcommands take the options they know, [`Args::finish()`] rejects the rest.
+#[derive(Debug, Default)]
+pub struct Args {
+    operands: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    pub fn parse(raw: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = Args::default();
+        let mut raw = raw.into_iter();
+        while let Some(arg) = raw.next() {
+            if arg == "--" {
+                args.operands.extend(raw.by_ref());
+            } else if let Some(option) = arg.strip_prefix("--") {
+                let option = match option.split_once('=') {
+                    Some((name, value)) => (name.to_owned(), Some(value.to_owned())),
+                    None if WITH_VALUE.contains(&option) => match raw.next() {
+                        Some(value) => (option.to_owned(), Some(value)),
+                        None => return Err(format!("option --{} needs a value", option)),
+                    },
+                    None => (option.to_owned(), None),
+                };
+                args.options.push(option);
+            } else if arg.starts_with('-') && arg != "-" {
+                return Err(format!("unknown option {}", arg));
+            } else {
+                args.operands.push(arg);
+            }
+        }
+        Ok(args)
+    }
+
+    fn take(&mut self, name: &str) -> Vec<Option<String>> {
+        let (taken, rest) = self
+            .options
+            .drain(..)
+            .partition::<Vec<_>, _>(|(option, _)| option == name);
+        self.options = rest;
+        taken.into_iter().map(|(_, value)| value).collect()
+    }
+
+    /// Whether the flag `--name` was given.
+    pub fn flag(&mut self, name: &str) -> Result<bool, String> {
+        let given = self.take(name);
+        if given.iter().any(Option::is_some) {
+            return Err(format!("option --{} does not take a value", name));
+        }
+        Ok(!given.is_empty())
+    }
+
+    /// All values of `--name`, in the order they were given.
+    pub fn values(&mut self, name: &str) -> Result<Vec<String>, String> {
+        self.take(name)
+            .into_iter()
+            .map(|value| value.ok_or_else(|| format!("option --{} needs a value", name)))
+            .collect()
+    }
+
+    /// The value of `--name`, the last one if it was given several times.
+    pub fn value(&mut self, name: &str) -> Result<Option<String>, String> {
+        Ok(self.values(name)?.pop())
+    }
+
+    /// The files to read, `-` for standard input if there are none.
+    pub fn files(&mut self) -> Vec<String> {
+        match std::mem::take(&mut self.operands) {
+            files if files.is_empty() => vec![String::from("-")],
+            files => files,
+        }
+    }
+
+    /// The single file to read, `-` for standard input if there is none.
+    pub fn file(&mut self) -> Result<String, String> {
+        match self.files().as_slice() {
+            [file] => Ok(file.clone()),
+            files => Err(format!("expected one file, got {}", files.len())),
+        }
+    }
+
+    /// Exactly `N` files, standard input is not used.
+    pub fn exact_files<const N: usize>(&mut self) -> Result<[String; N], String> {
+        let files = std::mem::take(&mut self.operands);
+        let count = files.len();
+        files
+            .try_into()
+            .map_err(|_| format!("expected {} files, got {}", N, count))
+    }
+
+    /// Fails on options that were not taken by the subcommand.
+    pub fn finish(self) -> Result<(), String> {
+        match self.options.first() {
+            Some((name, _)) => Err(format!("unknown option --{}", name)),
+            None if !self.operands.is_empty() => {
+                Err(format!("unexpected argument {}", self.operands.join(" ")))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options_and_operands() {
+        let mut args = parse(&[
+            "--lf",
+            "a.ics",
+            "--to",
+            "jcal",
+            "--ignore=DTSTAMP",
+            "--ignore",
+            "SEQUENCE",
+            "-",
+            "--",
+            "--not-an-option",
+        ])
+        .unwrap();
+        assert_eq!(args.flag("lf"), Ok(true));
+        assert_eq!(args.flag("canonical"), Ok(false));
+        assert_eq!(args.value("to"), Ok(Some(String::from("jcal"))));
+        assert_eq!(
+            args.values("ignore"),
+            Ok(vec![String::from("DTSTAMP"), String::from("SEQUENCE")])
+        );
+        assert_eq!(args.files(), vec!["a.ics", "-", "--not-an-option"]);
+        assert_eq!(args.finish(), Ok(()));
+    }
+
+    #[test]
+    fn mistakes() {
+        assert!(parse(&["--to"]).is_err());
+        assert!(parse(&["-x"]).is_err());
+        assert!(parse(&["--lf=yes"]).unwrap().flag("lf").is_err());
+        assert!(parse(&["--pretty"]).unwrap().finish().is_err());
+        assert!(parse(&["a.ics", "b.ics"]).unwrap().file().is_err());
+        assert_eq!(parse(&[]).unwrap().file(), Ok(String::from("-")));
+        assert!(parse(&["a.ics"]).unwrap().exact_files::<2>().is_err());
+    }
+}
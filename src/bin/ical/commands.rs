@@ -0,0 +1,369 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use icalendar::{
+    parser::{self, read_calendar_folded, read_components_folded},
+    Calendar, CalendarComponent, CalendarIndex, DiffOptions, LineEnding, MergeConflict,
+    WriteOptions,
+};
+
+use crate::{
+    args::Args,
+    jcal, output, read,
+    validate::{self, Severity},
+};
+
+/// Output does not make up `DTSTAMP`s or `UID`s the input did not have.
+fn write_options() -> WriteOptions {
+    WriteOptions::default().inject_defaults(false)
+}
+
+fn parse_calendar(path: &str, content: &str) -> Result<Calendar, String> {
+    read_calendar_folded(content)
+        .map(Calendar::from)
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Parses `2024-01-31`, `2024-01-31T09:00:00` or their iCalendar forms as UTC.
+fn parse_date(value: &str) -> Result<DateTime<Utc>, String> {
+    let value = value.trim_end_matches('Z');
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y%m%dT%H%M%S"] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(Utc.from_utc_datetime(&date_time));
+        }
+    }
+    ["%Y-%m-%d", "%Y%m%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|midnight| Utc.from_utc_datetime(&midnight))
+        .ok_or_else(|| format!("invalid date {}, expected one like 2024-01-31", value))
+}
+
+type TimeRange = (DateTime<Utc>, DateTime<Utc>);
+
+/// `--from` and `--to`, both or neither.
+fn time_range(args: &mut Args) -> Result<Option<TimeRange>, String> {
+    match (args.value("from")?, args.value("to")?) {
+        (Some(from), Some(to)) => {
+            let (from, to) = (parse_date(&from)?, parse_date(&to)?);
+            if to < from {
+                return Err(String::from("--to is before --from"));
+            }
+            Ok(Some((from, to)))
+        }
+        (None, None) => Ok(None),
+        _ => Err(String::from("--from and --to must be given together")),
+    }
+}
+
+pub fn validate(mut args: Args) -> Result<bool, String> {
+    let files = args.files();
+    args.finish()?;
+
+    let mut all_valid = true;
+    for path in files {
+        let content = read(&path)?;
+        let roots = match read_components_folded(&content) {
+            Ok(roots) => roots,
+            Err(error) => {
+                output(&format!("{}: {}\n", path, error))?;
+                all_valid = false;
+                continue;
+            }
+        };
+
+        let problems = validate::check(&roots);
+        let errors = problems
+            .iter()
+            .filter(|problem| problem.severity == Severity::Error)
+            .count();
+        let mut report = String::new();
+        for problem in &problems {
+            let _ = writeln!(report, "{}: {}", path, problem);
+        }
+        let _ = match errors {
+            0 => writeln!(report, "{}: ok, {} warnings", path, problems.len()),
+            _ => writeln!(
+                report,
+                "{}: {} errors, {} warnings",
+                path,
+                errors,
+                problems.len() - errors
+            ),
+        };
+        output(&report)?;
+        all_valid &= errors == 0;
+    }
+    Ok(all_valid)
+}
+
+/// An indented outline of components and their properties
+fn outline(
+    out: &mut String,
+    name: &str,
+    properties: &[parser::Property<'_>],
+    components: &[parser::Component<'_>],
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    let _ = writeln!(out, "{}{}", indent, name);
+    for property in properties {
+        let _ = write!(out, "{}  {}", indent, property.name.as_str());
+        for param in &property.params {
+            let values = param.values.iter().map(parser::ParseString::as_str);
+            let _ = write!(
+                out,
+                " {}={}",
+                param.key.as_str(),
+                values.collect::<Vec<_>>().join(",")
+            );
+        }
+        let _ = writeln!(out, ": {}", property.val.as_str().replace('\n', "\\n"));
+    }
+    for component in components {
+        outline(
+            out,
+            component.name.as_str(),
+            &component.properties,
+            &component.components,
+            depth + 1,
+        );
+    }
+}
+
+pub fn format(mut args: Args) -> Result<bool, String> {
+    let mut options = match args.flag("canonical")? {
+        true => WriteOptions::canonical(),
+        false => write_options(),
+    };
+    if args.flag("lf")? {
+        options = options.line_ending(LineEnding::Lf);
+    }
+    if args.flag("no-fold")? {
+        options = options.no_folding();
+    }
+    let pretty = args.flag("pretty")?;
+    let path = args.file()?;
+    args.finish()?;
+
+    let content = read(&path)?;
+    if pretty {
+        let calendar = read_calendar_folded(&content).map_err(|e| format!("{}: {}", path, e))?;
+        let mut out = String::new();
+        outline(
+            &mut out,
+            "VCALENDAR",
+            &calendar.properties,
+            &calendar.components,
+            0,
+        );
+        return output(&out).map(|_| true);
+    }
+    let calendar = parse_calendar(&path, &content)?;
+    output(&calendar.to_string_with(&options)).map(|_| true)
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Ics,
+    Json,
+    JCal,
+}
+
+impl Format {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "ics" | "ical" | "icalendar" => Ok(Format::Ics),
+            "json" => Ok(Format::Json),
+            "jcal" => Ok(Format::JCal),
+            other => Err(format!(
+                "unknown format {}, expected ics, json or jcal",
+                other
+            )),
+        }
+    }
+
+    /// jCal is an array, the JSON of the parser an object.
+    fn detect(content: &str) -> Self {
+        match content.trim_start().chars().next() {
+            Some('[') => Format::JCal,
+            Some('{') => Format::Json,
+            _ => Format::Ics,
+        }
+    }
+}
+
+pub fn convert(mut args: Args) -> Result<bool, String> {
+    let to = match args.value("to")? {
+        Some(to) => Format::parse(&to)?,
+        None => return Err(String::from("missing --to <ics|json|jcal>")),
+    };
+    let from = args
+        .value("from")?
+        .map(|from| Format::parse(&from))
+        .transpose()?;
+    let path = args.file()?;
+    args.finish()?;
+
+    let content = read(&path)?;
+    let in_file = |e: String| format!("{}: {}", path, e);
+    let json = |e: serde_json::Error| format!("{}: {}", path, e);
+    let calendar = match from.unwrap_or_else(|| Format::detect(&content)) {
+        Format::Ics => read_calendar_folded(&content).map_err(in_file)?,
+        Format::Json => serde_json::from_str::<parser::Calendar>(&content).map_err(json)?,
+        Format::JCal => {
+            let value = serde_json::from_str(&content).map_err(json)?;
+            jcal::from_jcal(&value).map_err(in_file)?
+        }
+    };
+
+    let converted = match to {
+        Format::Ics => calendar.to_string(),
+        Format::Json => serde_json::to_string_pretty(&calendar).map_err(json)? + "\n",
+        Format::JCal => {
+            serde_json::to_string_pretty(&jcal::to_jcal(&calendar)).map_err(json)? + "\n"
+        }
+    };
+    output(&converted).map(|_| true)
+}
+
+pub fn expand(mut args: Args) -> Result<bool, String> {
+    let (from, to) = time_range(&mut args)?.ok_or("missing --from <DATE> and --to <DATE>")?;
+    let path = args.file()?;
+    args.finish()?;
+
+    let calendar = parse_calendar(&path, &read(&path)?)?;
+    let index = CalendarIndex::from_calendar(&calendar, from, to);
+    let mut out = String::new();
+    for occurrence in index.overlapping(from, to) {
+        let component = occurrence.component;
+        let _ = writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}",
+            occurrence.start.format("%Y-%m-%dT%H:%M:%SZ"),
+            occurrence.end.format("%Y-%m-%dT%H:%M:%SZ"),
+            component.component_kind(),
+            component.uid().unwrap_or_default(),
+            component
+                .property_value("SUMMARY")
+                .unwrap_or_default()
+                .replace('\n', " "),
+        );
+    }
+    output(&out).map(|_| true)
+}
+
+/// Properties searched by `filter --text`
+const SEARCHED: [&str; 4] = ["SUMMARY", "DESCRIPTION", "LOCATION", "UID"];
+
+fn contains_text(component: &CalendarComponent, text: &str) -> bool {
+    SEARCHED
+        .iter()
+        .filter_map(|key| component.property_value(key))
+        .any(|value| value.to_lowercase().contains(text))
+}
+
+pub fn filter(mut args: Args) -> Result<bool, String> {
+    let text = args.value("text")?.map(|text| text.to_lowercase());
+    let range = time_range(&mut args)?;
+    let path = args.file()?;
+    args.finish()?;
+    if text.is_none() && range.is_none() {
+        return Err(String::from(
+            "missing --text <TEXT> or --from <DATE> --to <DATE>",
+        ));
+    }
+
+    let mut calendar = parse_calendar(&path, &read(&path)?)?;
+    let in_range = range.map(|(from, to)| {
+        let index = CalendarIndex::from_calendar(&calendar, from, to);
+        let found = index
+            .overlapping(from, to)
+            .iter()
+            .map(|occurrence| occurrence.id)
+            .collect::<BTreeSet<_>>();
+        // ids are handed out in the order the components were added
+        index
+            .components()
+            .map(|(id, _)| found.contains(&id))
+            .collect::<Vec<_>>()
+    });
+
+    let components = std::mem::take(&mut calendar.components);
+    calendar.components = components
+        .into_iter()
+        .enumerate()
+        .filter(|(position, component)| {
+            let matches_text = text
+                .as_deref()
+                .map_or(true, |text| contains_text(component, text));
+            let in_range = in_range
+                .as_ref()
+                .map_or(true, |in_range| in_range.get(*position) == Some(&true));
+            component.component_kind() == "VTIMEZONE" || (matches_text && in_range)
+        })
+        .map(|(_, component)| component)
+        .collect();
+    output(&calendar.to_string_with(&write_options())).map(|_| true)
+}
+
+pub fn diff(mut args: Args) -> Result<bool, String> {
+    let mut options = DiffOptions::new();
+    if args.flag("ignore-volatile")? {
+        options = options.ignore_volatile();
+    }
+    for name in args.values("ignore")? {
+        options = options.ignore(&name.to_uppercase());
+    }
+    let [old, new] = args.exact_files::<2>()?;
+    args.finish()?;
+
+    let old_calendar = parse_calendar(&old, &read(&old)?)?;
+    let new_calendar = parse_calendar(&new, &read(&new)?)?;
+    let diff = old_calendar.diff_with(&new_calendar, &options);
+    output(&diff.to_string())?;
+    Ok(diff.is_empty())
+}
+
+pub fn merge(mut args: Args) -> Result<bool, String> {
+    let files = args.files();
+    args.finish()?;
+
+    let mut merged: Option<Calendar> = None;
+    for path in files {
+        let calendar = parse_calendar(&path, &read(&path)?)?;
+        let merged = match merged.as_mut() {
+            Some(merged) => merged,
+            None => {
+                merged = Some(calendar);
+                continue;
+            }
+        };
+
+        let report = merged.merge(calendar);
+        eprintln!(
+            "ical: {}: {} added, {} duplicates, {} conflicts",
+            path,
+            report.added,
+            report.duplicates,
+            report.conflicts.len()
+        );
+        for conflict in report.conflicts {
+            let message = match conflict {
+                MergeConflict::Component { key, winner } => {
+                    format!("{} differs, kept {:?}", key, winner)
+                }
+                MergeConflict::Timezone { tzid, winner } => {
+                    format!("VTIMEZONE {} differs, kept {:?}", tzid, winner)
+                }
+                MergeConflict::Property { name, ours, theirs } => {
+                    format!("{} differs, kept {:?} over {:?}", name, ours, theirs)
+                }
+            };
+            eprintln!("ical: {}:   {}", path, message);
+        }
+    }
+    let merged = merged.unwrap_or_default();
+    output(&merged.to_string_with(&write_options())).map(|_| true)
+}
@@ -0,0 +1,495 @@
+//! Conversion between iCalendar and [jCal](https://datatracker.ietf.org/doc/html/rfc7265)
+//!
+//! Values are converted by their type, properties of unknown type keep their text as `unknown`.
+
+use std::str::FromStr;
+
+use icalendar::{
+    parser::{Calendar, Component, Parameter, ParseString, Property},
+    ValueType,
+};
+use serde_json::{json, Map, Value};
+
+/// Properties whose value is structured, see [rfc7265 3.4.1.2](https://datatracker.ietf.org/doc/html/rfc7265#section-3.4.1.2)
+const STRUCTURED: [&str; 2] = ["GEO", "REQUEST-STATUS"];
+
+pub fn to_jcal(calendar: &Calendar<'_>) -> Value {
+    json!([
+        "vcalendar",
+        calendar.properties.iter().map(property).collect::<Vec<_>>(),
+        calendar
+            .components
+            .iter()
+            .map(component)
+            .collect::<Vec<_>>(),
+    ])
+}
+
+fn component(component: &Component<'_>) -> Value {
+    json!([
+        component.name.as_str().to_lowercase(),
+        component
+            .properties
+            .iter()
+            .map(property)
+            .collect::<Vec<_>>(),
+        component
+            .components
+            .iter()
+            .map(self::component)
+            .collect::<Vec<_>>(),
+    ])
+}
+
+fn value_type(property: &Property<'_>) -> Option<ValueType> {
+    property
+        .params
+        .iter()
+        .find(|param| param.key.as_str() == "VALUE")
+        .and_then(Parameter::value)
+        .and_then(|value_type| ValueType::from_str(value_type.as_str()).ok())
+        .or_else(|| ValueType::by_name(property.name.as_str()))
+}
+
+fn property(property: &Property<'_>) -> Value {
+    let name = property.name.as_str();
+    let value_type = value_type(property);
+
+    let mut params = Map::new();
+    for param in property.params.iter().filter(|p| p.key.as_str() != "VALUE") {
+        let value = match param.values.as_slice() {
+            [value] => json!(value.as_str()),
+            values => json!(values.iter().map(ParseString::as_str).collect::<Vec<_>>()),
+        };
+        params.insert(param.key.as_str().to_lowercase(), value);
+    }
+
+    let mut jcal = vec![
+        json!(name.to_lowercase()),
+        Value::Object(params),
+        json!(value_type.map_or_else(
+            || String::from("unknown"),
+            |value_type| value_type.to_string().to_lowercase()
+        )),
+    ];
+
+    match value_type {
+        _ if STRUCTURED.contains(&name) => {
+            let parts = property.val.as_str().split(';');
+            let parts = match value_type {
+                Some(ValueType::Float) => parts.map(float).collect(),
+                _ => parts.map(|part| json!(part)).collect(),
+            };
+            jcal.push(Value::Array(parts));
+        }
+        Some(ValueType::Text) => {
+            let values = icalendar::Property::from(property.clone()).values();
+            jcal.extend(values.into_iter().map(Value::String));
+        }
+        Some(ValueType::Recur) => jcal.push(recur(property.val.as_str())),
+        Some(value_type) => jcal.extend(
+            property
+                .val
+                .as_str()
+                .split(',')
+                .map(|value| to_json(value_type, value)),
+        ),
+        None => jcal.push(json!(property.val.as_str())),
+    }
+    Value::Array(jcal)
+}
+
+fn float(value: &str) -> Value {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map_or_else(|| json!(value), Value::Number)
+}
+
+/// Inserts `separator` into `digits` after each of the `positions`, if it is all digits.
+fn separate(digits: &str, positions: &[usize], separator: char) -> Option<String> {
+    let expected = positions.last().copied().unwrap_or_default();
+    if digits.len() < expected || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut separated = String::with_capacity(digits.len() + positions.len());
+    let mut start = 0;
+    for end in positions.iter().copied().chain(Some(digits.len())) {
+        if start > 0 && start < digits.len() {
+            separated.push(separator);
+        }
+        separated.push_str(digits.get(start..end)?);
+        start = end;
+    }
+    Some(separated)
+}
+
+/// `20240101` to `2024-01-01`
+fn date(value: &str) -> Option<String> {
+    separate(value, &[4, 6], '-').filter(|date| date.len() == 10)
+}
+
+/// `090000Z` to `09:00:00Z`
+fn time(value: &str) -> Option<String> {
+    let (digits, utc) = match value.strip_suffix('Z') {
+        Some(digits) => (digits, "Z"),
+        None => (value, ""),
+    };
+    separate(digits, &[2, 4], ':')
+        .filter(|time| time.len() == 8)
+        .map(|time| time + utc)
+}
+
+/// `20240101T090000Z` to `2024-01-01T09:00:00Z`
+fn date_time(value: &str) -> Option<String> {
+    let (date_part, time_part) = value.split_once('T')?;
+    Some(format!("{}T{}", date(date_part)?, time(time_part)?))
+}
+
+/// `+0100` to `+01:00`
+fn utc_offset(value: &str) -> Option<String> {
+    let sign = value.get(..1).filter(|sign| *sign == "+" || *sign == "-")?;
+    let digits = value.get(1..)?;
+    if digits.len() != 4 && digits.len() != 6 {
+        return None;
+    }
+    Some(format!("{}{}", sign, separate(digits, &[2, 4], ':')?))
+}
+
+/// A date-time or duration of a period
+fn period_part(value: &str) -> String {
+    date_time(value).unwrap_or_else(|| value.to_owned())
+}
+
+fn recur(value: &str) -> Value {
+    let mut rule = Map::new();
+    for part in value.split(';').filter(|part| !part.is_empty()) {
+        let (key, value) = part.split_once('=').unwrap_or((part, ""));
+        let key = key.to_lowercase();
+        let mut values = value
+            .split(',')
+            .map(|value| match value.parse::<i64>() {
+                Ok(number) if key != "until" => json!(number),
+                _ if key == "until" => {
+                    json!(date_time(value)
+                        .or_else(|| date(value))
+                        .unwrap_or_else(|| value.into()))
+                }
+                _ => json!(value),
+            })
+            .collect::<Vec<_>>();
+        let value = match values.len() {
+            1 => values.remove(0),
+            _ => Value::Array(values),
+        };
+        rule.insert(key, value);
+    }
+    Value::Object(rule)
+}
+
+fn to_json(value_type: ValueType, value: &str) -> Value {
+    let converted = match value_type {
+        ValueType::Boolean => return json!(value.eq_ignore_ascii_case("TRUE")),
+        ValueType::Integer => {
+            return value
+                .parse::<i64>()
+                .map_or_else(|_| json!(value), |n| json!(n))
+        }
+        ValueType::Float => return float(value),
+        ValueType::Date => date(value),
+        ValueType::DateTime => date_time(value),
+        ValueType::Time => time(value),
+        ValueType::UtcOffset => utc_offset(value),
+        ValueType::Period => value
+            .split_once('/')
+            .map(|(start, end)| format!("{}/{}", period_part(start), period_part(end))),
+        _ => None,
+    };
+    json!(converted.unwrap_or_else(|| value.to_owned()))
+}
+
+/// Reads the jCal of a single `vcalendar`.
+pub fn from_jcal(jcal: &Value) -> Result<Calendar<'static>, String> {
+    let (name, properties, components) = parts(jcal)?;
+    if !name.eq_ignore_ascii_case("vcalendar") {
+        return Err(format!("expected a vcalendar, found {}", name));
+    }
+    Ok(Calendar {
+        properties,
+        components,
+    })
+}
+
+type Parts = (String, Vec<Property<'static>>, Vec<Component<'static>>);
+
+fn parts(jcal: &Value) -> Result<Parts, String> {
+    match jcal.as_array().map(Vec::as_slice) {
+        Some([Value::String(name), Value::Array(properties), Value::Array(components)]) => Ok((
+            name.clone(),
+            properties
+                .iter()
+                .map(jcal_property)
+                .collect::<Result<_, _>>()?,
+            components
+                .iter()
+                .map(jcal_component)
+                .collect::<Result<_, _>>()?,
+        )),
+        _ => Err(format!(
+            "expected a component like [name, [properties], [components]], found {}",
+            jcal
+        )),
+    }
+}
+
+fn jcal_component(jcal: &Value) -> Result<Component<'static>, String> {
+    let (name, properties, components) = parts(jcal)?;
+    Ok(Component {
+        name: name.to_uppercase().into(),
+        properties,
+        components,
+    })
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// [RFC 5545, Section 3.3.11](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.11)
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+fn jcal_property(jcal: &Value) -> Result<Property<'static>, String> {
+    let (name, params, value_type, values) = match jcal.as_array().map(Vec::as_slice) {
+        Some(
+            [Value::String(name), Value::Object(params), Value::String(value_type), values @ ..],
+        ) => (
+            name.to_uppercase(),
+            params,
+            value_type.to_uppercase(),
+            values,
+        ),
+        _ => {
+            return Err(format!(
+                "expected a property like [name, {{parameters}}, type, values...], found {}",
+                jcal
+            ))
+        }
+    };
+
+    let mut parameters = params
+        .iter()
+        .map(|(key, value)| Parameter {
+            key: key.to_uppercase().into(),
+            values: match value {
+                Value::Array(values) => values.iter().map(|v| text(v).into()).collect(),
+                value => vec![text(value).into()],
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let value_type = match value_type.as_str() {
+        "UNKNOWN" => None,
+        known => Some(
+            ValueType::from_str(known)
+                .map_err(|()| format!("unknown value type {} of {}", known, name))?,
+        ),
+    };
+    if value_type.is_some() && value_type != ValueType::by_name(&name) {
+        if let Some(value_type) = value_type {
+            parameters.push(Parameter {
+                key: "VALUE".into(),
+                values: vec![value_type.to_string().into()],
+            });
+        }
+    }
+
+    let val = match (value_type, values) {
+        (_, [Value::Array(parts)]) if STRUCTURED.contains(&name.as_str()) => {
+            parts.iter().map(text).collect::<Vec<_>>().join(";")
+        }
+        // the parser keeps list values escaped and unescapes everything else
+        (Some(ValueType::Text), values) if icalendar::Property::new(&name, "").is_list() => values
+            .iter()
+            .map(|value| escape_text(&text(value)))
+            .collect::<Vec<_>>()
+            .join(","),
+        (Some(value_type), values) => values
+            .iter()
+            .map(|value| from_json(value_type, value))
+            .collect::<Vec<_>>()
+            .join(","),
+        (None, values) => values.iter().map(text).collect::<Vec<_>>().join(","),
+    };
+
+    Ok(Property {
+        name: name.into(),
+        val: val.into(),
+        params: parameters,
+    })
+}
+
+fn from_json(value_type: ValueType, value: &Value) -> String {
+    match (value_type, value) {
+        (ValueType::Boolean, Value::Bool(boolean)) => boolean.to_string().to_uppercase(),
+        // `FREQ` goes first, some clients insist on it
+        (ValueType::Recur, Value::Object(rule)) => rule
+            .iter()
+            .filter(|(key, _)| key.as_str() == "freq")
+            .chain(rule.iter().filter(|(key, _)| key.as_str() != "freq"))
+            .map(|(key, value)| {
+                let values = match value {
+                    Value::Array(values) => values.iter().map(text).collect(),
+                    value => vec![text(value)],
+                };
+                let values = values
+                    .iter()
+                    .map(|value| match key.as_str() {
+                        "until" => value.replace(['-', ':'], ""),
+                        _ => value.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                format!("{}={}", key.to_uppercase(), values.join(","))
+            })
+            .collect::<Vec<_>>()
+            .join(";"),
+        (ValueType::Date | ValueType::DateTime | ValueType::Time | ValueType::UtcOffset, value) => {
+            text(value).replace(['-', ':'], "")
+        }
+        (ValueType::Period, value) => text(value)
+            .split('/')
+            .map(
+                |part| match part.starts_with(|c: char| c.is_ascii_digit()) {
+                    true => part.replace(['-', ':'], ""),
+                    false => part.to_owned(),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join("/"),
+        (_, value) => text(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use icalendar::parser::read_calendar_folded;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//example//ical//EN\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTAMP:20240101T080000Z\r
+DTSTART;TZID=Europe/Berlin:20240102T093000\r
+DURATION:PT15M\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20240301T000000Z\r
+SUMMARY:Standup\\, daily\r
+CATEGORIES:work,team\\, core\r
+GEO:52.52;13.405\r
+X-CUSTOM;X-PARAM=a:raw value\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT5M\r
+DESCRIPTION:Standup\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:report@example.com\r
+DTSTAMP:20240101T080000Z\r
+DUE;VALUE=DATE:20240105\r
+PRIORITY:1\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn typed_values() {
+        let jcal = to_jcal(&read_calendar_folded(CALENDAR).unwrap());
+        let event = &jcal[2][0];
+        assert_eq!(event[0], "vevent");
+        let properties = event[1].as_array().unwrap();
+        let find = |name: &str| properties.iter().find(|p| p[0] == name).unwrap().clone();
+
+        assert_eq!(
+            find("dtstart"),
+            json!(["dtstart", {"tzid": "Europe/Berlin"}, "date-time", "2024-01-02T09:30:00"])
+        );
+        assert_eq!(
+            find("rrule"),
+            json!(["rrule", {}, "recur", {"freq": "WEEKLY", "byday": ["MO", "WE"], "until": "2024-03-01T00:00:00Z"}])
+        );
+        assert_eq!(
+            find("summary"),
+            json!(["summary", {}, "text", "Standup, daily"])
+        );
+        assert_eq!(
+            find("categories"),
+            json!(["categories", {}, "text", "work", "team, core"])
+        );
+        assert_eq!(find("geo"), json!(["geo", {}, "float", [52.52, 13.405]]));
+        assert_eq!(
+            find("x-custom"),
+            json!(["x-custom", {"x-param": "a"}, "unknown", "raw value"])
+        );
+        assert_eq!(
+            jcal[2][1][1],
+            json!([
+                ["uid", {}, "text", "report@example.com"],
+                ["dtstamp", {}, "date-time", "2024-01-01T08:00:00Z"],
+                ["due", {}, "date", "2024-01-05"],
+                ["priority", {}, "integer", 1],
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let calendar = read_calendar_folded(CALENDAR).unwrap();
+        let jcal = to_jcal(&calendar);
+        let back = from_jcal(&jcal).unwrap();
+        assert_eq!(back.to_string(), CALENDAR);
+        assert_eq!(to_jcal(&back), jcal);
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(utc_offset("+0100").as_deref(), Some("+01:00"));
+        assert_eq!(utc_offset("-013015").as_deref(), Some("-01:30:15"));
+        assert_eq!(utc_offset("0100"), None);
+        assert_eq!(
+            to_json(ValueType::Period, "20240101T090000Z/PT1H"),
+            json!("2024-01-01T09:00:00Z/PT1H")
+        );
+        assert_eq!(
+            from_json(ValueType::Period, &json!("2024-01-01T09:00:00Z/PT1H")),
+            "20240101T090000Z/PT1H"
+        );
+        assert_eq!(to_json(ValueType::Boolean, "TRUE"), json!(true));
+        assert_eq!(from_json(ValueType::Boolean, &json!(false)), "FALSE");
+        assert_eq!(to_json(ValueType::DateTime, "garbage"), json!("garbage"));
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(from_jcal(&json!(["vevent", [], []])).is_err());
+        assert!(from_jcal(&json!({"vcalendar": []})).is_err());
+        assert!(from_jcal(&json!(["vcalendar", [["version", {}]], []])).is_err());
+        assert!(from_jcal(&json!([
+            "vcalendar",
+            [["x-a", {}, "no-such-type", "1"]],
+            []
+        ]))
+        .is_err());
+    }
+}
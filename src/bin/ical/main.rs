@@ -0,0 +1,113 @@
+//! `ical`, a command line tool for triaging iCalendar files
+//!
+//! Built with the `cli` feature, see `ical help` for the subcommands.
+//! Files are read from standard input when none or `-` are given.
+
+mod args;
+mod commands;
+mod jcal;
+mod validate;
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    process,
+};
+
+use args::Args;
+
+const USAGE: &str = "\
+ical - validate, reformat, convert and compare iCalendar files
+
+USAGE:
+    ical <COMMAND> [OPTIONS] [FILE...]
+
+Files are read from standard input when none or `-` are given.
+
+COMMANDS:
+    validate [FILE...]
+        Reports syntax errors with their line numbers and structural problems
+    format [--canonical] [--lf] [--no-fold] [--pretty] [FILE]
+        Reformats a calendar, --pretty prints an indented outline instead
+    convert --to <ics|json|jcal> [--from <ics|json|jcal>] [FILE]
+        Converts between iCalendar, the JSON of the parser and jCal (RFC 7265)
+    expand --from <DATE> --to <DATE> [FILE]
+        Lists the occurrences within a time range, one per line
+    filter [--text <TEXT>] [--from <DATE>] [--to <DATE>] [FILE]
+        Keeps the components that contain a text or occur within a time range
+    diff [--ignore-volatile] [--ignore <PROPERTY>...] <OLD> <NEW>
+        Shows what changed between two versions of a calendar
+    merge [FILE...]
+        Merges exports of the same calendar, keeping the latest revisions
+
+Dates are given like 2024-01-31 or 2024-01-31T09:00:00 and are taken as UTC.
+
+EXIT STATUS:
+    0  success
+    1  a file is invalid or the calendars differ
+    2  usage or input errors
+";
+
+/// A file is invalid or the calendars differ
+const EXIT_FAILED: i32 = 1;
+/// The command line or the input could not be used
+const EXIT_ERROR: i32 = 2;
+
+fn main() {
+    let code = match run(std::env::args().skip(1)) {
+        Ok(true) => 0,
+        Ok(false) => EXIT_FAILED,
+        Err(error) => {
+            eprintln!("ical: {}", error);
+            EXIT_ERROR
+        }
+    };
+    process::exit(code);
+}
+
+/// Runs a subcommand, `Ok(false)` if it found invalid or differing files.
+fn run(mut raw: impl Iterator<Item = String>) -> Result<bool, String> {
+    let command = raw.next().unwrap_or_default();
+    let args = Args::parse(raw)?;
+    match command.as_str() {
+        "validate" => commands::validate(args),
+        "format" => commands::format(args),
+        "convert" => commands::convert(args),
+        "expand" => commands::expand(args),
+        "filter" => commands::filter(args),
+        "diff" => commands::diff(args),
+        "merge" => commands::merge(args),
+        "help" | "--help" | "-h" => output(USAGE).map(|_| true),
+        "--version" | "-V" => {
+            output(&format!("ical {}\n", env!("CARGO_PKG_VERSION"))).map(|_| true)
+        }
+        "" => Err(format!("missing command\n\n{}", USAGE)),
+        unknown => Err(format!("unknown command {}, see `ical help`", unknown)),
+    }
+}
+
+/// Reads a file, or standard input for `-`.
+///
+/// Invalid UTF-8 is replaced rather than rejected, customer files are not always clean.
+fn read(path: &str) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    let read = if path == "-" {
+        io::stdin().read_to_end(&mut bytes).map(|_| ())
+    } else {
+        fs::read(path).map(|content| bytes = content)
+    };
+    read.map_err(|e| format!("{}: {}", path, e))?;
+
+    String::from_utf8(bytes).or_else(|e| {
+        eprintln!("ical: {}: invalid UTF-8, replaced undecodable bytes", path);
+        Ok(String::from_utf8_lossy(e.as_bytes()).into_owned())
+    })
+}
+
+/// Writes to standard output, a closed pipe is not an error.
+fn output(text: &str) -> Result<(), String> {
+    match io::stdout().lock().write_all(text.as_bytes()) {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,307 @@
+//! Checks beyond the syntax, for what calendar applications commonly choke on
+
+use std::{cmp::Ordering, collections::BTreeSet, fmt};
+
+use icalendar::{
+    parser::{Component, Property},
+    CalendarDateTime, DatePerhapsTime, RecurrenceRule,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in a calendar
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub severity: Severity,
+    /// Path to the component, like `VEVENT standup@example.com > VALARM`
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}", severity, self.location, self.message)
+    }
+}
+
+/// Components that require a `UID` and a `DTSTAMP`
+const SCHEDULED: [&str; 4] = ["VEVENT", "VTODO", "VJOURNAL", "VFREEBUSY"];
+
+/// Properties that must not occur more than once in a component
+const SINGLE: [&str; 15] = [
+    "UID",
+    "DTSTAMP",
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "DURATION",
+    "RECURRENCE-ID",
+    "SUMMARY",
+    "LOCATION",
+    "STATUS",
+    "CLASS",
+    "SEQUENCE",
+    "PRIORITY",
+    "ORGANIZER",
+    "URL",
+];
+
+/// Properties whose value is a date or date-time
+const DATES: [&str; 8] = [
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "RECURRENCE-ID",
+    "DTSTAMP",
+    "CREATED",
+    "LAST-MODIFIED",
+    "COMPLETED",
+];
+
+#[derive(Default)]
+struct Checker {
+    problems: Vec<Problem>,
+    tzids_used: BTreeSet<String>,
+    tzids_defined: BTreeSet<String>,
+}
+
+impl Checker {
+    fn report(&mut self, severity: Severity, location: &str, message: String) {
+        self.problems.push(Problem {
+            severity,
+            location: location.to_owned(),
+            message,
+        });
+    }
+
+    fn component(&mut self, component: &Component<'_>, parent: Option<&str>) {
+        let name = component.name.as_str();
+        let mut location = match component.find_prop("UID") {
+            Some(uid) => format!("{} {}", name, uid.val.as_str()),
+            None => name.to_owned(),
+        };
+        if let Some(parent) = parent {
+            location = format!("{} > {}", parent, location);
+        }
+
+        if name == "VTIMEZONE" {
+            if let Some(tzid) = component.find_prop("TZID") {
+                self.tzids_defined.insert(tzid.val.as_str().to_owned());
+            }
+        }
+        let required: &[&str] = match name {
+            _ if SCHEDULED.contains(&name) => &["UID", "DTSTAMP"],
+            "VALARM" => &["ACTION", "TRIGGER"],
+            "VTIMEZONE" => &["TZID"],
+            _ => &[],
+        };
+        for key in required {
+            if component.find_prop(key).is_none() {
+                self.report(Severity::Error, &location, format!("missing {}", key));
+            }
+        }
+
+        for key in SINGLE {
+            let count = component
+                .properties
+                .iter()
+                .filter(|p| p.name.as_str() == key)
+                .count();
+            if count > 1 && name != "VJOURNAL" {
+                let message = format!("{} occurs {} times", key, count);
+                self.report(Severity::Error, &location, message);
+            }
+        }
+
+        for property in &component.properties {
+            self.property(property, &location);
+        }
+        self.timing(component, &location);
+
+        for child in &component.components {
+            self.component(child, Some(&location));
+        }
+    }
+
+    fn property(&mut self, property: &Property<'_>, location: &str) {
+        let key = property.name.as_str();
+        if let Some(tzid) = property.params.iter().find(|p| p.key.as_str() == "TZID") {
+            if let Some(tzid) = tzid.value() {
+                self.tzids_used.insert(tzid.as_str().to_owned());
+            }
+        }
+        if DATES.contains(&key) && DatePerhapsTime::try_from(property).is_err() {
+            let message = format!("invalid {} value {:?}", key, property.val.as_str());
+            self.report(Severity::Error, location, message);
+        }
+        if key == "RRULE" && property.val.as_str().parse::<RecurrenceRule>().is_err() {
+            let message = format!("invalid RRULE {:?}", property.val.as_str());
+            self.report(Severity::Error, location, message);
+        }
+    }
+
+    /// The end of a component must not precede its start.
+    fn timing(&mut self, component: &Component<'_>, location: &str) {
+        let date = |key| {
+            component
+                .find_prop(key)
+                .and_then(|property| DatePerhapsTime::try_from(property).ok())
+        };
+        let (end_key, end) = match (date("DTEND"), date("DUE")) {
+            (Some(end), _) => ("DTEND", end),
+            (None, Some(due)) => ("DUE", due),
+            (None, None) => return,
+        };
+        if component.find_prop("DURATION").is_some() {
+            let message = format!("{} and DURATION must not occur together", end_key);
+            self.report(Severity::Error, location, message);
+        }
+        let start = match date("DTSTART") {
+            Some(start) => start,
+            None => return,
+        };
+        match compare(&start, &end) {
+            Some(Ordering::Greater) => {
+                let message = format!("{} is before DTSTART", end_key);
+                self.report(Severity::Error, location, message);
+            }
+            Some(_) => {}
+            None if matches!(start, DatePerhapsTime::Date(_))
+                != matches!(end, DatePerhapsTime::Date(_)) =>
+            {
+                let message = format!("DTSTART and {} are not both dates or date-times", end_key);
+                self.report(Severity::Error, location, message);
+            }
+            None => {}
+        }
+    }
+}
+
+/// Compares dates with dates, and date-times in UTC or without time zone with each other.
+fn compare(a: &DatePerhapsTime, b: &DatePerhapsTime) -> Option<Ordering> {
+    use DatePerhapsTime::*;
+    match (a, b) {
+        (Date(a), Date(b)) => Some(a.cmp(b)),
+        (DateTime(CalendarDateTime::Floating(a)), DateTime(CalendarDateTime::Floating(b))) => {
+            Some(a.cmp(b))
+        }
+        (DateTime(a), DateTime(b)) => Some(a.try_into_utc()?.cmp(&b.try_into_utc()?)),
+        _ => None,
+    }
+}
+
+/// Checks the root components of a document.
+pub fn check(roots: &[Component<'_>]) -> Vec<Problem> {
+    let mut checker = Checker::default();
+    let calendar = match roots {
+        [calendar] if calendar.name.as_str() == "VCALENDAR" => calendar,
+        _ => {
+            let names = roots.iter().map(|root| root.name.as_str());
+            let message = format!(
+                "expected a single VCALENDAR, found [{}]",
+                names.collect::<Vec<_>>().join(", ")
+            );
+            checker.report(Severity::Error, "document", message);
+            for root in roots {
+                checker.component(root, None);
+            }
+            return checker.problems;
+        }
+    };
+
+    for key in ["VERSION", "PRODID"] {
+        if calendar.find_prop(key).is_none() {
+            checker.report(Severity::Error, "VCALENDAR", format!("missing {}", key));
+        }
+    }
+    if let Some(version) = calendar.find_prop("VERSION") {
+        if version.val.as_str() != "2.0" {
+            let message = format!("VERSION is {}, not 2.0", version.val.as_str());
+            checker.report(Severity::Warning, "VCALENDAR", message);
+        }
+    }
+    for property in &calendar.properties {
+        checker.property(property, "VCALENDAR");
+    }
+    for component in &calendar.components {
+        checker.component(component, None);
+    }
+
+    let undefined = checker
+        .tzids_used
+        .difference(&checker.tzids_defined)
+        .cloned()
+        .collect::<Vec<_>>();
+    for tzid in undefined {
+        let message = format!("TZID {} is not defined by a VTIMEZONE", tzid);
+        checker.report(Severity::Warning, "VCALENDAR", message);
+    }
+    checker.problems
+}
+
+#[cfg(test)]
+mod tests {
+    use icalendar::parser::read_components_folded;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn problems(input: &str) -> Vec<String> {
+        check(&read_components_folded(input).unwrap())
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn valid() {
+        let input = "BEGIN:VCALENDAR\nVERSION:2.0\nPRODID:test\n\
+                     BEGIN:VTIMEZONE\nTZID:Europe/Berlin\nEND:VTIMEZONE\n\
+                     BEGIN:VEVENT\nUID:1\nDTSTAMP:20240101T000000Z\n\
+                     DTSTART;TZID=Europe/Berlin:20240101T100000\nDTEND:20240101T093000Z\n\
+                     RRULE:FREQ=DAILY;COUNT=3\nEND:VEVENT\nEND:VCALENDAR\n";
+        assert_eq!(problems(input), Vec::<String>::new());
+    }
+
+    #[test]
+    fn invalid() {
+        let input = "BEGIN:VCALENDAR\nVERSION:1.0\n\
+                     BEGIN:VEVENT\nUID:1\nDTSTART;TZID=Europe/Berlin:20240101T100000\n\
+                     DTEND:20240101T083000Z\nDURATION:PT1H\nRRULE:FREQ=SOMETIMES\nEND:VEVENT\n\
+                     BEGIN:VTODO\nUID:2\nUID:3\nDTSTAMP:2024\nDTSTART;VALUE=DATE:20240102\n\
+                     DUE:20240103T000000Z\nBEGIN:VALARM\nACTION:DISPLAY\nEND:VALARM\nEND:VTODO\n\
+                     END:VCALENDAR\n";
+        assert_eq!(
+            problems(input),
+            vec![
+                "error: VCALENDAR: missing PRODID",
+                "warning: VCALENDAR: VERSION is 1.0, not 2.0",
+                "error: VEVENT 1: missing DTSTAMP",
+                "error: VEVENT 1: invalid RRULE \"FREQ=SOMETIMES\"",
+                "error: VEVENT 1: DTEND and DURATION must not occur together",
+                "error: VEVENT 1: DTEND is before DTSTART",
+                "error: VTODO 2: UID occurs 2 times",
+                "error: VTODO 2: invalid DTSTAMP value \"2024\"",
+                "error: VTODO 2: DTSTART and DUE are not both dates or date-times",
+                "error: VTODO 2 > VALARM: missing TRIGGER",
+                "warning: VCALENDAR: TZID Europe/Berlin is not defined by a VTIMEZONE",
+            ]
+        );
+    }
+
+    #[test]
+    fn without_calendar() {
+        assert_eq!(
+            problems("BEGIN:VEVENT\nUID:1\nDTSTAMP:20240101T000000Z\nEND:VEVENT\n"),
+            vec!["error: document: expected a single VCALENDAR, found [VEVENT]"]
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Building blocks for [CalDAV](https://datatracker.ietf.org/doc/html/rfc4791) servers and clients
+//!
+//! Requires the `caldav` feature, the [`Client`] also requires the `parser` feature.
+
+#[cfg(feature = "parser")]
+mod client;
+mod filter;
+#[cfg(feature = "parser")]
+mod multistatus;
+
+#[cfg(feature = "parser")]
+pub use client::{
+    CalendarCollection, CalendarObject, Client, ClientError, HttpRequest, HttpResponse,
+    HttpTransport, SyncChanges,
+};
+pub use filter::{Collation, CompFilter, Filter, ParamFilter, PropFilter, TextMatch, TimeRange};
+
+#[cfg(feature = "parser")]
+const DAV_NS: &str = "DAV:";
+const CALDAV_NS: &str = "urn:ietf:params:xml:ns:caldav";
+
+/// Escapes text for XML element content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,1026 @@
+//! A [CalDAV](https://datatracker.ietf.org/doc/html/rfc4791) client on top of a pluggable [`HttpTransport`]
+
+use std::{error, fmt};
+
+use super::{escape_xml, multistatus::Multistatus, Filter, CALDAV_NS, DAV_NS};
+use crate::{parser::read_calendar_folded, Calendar};
+
+/// An HTTP request made by the [`Client`], with an absolute URL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// Like `PROPFIND`, `REPORT`, `PUT` or `DELETE`
+    pub method: String,
+    /// Absolute URL of the resource
+    pub url: String,
+    /// Header names and values, like `Depth` or `If-Match`
+    pub headers: Vec<(String, String)>,
+    /// XML for `PROPFIND` and `REPORT`, iCalendar for `PUT`, empty otherwise
+    pub body: String,
+}
+
+impl HttpRequest {
+    fn new(method: &str, url: String) -> Self {
+        HttpRequest {
+            method: method.to_owned(),
+            url,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    fn xml(self, body: String) -> Self {
+        HttpRequest { body, ..self }.header("Content-Type", "application/xml; charset=utf-8")
+    }
+
+    /// Gets a header value, ignoring the case of its name.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// The response to an [`HttpRequest`], whatever its status
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// Status code, like 207 for a multistatus
+    pub status: u16,
+    /// Header names and values, like `ETag`
+    pub headers: Vec<(String, String)>,
+    /// The response body
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Creates a response without headers.
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Adds a header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Gets a header value, ignoring the case of its name.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'h>(headers: &'h [(String, String)], name: &str) -> Option<&'h str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Sends the requests of a [`Client`]
+///
+/// Implement this on top of the HTTP library of your choice,
+/// it is also responsible for authentication and following redirects.
+/// Closures taking an [`HttpRequest`] implement this trait.
+pub trait HttpTransport {
+    /// Sends a request, an error means that no response was received at all.
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, String>;
+}
+
+impl<F> HttpTransport for F
+where
+    F: Fn(&HttpRequest) -> Result<HttpResponse, String>,
+{
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, String> {
+        self(request)
+    }
+}
+
+/// Why a [`Client`] request failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientError {
+    /// The [`HttpTransport`] did not get a response
+    Transport(String),
+    /// The server answered with an unexpected status,
+    /// `412 Precondition Failed` if the `ETag` given to [`Client::put()`] or [`Client::delete()`] is outdated
+    Status {
+        /// Method of the request
+        method: String,
+        /// URL of the request
+        url: String,
+        /// Status code of the response
+        status: u16,
+    },
+    /// The response could not be understood
+    InvalidResponse(String),
+}
+
+impl ClientError {
+    /// Whether the resource was changed by someone else since its `ETag` was read.
+    pub fn is_precondition_failed(&self) -> bool {
+        matches!(self, ClientError::Status { status: 412, .. })
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Transport(error) => write!(f, "transport error: {}", error),
+            ClientError::Status {
+                method,
+                url,
+                status,
+            } => write!(f, "{} {} failed with status {}", method, url, status),
+            ClientError::InvalidResponse(error) => write!(f, "invalid response: {}", error),
+        }
+    }
+}
+
+impl error::Error for ClientError {}
+
+/// A calendar collection found by [`Client::calendars()`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CalendarCollection {
+    /// Path or URL of the collection as the server reported it
+    pub href: String,
+    /// `DAV:displayname`
+    pub display_name: Option<String>,
+    /// `CALDAV:calendar-description`
+    pub description: Option<String>,
+    /// Apple's `calendar-color`, like `#FF0000`
+    pub color: Option<String>,
+    /// The `getctag` of Apple's calendar server, which changes whenever the collection does
+    pub ctag: Option<String>,
+    /// `DAV:sync-token` for [`Client::sync()`]
+    pub sync_token: Option<String>,
+    /// Component kinds the collection accepts, like `VEVENT`; empty if the server did not say
+    pub components: Vec<String>,
+}
+
+/// A calendar object resource, one `.ics` file on the server
+#[derive(Debug, PartialEq, Eq)]
+pub struct CalendarObject {
+    /// Path or URL of the resource as the server reported it
+    pub href: String,
+    /// `ETag` to pass to [`Client::put()`] and [`Client::delete()`]
+    pub etag: Option<String>,
+    /// The parsed `CALDAV:calendar-data`
+    pub calendar: Calendar,
+}
+
+/// Changes of a collection since a sync-token, see [`Client::sync()`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct SyncChanges {
+    /// Resources that were added or changed
+    pub changed: Vec<CalendarObject>,
+    /// Hrefs of resources that were removed
+    pub deleted: Vec<String>,
+    /// Token to pass to the next sync
+    pub sync_token: String,
+    /// The server sent only part of the changes, syncing again with `sync_token` fetches the rest,
+    /// see [RFC 6578, Section 3.6](https://datatracker.ietf.org/doc/html/rfc6578#section-3.6)
+    pub truncated: bool,
+}
+
+const CALENDAR_DATA_PROPS: &str = "<D:prop><D:getetag/><C:calendar-data/></D:prop>";
+
+fn xml_document(root: &str, content: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><{root} xmlns:D="{DAV_NS}" xmlns:C="{CALDAV_NS}">{content}</{root}>"#,
+    )
+}
+
+/// A [CalDAV](https://datatracker.ietf.org/doc/html/rfc4791) client
+///
+/// Calendars are found through the
+/// [`current-user-principal`](https://datatracker.ietf.org/doc/html/rfc5397) of the base URL
+/// and its [`calendar-home-set`](https://datatracker.ietf.org/doc/html/rfc4791#section-6.2.1).
+/// Calendar data is parsed into owned [`Calendar`]s.
+/// Hrefs may be paths, which are resolved against the base URL.
+///
+/// ```
+/// # use icalendar::caldav::*;
+/// let transport = |request: &HttpRequest| {
+///     assert_eq!(request.method, "DELETE");
+///     assert_eq!(request.url, "https://dav.example.com/calendars/alice/work/lunch.ics");
+///     assert_eq!(request.get_header("If-Match"), Some("\"2\""));
+///     Ok(HttpResponse::new(412, ""))
+/// };
+/// let client = Client::new(transport, "https://dav.example.com/dav/");
+///
+/// let error = client
+///     .delete("/calendars/alice/work/lunch.ics", Some("\"2\""))
+///     .unwrap_err();
+/// assert!(error.is_precondition_failed());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Client<T> {
+    transport: T,
+    base_url: String,
+}
+
+impl<T: HttpTransport> Client<T> {
+    /// Creates a client for the server at `base_url`, like `https://dav.example.com/`.
+    pub fn new(transport: T, base_url: &str) -> Self {
+        Client {
+            transport,
+            base_url: base_url.to_owned(),
+        }
+    }
+
+    /// The transport requests are sent with.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Resolves an href against the base URL.
+    pub fn url(&self, href: &str) -> String {
+        if href.starts_with("http://") || href.starts_with("https://") {
+            return href.to_owned();
+        }
+        let authority = self.base_url.find("://").map_or(0, |scheme| scheme + 3);
+        let path = self
+            .base_url
+            .get(authority..)
+            .and_then(|rest| rest.find('/'))
+            .map_or(self.base_url.len(), |slash| authority + slash);
+        if href.starts_with('/') {
+            return format!("{}{}", self.base_url.get(..path).unwrap_or_default(), href);
+        }
+        // relative to the directory of the base URL
+        let directory = self
+            .base_url
+            .rfind('/')
+            .filter(|slash| *slash >= path)
+            .map_or(self.base_url.len(), |slash| slash + 1);
+        let base = self.base_url.get(..directory).unwrap_or_default();
+        match base.ends_with('/') {
+            true => format!("{}{}", base, href),
+            false => format!("{}/{}", base, href),
+        }
+    }
+
+    fn send(&self, request: HttpRequest, expected: &[u16]) -> Result<HttpResponse, ClientError> {
+        let response = self
+            .transport
+            .send(&request)
+            .map_err(ClientError::Transport)?;
+        if expected.contains(&response.status) {
+            Ok(response)
+        } else {
+            Err(ClientError::Status {
+                method: request.method,
+                url: request.url,
+                status: response.status,
+            })
+        }
+    }
+
+    fn multistatus(&self, request: HttpRequest) -> Result<Multistatus, ClientError> {
+        let response = self.send(request, &[207])?;
+        response.body.parse().map_err(ClientError::InvalidResponse)
+    }
+
+    fn propfind(&self, href: &str, depth: u8, props: &str) -> Result<Multistatus, ClientError> {
+        let body = xml_document("D:propfind", &format!("<D:prop>{}</D:prop>", props));
+        self.multistatus(
+            HttpRequest::new("PROPFIND", self.url(href))
+                .header("Depth", &depth.to_string())
+                .xml(body),
+        )
+    }
+
+    /// Finds the principal URL of the authenticated user.
+    pub fn current_user_principal(&self) -> Result<String, ClientError> {
+        self.propfind(&self.base_url, 0, "<D:current-user-principal/>")?
+            .responses
+            .into_iter()
+            .find_map(|response| response.props.current_user_principal)
+            .ok_or_else(|| ClientError::InvalidResponse("no current-user-principal".into()))
+    }
+
+    /// Finds the collection containing the calendars of a principal.
+    pub fn calendar_home_set(&self, principal: &str) -> Result<String, ClientError> {
+        self.propfind(principal, 0, "<C:calendar-home-set/>")?
+            .responses
+            .into_iter()
+            .find_map(|response| response.props.calendar_home_set)
+            .ok_or_else(|| ClientError::InvalidResponse("no calendar-home-set".into()))
+    }
+
+    /// Finds the calendar home of the authenticated user through its principal.
+    pub fn discover(&self) -> Result<String, ClientError> {
+        let principal = self.current_user_principal()?;
+        self.calendar_home_set(&principal)
+    }
+
+    /// Lists the calendar collections in a calendar home.
+    pub fn calendars(&self, home: &str) -> Result<Vec<CalendarCollection>, ClientError> {
+        let props = "<D:resourcetype/><D:displayname/><D:sync-token/>\
+                     <C:calendar-description/><C:supported-calendar-component-set/>\
+                     <CS:getctag xmlns:CS=\"http://calendarserver.org/ns/\"/>\
+                     <IC:calendar-color xmlns:IC=\"http://apple.com/ns/ical/\"/>";
+        Ok(self
+            .propfind(home, 1, props)?
+            .responses
+            .into_iter()
+            .filter(|response| response.props.is_calendar)
+            .map(|response| CalendarCollection {
+                href: response.href,
+                display_name: response.props.display_name,
+                description: response.props.description,
+                color: response.props.color,
+                ctag: response.props.ctag,
+                sync_token: response.props.sync_token,
+                components: response.props.components,
+            })
+            .collect())
+    }
+
+    /// Parses the calendar data of the responses, skipping those without.
+    fn objects(multistatus: Multistatus) -> Result<Vec<CalendarObject>, ClientError> {
+        multistatus
+            .responses
+            .into_iter()
+            .filter_map(|response| {
+                let data = response.props.calendar_data?;
+                Some(
+                    read_calendar_folded(&data)
+                        .map(|calendar| CalendarObject {
+                            calendar: calendar.into(),
+                            href: response.href.clone(),
+                            etag: response.props.etag,
+                        })
+                        .map_err(|error| {
+                            ClientError::InvalidResponse(format!("{}: {}", response.href, error))
+                        }),
+                )
+            })
+            .collect()
+    }
+
+    fn report(&self, collection: &str, body: String) -> Result<Multistatus, ClientError> {
+        self.multistatus(
+            HttpRequest::new("REPORT", self.url(collection))
+                .header("Depth", "1")
+                .xml(body),
+        )
+    }
+
+    /// Fetches the calendar objects of a collection that match a filter,
+    /// [RFC 4791, Section 7.8](https://datatracker.ietf.org/doc/html/rfc4791#section-7.8).
+    pub fn query(
+        &self,
+        collection: &str,
+        filter: &Filter,
+    ) -> Result<Vec<CalendarObject>, ClientError> {
+        let body = xml_document(
+            "C:calendar-query",
+            &format!("{}{}", CALENDAR_DATA_PROPS, filter),
+        );
+        Self::objects(self.report(collection, body)?)
+    }
+
+    /// Fetches calendar objects by their hrefs, those that do not exist are left out,
+    /// [RFC 4791, Section 7.9](https://datatracker.ietf.org/doc/html/rfc4791#section-7.9).
+    pub fn multiget<S: AsRef<str>>(
+        &self,
+        collection: &str,
+        hrefs: &[S],
+    ) -> Result<Vec<CalendarObject>, ClientError> {
+        if hrefs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let hrefs = hrefs
+            .iter()
+            .map(|href| format!("<D:href>{}</D:href>", escape_xml(href.as_ref())))
+            .collect::<String>();
+        let body = xml_document(
+            "C:calendar-multiget",
+            &format!("{}{}", CALENDAR_DATA_PROPS, hrefs),
+        );
+        Self::objects(self.report(collection, body)?)
+    }
+
+    /// Fetches what changed since a sync-token, everything without one,
+    /// [RFC 6578](https://datatracker.ietf.org/doc/html/rfc6578).
+    ///
+    /// Changed resources the server reports without their calendar data are fetched with [`Client::multiget()`].
+    /// Servers may limit the number of changes per response, check [`SyncChanges::truncated`].
+    pub fn sync(
+        &self,
+        collection: &str,
+        sync_token: Option<&str>,
+    ) -> Result<SyncChanges, ClientError> {
+        let body = xml_document(
+            "D:sync-collection",
+            &format!(
+                "<D:sync-token>{}</D:sync-token><D:sync-level>1</D:sync-level>{}",
+                escape_xml(sync_token.unwrap_or_default()),
+                CALENDAR_DATA_PROPS
+            ),
+        );
+        let multistatus =
+            self.multistatus(HttpRequest::new("REPORT", self.url(collection)).xml(body))?;
+        let sync_token = multistatus
+            .sync_token
+            .clone()
+            .ok_or_else(|| ClientError::InvalidResponse("no sync-token".into()))?;
+
+        // a truncated result is reported by a 507 response for the collection itself
+        let truncated = multistatus
+            .responses
+            .iter()
+            .any(|response| response.status == Some(507));
+        let collection_url = self.url(collection);
+        let is_collection = |href: &str| {
+            self.url(href).trim_end_matches('/') == collection_url.trim_end_matches('/')
+        };
+        let (deleted, present) = multistatus
+            .responses
+            .into_iter()
+            .filter(|response| response.status != Some(507) && !is_collection(&response.href))
+            .partition::<Vec<_>, _>(|response| response.status == Some(404));
+        let (with_data, without_data) = present
+            .into_iter()
+            .partition::<Vec<_>, _>(|response| response.props.calendar_data.is_some());
+
+        let missing = without_data
+            .into_iter()
+            .map(|response| response.href)
+            .collect::<Vec<_>>();
+        let mut changed = Self::objects(Multistatus {
+            responses: with_data,
+            sync_token: None,
+        })?;
+        changed.extend(self.multiget(collection, &missing)?);
+
+        Ok(SyncChanges {
+            changed,
+            deleted: deleted.into_iter().map(|response| response.href).collect(),
+            sync_token,
+            truncated,
+        })
+    }
+
+    /// Stores a calendar object and returns its new `ETag`, if the server sent one.
+    ///
+    /// With an `etag` the resource is only replaced if it still has that `ETag`,
+    /// without one it is only created if it does not exist yet.
+    /// Otherwise the server answers `412 Precondition Failed`, see [`ClientError::is_precondition_failed()`].
+    pub fn put(
+        &self,
+        href: &str,
+        calendar: &Calendar,
+        etag: Option<&str>,
+    ) -> Result<Option<String>, ClientError> {
+        let request = HttpRequest::new("PUT", self.url(href))
+            .header("Content-Type", "text/calendar; charset=utf-8");
+        let request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request.header("If-None-Match", "*"),
+        };
+        let request = HttpRequest {
+            body: calendar.to_string(),
+            ..request
+        };
+        let response = self.send(request, &[200, 201, 204])?;
+        Ok(response.get_header("ETag").map(str::to_owned))
+    }
+
+    /// Deletes a calendar object, only if it still has the `etag` if one is given.
+    pub fn delete(&self, href: &str, etag: Option<&str>) -> Result<(), ClientError> {
+        let request = HttpRequest::new("DELETE", self.url(href));
+        let request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request,
+        };
+        self.send(request, &[200, 204]).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    use chrono::{TimeZone, Utc};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{caldav::CompFilter, caldav::TimeRange, Component, Event, EventLike};
+
+    /// Members of the calendar home, only one of them is a calendar
+    const CALENDAR_HOME: &str = r#"
+          <d:response>
+            <d:href>/calendars/alice/</d:href>
+            <d:propstat>
+              <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+              <d:status>HTTP/1.1 200 OK</d:status>
+            </d:propstat>
+          </d:response>
+          <d:response>
+            <d:href>/calendars/alice/work/</d:href>
+            <d:propstat>
+              <d:prop>
+                <d:resourcetype><d:collection/><c:calendar/></d:resourcetype>
+                <d:displayname>Work</d:displayname>
+                <d:sync-token>v0</d:sync-token>
+              </d:prop>
+              <d:status>HTTP/1.1 200 OK</d:status>
+            </d:propstat>
+          </d:response>
+          <d:response>
+            <d:href>/calendars/alice/inbox/</d:href>
+            <d:propstat>
+              <d:prop><d:resourcetype><d:collection/><c:schedule-inbox/></d:resourcetype></d:prop>
+              <d:status>HTTP/1.1 200 OK</d:status>
+            </d:propstat>
+          </d:response>"#;
+
+    struct Resource {
+        etag: String,
+        ics: String,
+        version: u32,
+    }
+
+    /// An in-process server with one calendar at `/calendars/alice/work/`
+    #[derive(Default)]
+    struct MockServer {
+        resources: RefCell<BTreeMap<String, Resource>>,
+        deleted: RefCell<Vec<(String, u32)>>,
+        version: RefCell<u32>,
+        requests: RefCell<Vec<String>>,
+        /// Maximum number of changes per sync response
+        sync_limit: RefCell<Option<usize>>,
+    }
+
+    impl MockServer {
+        fn store(&self, href: &str, ics: String) -> String {
+            let version = *self.version.borrow() + 1;
+            *self.version.borrow_mut() = version;
+            let etag = format!("\"{}\"", version);
+            let resource = Resource {
+                etag: etag.clone(),
+                ics,
+                version,
+            };
+            self.resources
+                .borrow_mut()
+                .insert(href.to_owned(), resource);
+            etag
+        }
+
+        fn multistatus(responses: &str, sync_token: Option<u32>) -> HttpResponse {
+            let sync_token = sync_token
+                .map(|version| format!("<d:sync-token>v{}</d:sync-token>", version))
+                .unwrap_or_default();
+            HttpResponse::new(
+                207,
+                format!(
+                    r#"<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">{}{}</d:multistatus>"#,
+                    responses, sync_token
+                ),
+            )
+        }
+
+        fn object(href: &str, resource: &Resource, with_data: bool) -> String {
+            let data = match with_data {
+                true => format!(
+                    "<c:calendar-data>{}</c:calendar-data>",
+                    escape_xml(&resource.ics)
+                ),
+                false => String::new(),
+            };
+            format!(
+                "<d:response><d:href>{}</d:href><d:propstat><d:prop><d:getetag>{}</d:getetag>{}</d:prop>\
+                 <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+                href,
+                escape_xml(&resource.etag),
+                data
+            )
+        }
+
+        fn propfind(&self, path: &str) -> HttpResponse {
+            let prop = match path {
+                "/dav/" => "<d:current-user-principal><d:href>/principals/alice/</d:href></d:current-user-principal>",
+                "/principals/alice/" => "<c:calendar-home-set><d:href>/calendars/alice/</d:href></c:calendar-home-set>",
+                "/calendars/alice/" => return Self::multistatus(CALENDAR_HOME, None),
+                _ => return HttpResponse::new(404, ""),
+            };
+            Self::multistatus(
+                &format!(
+                    "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}</d:prop>\
+                     <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+                    path, prop
+                ),
+                None,
+            )
+        }
+
+        fn report(&self, body: &str) -> HttpResponse {
+            let document = roxmltree::Document::parse(body).unwrap();
+            let resources = self.resources.borrow();
+            match document.root_element().tag_name().name() {
+                "calendar-query" => {
+                    let filter = body.parse::<Filter>().unwrap();
+                    let responses = resources
+                        .iter()
+                        .filter(|(_, resource)| {
+                            filter.matches(&resource.ics.parse::<Calendar>().unwrap())
+                        })
+                        .map(|(href, resource)| Self::object(href, resource, true))
+                        .collect::<String>();
+                    Self::multistatus(&responses, None)
+                }
+                "calendar-multiget" => {
+                    let responses = document
+                        .descendants()
+                        .filter(|node| node.tag_name().name() == "href")
+                        .filter_map(|node| node.text())
+                        .map(|href| match resources.get(href) {
+                            Some(resource) => Self::object(href, resource, true),
+                            None => format!(
+                                "<d:response><d:href>{}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>",
+                                href
+                            ),
+                        })
+                        .collect::<String>();
+                    Self::multistatus(&responses, None)
+                }
+                "sync-collection" => {
+                    let token = document
+                        .descendants()
+                        .find(|node| node.tag_name().name() == "sync-token")
+                        .and_then(|node| node.text())
+                        .unwrap_or_default();
+                    let since = match token.strip_prefix('v') {
+                        Some(version) => version.parse().unwrap(),
+                        None => 0,
+                    };
+                    // changes since a token come without their data
+                    let changed = resources
+                        .iter()
+                        .filter(|(_, resource)| resource.version > since)
+                        .map(|(href, resource)| {
+                            (resource.version, Self::object(href, resource, since == 0))
+                        });
+                    let deleted = self
+                        .deleted
+                        .borrow()
+                        .iter()
+                        .filter(|(_, version)| *version > since)
+                        .map(|(href, version)| {
+                            (
+                                *version,
+                                format!(
+                                    "<d:response><d:href>{}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>",
+                                    href
+                                ),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let mut changes = changed.chain(deleted).collect::<Vec<_>>();
+                    changes.sort();
+                    let mut token = *self.version.borrow();
+                    let mut truncated = String::new();
+                    if let Some(limit) = *self.sync_limit.borrow() {
+                        if changes.len() > limit {
+                            changes.truncate(limit);
+                            token = changes.last().map_or(since, |(version, _)| *version);
+                            truncated = String::from(
+                                "<d:response><d:href>/calendars/alice/work/</d:href>\
+                                 <d:status>HTTP/1.1 507 Insufficient Storage</d:status></d:response>",
+                            );
+                        }
+                    }
+                    // some servers list the collection itself, with an absolute URL
+                    let collection = String::from(
+                        "<d:response><d:href>http://mock/calendars/alice/work/</d:href>\
+                         <d:propstat><d:prop><d:getetag/></d:prop>\
+                         <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+                    );
+                    let responses = changes
+                        .into_iter()
+                        .map(|(_, response)| response)
+                        .chain([collection, truncated])
+                        .collect::<String>();
+                    Self::multistatus(&responses, Some(token))
+                }
+                _ => HttpResponse::new(400, ""),
+            }
+        }
+
+        fn put(&self, path: &str, request: &HttpRequest) -> HttpResponse {
+            let current = self
+                .resources
+                .borrow()
+                .get(path)
+                .map(|resource| resource.etag.clone());
+            let precondition = match (
+                request.get_header("If-Match"),
+                request.get_header("If-None-Match"),
+            ) {
+                (Some(etag), _) => current.as_deref() == Some(etag),
+                (None, Some("*")) => current.is_none(),
+                _ => true,
+            };
+            if !precondition {
+                return HttpResponse::new(412, "");
+            }
+            let status = if current.is_some() { 204 } else { 201 };
+            let etag = self.store(path, request.body.clone());
+            HttpResponse::new(status, "").header("ETag", &etag)
+        }
+
+        fn delete(&self, path: &str, request: &HttpRequest) -> HttpResponse {
+            let mut resources = self.resources.borrow_mut();
+            let current = match resources.get(path) {
+                Some(resource) => &resource.etag,
+                None => return HttpResponse::new(404, ""),
+            };
+            if request
+                .get_header("If-Match")
+                .map_or(false, |etag| etag != current)
+            {
+                return HttpResponse::new(412, "");
+            }
+            resources.remove(path);
+            let version = *self.version.borrow() + 1;
+            *self.version.borrow_mut() = version;
+            self.deleted.borrow_mut().push((path.to_owned(), version));
+            HttpResponse::new(204, "")
+        }
+    }
+
+    impl HttpTransport for MockServer {
+        fn send(&self, request: &HttpRequest) -> Result<HttpResponse, String> {
+            self.requests
+                .borrow_mut()
+                .push(format!("{} {}", request.method, request.url));
+            let path = request
+                .url
+                .strip_prefix("http://mock")
+                .ok_or("unknown host")?;
+            Ok(match request.method.as_str() {
+                "PROPFIND" => self.propfind(path),
+                "REPORT" => self.report(&request.body),
+                "PUT" => self.put(path, request),
+                "DELETE" => self.delete(path, request),
+                _ => HttpResponse::new(405, ""),
+            })
+        }
+    }
+
+    fn calendar(uid: &str, summary: &str, day: u32) -> Calendar {
+        Calendar::new()
+            .push(
+                Event::new()
+                    .uid(uid)
+                    .summary(summary)
+                    .starts(Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap())
+                    .ends(Utc.with_ymd_and_hms(2024, 1, day, 13, 0, 0).unwrap())
+                    .done(),
+            )
+            .done()
+    }
+
+    fn client() -> Client<MockServer> {
+        let server = MockServer::default();
+        server.store(
+            "/calendars/alice/work/lunch.ics",
+            calendar("lunch", "Lunch", 1).to_string(),
+        );
+        server.store(
+            "/calendars/alice/work/review.ics",
+            calendar("review", "Review", 15).to_string(),
+        );
+        Client::new(server, "http://mock/dav/")
+    }
+
+    /// Hrefs and the uids and summaries of their events
+    fn summaries(objects: &[CalendarObject]) -> Vec<(&str, &str)> {
+        objects
+            .iter()
+            .flat_map(|object| {
+                object
+                    .calendar
+                    .components
+                    .iter()
+                    .filter_map(move |component| {
+                        Some((object.href.as_str(), component.property_value("SUMMARY")?))
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resolve_hrefs() {
+        let client = Client::new(
+            |_: &HttpRequest| Err(String::new()),
+            "https://dav.example.com/dav/",
+        );
+        assert_eq!(
+            client.url("https://other.example.com/x"),
+            "https://other.example.com/x"
+        );
+        assert_eq!(
+            client.url("/calendars/"),
+            "https://dav.example.com/calendars/"
+        );
+        assert_eq!(client.url("work/"), "https://dav.example.com/dav/work/");
+
+        let client = Client::new(
+            |_: &HttpRequest| Err(String::new()),
+            "https://dav.example.com",
+        );
+        assert_eq!(
+            client.url("/calendars/"),
+            "https://dav.example.com/calendars/"
+        );
+        assert_eq!(client.url("work/"), "https://dav.example.com/work/");
+        assert_eq!(
+            client.delete("work/a.ics", None),
+            Err(ClientError::Transport(String::new()))
+        );
+    }
+
+    #[test]
+    fn discover_calendars() {
+        let client = client();
+        let home = client.discover().unwrap();
+        assert_eq!(home, "/calendars/alice/");
+        assert_eq!(
+            client.calendars(&home).unwrap(),
+            vec![CalendarCollection {
+                href: String::from("/calendars/alice/work/"),
+                display_name: Some(String::from("Work")),
+                sync_token: Some(String::from("v0")),
+                ..CalendarCollection::default()
+            }]
+        );
+        assert_eq!(
+            *client.transport().requests.borrow(),
+            vec![
+                "PROPFIND http://mock/dav/",
+                "PROPFIND http://mock/principals/alice/",
+                "PROPFIND http://mock/calendars/alice/",
+            ]
+        );
+
+        let missing = client.calendar_home_set("/principals/bob/").unwrap_err();
+        assert!(matches!(missing, ClientError::Status { status: 404, .. }));
+    }
+
+    #[test]
+    fn query_and_multiget() {
+        let client = client();
+        let filter = Filter::new(CompFilter::new("VCALENDAR").comp_filter(
+            CompFilter::new("VEVENT").time_range(TimeRange::between(
+                Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 20, 0, 0, 0).unwrap(),
+            )),
+        ));
+        let found = client.query("/calendars/alice/work/", &filter).unwrap();
+        assert_eq!(
+            summaries(&found),
+            vec![("/calendars/alice/work/review.ics", "Review")]
+        );
+        assert_eq!(
+            found.first().and_then(|object| object.etag.as_deref()),
+            Some("\"2\"")
+        );
+
+        let fetched = client
+            .multiget(
+                "/calendars/alice/work/",
+                &[
+                    "/calendars/alice/work/lunch.ics",
+                    "/calendars/alice/work/gone.ics",
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            summaries(&fetched),
+            vec![("/calendars/alice/work/lunch.ics", "Lunch")]
+        );
+        assert!(client
+            .multiget::<&str>("/calendars/alice/work/", &[])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn sync() {
+        let client = client();
+        let collection = "/calendars/alice/work/";
+        let initial = client.sync(collection, None).unwrap();
+        assert_eq!(initial.changed.len(), 2);
+        assert_eq!(initial.sync_token, "v2");
+
+        client
+            .put(
+                "/calendars/alice/work/lunch.ics",
+                &calendar("lunch", "Long lunch", 1),
+                Some("\"1\""),
+            )
+            .unwrap();
+        client
+            .delete("/calendars/alice/work/review.ics", None)
+            .unwrap();
+        let requests = client.transport().requests.borrow().len();
+
+        let changes = client.sync(collection, Some(&initial.sync_token)).unwrap();
+        assert_eq!(
+            summaries(&changes.changed),
+            vec![("/calendars/alice/work/lunch.ics", "Long lunch")]
+        );
+        assert_eq!(changes.deleted, vec!["/calendars/alice/work/review.ics"]);
+        assert_eq!(changes.sync_token, "v4");
+        // the changed resource came without data and was fetched with a multiget
+        assert_eq!(client.transport().requests.borrow().len(), requests + 2);
+
+        let unchanged = client.sync(collection, Some(&changes.sync_token)).unwrap();
+        assert_eq!(
+            unchanged,
+            SyncChanges {
+                changed: Vec::new(),
+                deleted: Vec::new(),
+                sync_token: String::from("v4"),
+                truncated: false,
+            }
+        );
+    }
+
+    #[test]
+    fn truncated_sync() {
+        let client = client();
+        *client.transport().sync_limit.borrow_mut() = Some(1);
+        let collection = "/calendars/alice/work/";
+
+        let first = client.sync(collection, None).unwrap();
+        assert!(first.truncated);
+        assert_eq!(
+            summaries(&first.changed),
+            vec![("/calendars/alice/work/lunch.ics", "Lunch")]
+        );
+        assert_eq!(first.sync_token, "v1");
+        // the collection itself is not fetched as a changed resource
+        assert_eq!(client.transport().requests.borrow().len(), 1);
+
+        // the collection is recognized without its trailing slash as well
+        let rest = client
+            .sync("/calendars/alice/work", Some(&first.sync_token))
+            .unwrap();
+        assert!(!rest.truncated);
+        assert_eq!(
+            summaries(&rest.changed),
+            vec![("/calendars/alice/work/review.ics", "Review")]
+        );
+        assert_eq!(rest.sync_token, "v2");
+    }
+
+    #[test]
+    fn put_and_delete_with_etags() {
+        let client = client();
+        let href = "/calendars/alice/work/dinner.ics";
+        let dinner = calendar("dinner", "Dinner", 2);
+
+        let etag = client.put(href, &dinner, None).unwrap().unwrap();
+        assert_eq!(etag, "\"3\"");
+        // creating it again must not overwrite it
+        assert!(client
+            .put(href, &dinner, None)
+            .unwrap_err()
+            .is_precondition_failed());
+
+        let etag = client.put(href, &dinner, Some(&etag)).unwrap().unwrap();
+        assert_eq!(etag, "\"4\"");
+        let outdated = client.put(href, &dinner, Some("\"3\"")).unwrap_err();
+        assert_eq!(
+            outdated,
+            ClientError::Status {
+                method: String::from("PUT"),
+                url: String::from("http://mock/calendars/alice/work/dinner.ics"),
+                status: 412,
+            }
+        );
+        assert_eq!(
+            outdated.to_string(),
+            "PUT http://mock/calendars/alice/work/dinner.ics failed with status 412"
+        );
+
+        assert!(client
+            .delete(href, Some("\"3\""))
+            .unwrap_err()
+            .is_precondition_failed());
+        client.delete(href, Some(&etag)).unwrap();
+        let gone = client.delete(href, None).unwrap_err();
+        assert!(!gone.is_precondition_failed());
+    }
+}
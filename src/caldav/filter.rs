@@ -0,0 +1,958 @@
+//! `CALDAV:filter` of a calendar-query [(RFC 4791, Section 9.7)](https://datatracker.ietf.org/doc/html/rfc4791#section-9.7)
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+
+use super::{escape_xml, CALDAV_NS};
+use crate::{
+    components::{
+        date_time::{format_utc_date_time, parse_utc_date_time},
+        properties_by_key, recurrence, InnerComponent, Other,
+    },
+    Alarm, Calendar, CalendarComponent, Component, DatePerhapsTime, Event, EventLike, Property,
+};
+
+/// Collation used by a [`TextMatch`]
+///
+/// [RFC 4791, Section 7.5](https://datatracker.ietf.org/doc/html/rfc4791#section-7.5)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Collation {
+    /// `i;ascii-casemap`, ignores the case of ASCII letters
+    AsciiCasemap,
+    /// `i;octet`, compares bytes as they are
+    Octet,
+}
+
+impl Default for Collation {
+    fn default() -> Self {
+        Collation::AsciiCasemap
+    }
+}
+
+impl Collation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Collation::AsciiCasemap => "i;ascii-casemap",
+            Collation::Octet => "i;octet",
+        }
+    }
+}
+
+impl FromStr for Collation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "i;ascii-casemap" => Ok(Collation::AsciiCasemap),
+            "i;octet" => Ok(Collation::Octet),
+            _ => Err(()),
+        }
+    }
+}
+
+/// `CALDAV:text-match`, a substring search on a property or parameter value
+///
+/// [RFC 4791, Section 9.7.5](https://datatracker.ietf.org/doc/html/rfc4791#section-9.7.5)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextMatch {
+    text: String,
+    collation: Collation,
+    negate: bool,
+}
+
+impl TextMatch {
+    /// Matches values containing `text`, ignoring ASCII case.
+    pub fn new(text: &str) -> Self {
+        TextMatch {
+            text: text.to_owned(),
+            collation: Collation::default(),
+            negate: false,
+        }
+    }
+
+    /// Sets the [`Collation`] used to compare.
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Matches values not containing the text instead, `negate-condition="yes"`.
+    pub fn negate(mut self) -> Self {
+        self.negate = true;
+        self
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        let contains = match self.collation {
+            Collation::AsciiCasemap => value
+                .to_ascii_lowercase()
+                .contains(&self.text.to_ascii_lowercase()),
+            Collation::Octet => value.contains(&self.text),
+        };
+        contains != self.negate
+    }
+}
+
+/// `CALDAV:time-range`, at least one bound should be set
+///
+/// [RFC 4791, Section 9.9](https://datatracker.ietf.org/doc/html/rfc4791#section-9.9)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TimeRange {
+    /// Inclusive start, unlimited if `None`
+    pub start: Option<DateTime<Utc>>,
+    /// Exclusive end, unlimited if `None`
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    /// Creates a range from `start` to `end`.
+    pub fn between(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        TimeRange {
+            start: Some(start),
+            end: Some(end),
+        }
+    }
+
+    /// Creates a range from `start` on.
+    pub fn starting(start: DateTime<Utc>) -> Self {
+        TimeRange {
+            start: Some(start),
+            end: None,
+        }
+    }
+
+    /// Creates a range until `end`.
+    pub fn ending(end: DateTime<Utc>) -> Self {
+        TimeRange {
+            start: None,
+            end: Some(end),
+        }
+    }
+
+    fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start.map_or(true, |start| start <= at) && self.end.map_or(true, |end| at < end)
+    }
+}
+
+/// `CALDAV:param-filter`, matches a parameter of a property
+///
+/// [RFC 4791, Section 9.7.3](https://datatracker.ietf.org/doc/html/rfc4791#section-9.7.3)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParamFilter {
+    name: String,
+    is_not_defined: bool,
+    text_match: Option<TextMatch>,
+}
+
+impl ParamFilter {
+    /// Matches properties that have the parameter `name`.
+    pub fn new(name: &str) -> Self {
+        ParamFilter {
+            name: name.to_ascii_uppercase(),
+            is_not_defined: false,
+            text_match: None,
+        }
+    }
+
+    /// Matches properties that do not have the parameter instead.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Only matches parameters with a matching value.
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_match = Some(text_match);
+        self
+    }
+
+    fn matches(&self, property: &Property) -> bool {
+        match property.params().get(&self.name) {
+            None => self.is_not_defined,
+            Some(_) if self.is_not_defined => false,
+            Some(param) => self
+                .text_match
+                .as_ref()
+                .map_or(true, |text_match| text_match.matches(param.value())),
+        }
+    }
+}
+
+/// `CALDAV:prop-filter`, matches a property of a component
+///
+/// [RFC 4791, Section 9.7.2](https://datatracker.ietf.org/doc/html/rfc4791#section-9.7.2)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropFilter {
+    name: String,
+    is_not_defined: bool,
+    time_range: Option<TimeRange>,
+    text_match: Option<TextMatch>,
+    param_filters: Vec<ParamFilter>,
+}
+
+impl PropFilter {
+    /// Matches components that have the property `name`.
+    pub fn new(name: &str) -> Self {
+        PropFilter {
+            name: name.to_ascii_uppercase(),
+            is_not_defined: false,
+            time_range: None,
+            text_match: None,
+            param_filters: Vec::new(),
+        }
+    }
+
+    /// Matches components that do not have the property instead.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Only matches date or date-time properties within the range.
+    pub fn time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
+    /// Only matches properties with a matching value.
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_match = Some(text_match);
+        self
+    }
+
+    /// Only matches properties whose parameters match all param-filters.
+    pub fn param_filter(mut self, param_filter: ParamFilter) -> Self {
+        self.param_filters.push(param_filter);
+        self
+    }
+
+    fn matches_property(&self, property: &Property) -> bool {
+        self.time_range.as_ref().map_or(true, |time_range| {
+            DatePerhapsTime::from_property(property)
+                .as_ref()
+                .and_then(recurrence::instant)
+                .map_or(false, |at| time_range.contains(at))
+        }) && self
+            .text_match
+            .as_ref()
+            .map_or(true, |text_match| text_match.matches(property.value()))
+            && self
+                .param_filters
+                .iter()
+                .all(|param_filter| param_filter.matches(property))
+    }
+
+    fn matches<C: Component + ?Sized>(&self, component: &C) -> bool {
+        let mut properties = properties_by_key(component, &self.name);
+        if self.is_not_defined {
+            properties.next().is_none()
+        } else {
+            properties.any(|property| self.matches_property(property))
+        }
+    }
+}
+
+/// `CALDAV:comp-filter`, matches a component
+///
+/// [RFC 4791, Section 9.7.1](https://datatracker.ietf.org/doc/html/rfc4791#section-9.7.1)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompFilter {
+    name: String,
+    is_not_defined: bool,
+    time_range: Option<TimeRange>,
+    prop_filters: Vec<PropFilter>,
+    comp_filters: Vec<CompFilter>,
+}
+
+impl CompFilter {
+    /// Matches if a component of kind `name`, like `VEVENT`, exists.
+    pub fn new(name: &str) -> Self {
+        CompFilter {
+            name: name.to_ascii_uppercase(),
+            is_not_defined: false,
+            time_range: None,
+            prop_filters: Vec::new(),
+            comp_filters: Vec::new(),
+        }
+    }
+
+    /// Matches if no such component exists instead.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Only matches components overlapping the range, taking recurrences into account.
+    pub fn time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
+    /// Only matches components whose properties match all prop-filters.
+    pub fn prop_filter(mut self, prop_filter: PropFilter) -> Self {
+        self.prop_filters.push(prop_filter);
+        self
+    }
+
+    /// Only matches components whose children match all comp-filters.
+    pub fn comp_filter(mut self, comp_filter: CompFilter) -> Self {
+        self.comp_filters.push(comp_filter);
+        self
+    }
+
+    fn matches_component(&self, component: &Other, view: &Event, parent: Option<&Event>) -> bool {
+        self.time_range.as_ref().map_or(true, |time_range| {
+            overlaps(component, view, parent, time_range)
+        }) && self
+            .prop_filters
+            .iter()
+            .all(|prop_filter| prop_filter.matches(component))
+            && self
+                .comp_filters
+                .iter()
+                .all(|comp_filter| comp_filter.matches_children(component, view))
+    }
+
+    fn matches_children(&self, parent: &Other, parent_view: &Event) -> bool {
+        let mut children = parent
+            .components()
+            .iter()
+            .filter(|child| child.component_kind() == self.name);
+        if self.is_not_defined {
+            children.next().is_none()
+        } else {
+            children.any(|child| {
+                self.matches_component(child, &occurrence_view(child, parent), Some(parent_view))
+            })
+        }
+    }
+}
+
+fn event_view(component: &Other) -> Event {
+    Event::from(InnerComponent::from(component.clone()))
+}
+
+/// Like [`event_view`] but without the occurrences that siblings with the same `UID`
+/// replace through their `RECURRENCE-ID`, those are matched on their own.
+fn occurrence_view(component: &Other, parent: &Other) -> Event {
+    let mut view = event_view(component);
+    let uid = match component.get_uid() {
+        Some(uid) if !component.properties().contains_key("RECURRENCE-ID") => uid,
+        _ => return view,
+    };
+    for sibling in parent.components() {
+        if sibling.component_kind() != component.component_kind() || sibling.get_uid() != Some(uid)
+        {
+            continue;
+        }
+        if let Some(recurrence_id) = sibling
+            .properties()
+            .get("RECURRENCE-ID")
+            .and_then(DatePerhapsTime::from_property)
+        {
+            view.exdate(recurrence_id);
+        }
+    }
+    view
+}
+
+/// Whether a component overlaps a time range as described in
+/// [RFC 4791, Section 9.9](https://datatracker.ietf.org/doc/html/rfc4791#section-9.9).
+fn overlaps(
+    component: &Other,
+    view: &Event,
+    parent: Option<&Event>,
+    time_range: &TimeRange,
+) -> bool {
+    let TimeRange { start, end } = *time_range;
+
+    match component.component_kind().as_str() {
+        "VALARM" => match (parent, Alarm::try_from(component.clone())) {
+            (Some(parent), Ok(alarm)) => {
+                recurrence::alarm_instants(parent, &alarm, start, end, &Utc)
+                    .next()
+                    .is_some()
+            }
+            _ => false,
+        },
+        _ if view.get_start().is_some() => recurrence::overlaps(view, start, end),
+        "VTODO" => {
+            let date_time = |key: &str| {
+                DatePerhapsTime::from_property(view.properties().get(key)?)
+                    .as_ref()
+                    .and_then(recurrence::instant)
+            };
+            let after_start = |at: DateTime<Utc>| start.map_or(true, |start| start <= at);
+            let before_end = |at: DateTime<Utc>| end.map_or(true, |end| at <= end);
+            match (
+                date_time("DUE"),
+                date_time("COMPLETED"),
+                date_time("CREATED"),
+            ) {
+                (Some(due), _, _) => start.map_or(true, |start| start < due) && before_end(due),
+                (None, Some(completed), Some(created)) => {
+                    (after_start(created) || after_start(completed))
+                        && (before_end(created) || before_end(completed))
+                }
+                (None, Some(completed), None) => after_start(completed) && before_end(completed),
+                (None, None, Some(created)) => end.map_or(true, |end| end > created),
+                (None, None, None) => true,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// A `CALDAV:filter` as sent in a calendar-query `REPORT`
+///
+/// [RFC 4791, Section 9.7](https://datatracker.ietf.org/doc/html/rfc4791#section-9.7)
+///
+/// Filters can either be built
+///
+/// ```
+/// # use icalendar::{*, caldav::*};
+/// # use chrono::*;
+/// let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+/// let filter = Filter::new(
+///     CompFilter::new("VCALENDAR").comp_filter(
+///         CompFilter::new("VEVENT")
+///             .time_range(TimeRange::between(start, start + Duration::weeks(1)))
+///             .prop_filter(PropFilter::new("SUMMARY").text_match(TextMatch::new("standup"))),
+///     ),
+/// );
+///
+/// let standup = Event::new()
+///     .summary("Daily Standup")
+///     .starts(start - Duration::days(30) + Duration::hours(9))
+///     .repeats(RecurrenceRule::new(Frequency::Daily))
+///     .done();
+/// assert!(filter.matches_component(&standup.into()));
+/// ```
+///
+/// or parsed from their XML form, which [`Display`](fmt::Display) renders.
+///
+/// ```
+/// # use icalendar::{*, caldav::*};
+/// let filter: Filter = r#"
+///     <C:filter xmlns:C="urn:ietf:params:xml:ns:caldav">
+///       <C:comp-filter name="VCALENDAR">
+///         <C:comp-filter name="VTODO">
+///           <C:prop-filter name="COMPLETED">
+///             <C:is-not-defined/>
+///           </C:prop-filter>
+///         </C:comp-filter>
+///       </C:comp-filter>
+///     </C:filter>"#
+///     .parse()
+///     .unwrap();
+///
+/// assert!(filter.matches_component(&Todo::new().summary("open").done().into()));
+/// assert!(!filter.matches_component(&Todo::new().completed(chrono::Utc::now()).done().into()));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    root: CompFilter,
+}
+
+impl Filter {
+    /// Creates a filter from its top level comp-filter, which must be named `VCALENDAR` to match anything.
+    pub fn new(root: CompFilter) -> Self {
+        Filter { root }
+    }
+
+    /// Whether the calendar matches this filter.
+    pub fn matches(&self, calendar: &Calendar) -> bool {
+        let mut vcalendar = Other::from(("VCALENDAR".to_owned(), InnerComponent::default()));
+        for property in &calendar.properties {
+            vcalendar.append_multi_property(property.clone());
+        }
+        for component in &calendar.components {
+            vcalendar.append_component(component.to_other());
+        }
+        self.matches_vcalendar(&vcalendar)
+    }
+
+    /// Whether a calendar holding just this component matches this filter.
+    pub fn matches_component(&self, component: &CalendarComponent) -> bool {
+        let mut vcalendar = Other::from(("VCALENDAR".to_owned(), InnerComponent::default()));
+        vcalendar.append_component(component.to_other());
+        self.matches_vcalendar(&vcalendar)
+    }
+
+    fn matches_vcalendar(&self, vcalendar: &Other) -> bool {
+        if self.root.name != "VCALENDAR" {
+            return false;
+        }
+        !self.root.is_not_defined
+            && self
+                .root
+                .matches_component(vcalendar, &event_view(vcalendar), None)
+    }
+}
+
+impl From<CompFilter> for Filter {
+    fn from(root: CompFilter) -> Self {
+        Filter::new(root)
+    }
+}
+
+impl fmt::Display for TextMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<C:text-match collation=\"{}\"", self.collation.as_str())?;
+        if self.negate {
+            f.write_str(" negate-condition=\"yes\"")?;
+        }
+        write!(f, ">{}</C:text-match>", escape_xml(&self.text))
+    }
+}
+
+impl fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<C:time-range")?;
+        if let Some(start) = self.start {
+            write!(f, " start=\"{}\"", format_utc_date_time(start))?;
+        }
+        if let Some(end) = self.end {
+            write!(f, " end=\"{}\"", format_utc_date_time(end))?;
+        }
+        f.write_str("/>")
+    }
+}
+
+impl fmt::Display for ParamFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<C:param-filter name=\"{}\">", escape_xml(&self.name))?;
+        if self.is_not_defined {
+            f.write_str("<C:is-not-defined/>")?;
+        }
+        if let Some(text_match) = &self.text_match {
+            write!(f, "{}", text_match)?;
+        }
+        f.write_str("</C:param-filter>")
+    }
+}
+
+impl fmt::Display for PropFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<C:prop-filter name=\"{}\">", escape_xml(&self.name))?;
+        if self.is_not_defined {
+            f.write_str("<C:is-not-defined/>")?;
+        }
+        if let Some(time_range) = &self.time_range {
+            write!(f, "{}", time_range)?;
+        }
+        if let Some(text_match) = &self.text_match {
+            write!(f, "{}", text_match)?;
+        }
+        for param_filter in &self.param_filters {
+            write!(f, "{}", param_filter)?;
+        }
+        f.write_str("</C:prop-filter>")
+    }
+}
+
+impl fmt::Display for CompFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<C:comp-filter name=\"{}\">", escape_xml(&self.name))?;
+        if self.is_not_defined {
+            f.write_str("<C:is-not-defined/>")?;
+        }
+        if let Some(time_range) = &self.time_range {
+            write!(f, "{}", time_range)?;
+        }
+        for prop_filter in &self.prop_filters {
+            write!(f, "{}", prop_filter)?;
+        }
+        for comp_filter in &self.comp_filters {
+            write!(f, "{}", comp_filter)?;
+        }
+        f.write_str("</C:comp-filter>")
+    }
+}
+
+/// Renders the `CALDAV:filter` element, declaring the `C` namespace prefix itself.
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<C:filter xmlns:C=\"{}\">{}</C:filter>",
+            CALDAV_NS, self.root
+        )
+    }
+}
+
+fn is_caldav(node: &roxmltree::Node<'_, '_>, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().name() == name
+        && node.tag_name().namespace() == Some(CALDAV_NS)
+}
+
+fn name_attribute(node: &roxmltree::Node<'_, '_>) -> Result<String, String> {
+    node.attribute("name")
+        .map(str::to_owned)
+        .ok_or_else(|| format!("<{}> without name", node.tag_name().name()))
+}
+
+fn parse_time_range(node: &roxmltree::Node<'_, '_>) -> Result<TimeRange, String> {
+    let bound = |key: &str| {
+        node.attribute(key)
+            .map(|value| {
+                parse_utc_date_time(value)
+                    .ok_or_else(|| format!("invalid time-range {key} {value:?}"))
+            })
+            .transpose()
+    };
+    let time_range = TimeRange {
+        start: bound("start")?,
+        end: bound("end")?,
+    };
+    if time_range.start.is_none() && time_range.end.is_none() {
+        return Err("time-range without start or end".into());
+    }
+    Ok(time_range)
+}
+
+fn parse_text_match(node: &roxmltree::Node<'_, '_>) -> Result<TextMatch, String> {
+    let collation = match node.attribute("collation") {
+        Some(collation) => collation
+            .parse()
+            .map_err(|_| format!("unsupported collation {collation:?}"))?,
+        None => Collation::default(),
+    };
+    let negate = match node.attribute("negate-condition") {
+        None | Some("no") => false,
+        Some("yes") => true,
+        Some(other) => return Err(format!("invalid negate-condition {other:?}")),
+    };
+    let text = node
+        .children()
+        .filter_map(|child| child.text())
+        .collect::<String>();
+    Ok(TextMatch {
+        text,
+        collation,
+        negate,
+    })
+}
+
+fn parse_param_filter(node: &roxmltree::Node<'_, '_>) -> Result<ParamFilter, String> {
+    let mut param_filter = ParamFilter::new(&name_attribute(node)?);
+    for child in node.children().filter(roxmltree::Node::is_element) {
+        if is_caldav(&child, "is-not-defined") {
+            param_filter.is_not_defined = true;
+        } else if is_caldav(&child, "text-match") {
+            param_filter.text_match = Some(parse_text_match(&child)?);
+        }
+    }
+    Ok(param_filter)
+}
+
+fn parse_prop_filter(node: &roxmltree::Node<'_, '_>) -> Result<PropFilter, String> {
+    let mut prop_filter = PropFilter::new(&name_attribute(node)?);
+    for child in node.children().filter(roxmltree::Node::is_element) {
+        if is_caldav(&child, "is-not-defined") {
+            prop_filter.is_not_defined = true;
+        } else if is_caldav(&child, "time-range") {
+            prop_filter.time_range = Some(parse_time_range(&child)?);
+        } else if is_caldav(&child, "text-match") {
+            prop_filter.text_match = Some(parse_text_match(&child)?);
+        } else if is_caldav(&child, "param-filter") {
+            prop_filter.param_filters.push(parse_param_filter(&child)?);
+        }
+    }
+    Ok(prop_filter)
+}
+
+fn parse_comp_filter(node: &roxmltree::Node<'_, '_>) -> Result<CompFilter, String> {
+    let mut comp_filter = CompFilter::new(&name_attribute(node)?);
+    for child in node.children().filter(roxmltree::Node::is_element) {
+        if is_caldav(&child, "is-not-defined") {
+            comp_filter.is_not_defined = true;
+        } else if is_caldav(&child, "time-range") {
+            comp_filter.time_range = Some(parse_time_range(&child)?);
+        } else if is_caldav(&child, "prop-filter") {
+            comp_filter.prop_filters.push(parse_prop_filter(&child)?);
+        } else if is_caldav(&child, "comp-filter") {
+            comp_filter.comp_filters.push(parse_comp_filter(&child)?);
+        }
+    }
+    Ok(comp_filter)
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    /// Parses a `CALDAV:filter` element, which may also be nested in a whole calendar-query.
+    fn from_str(xml: &str) -> Result<Self, Self::Err> {
+        let document = roxmltree::Document::parse(xml).map_err(|error| error.to_string())?;
+        let filter = document
+            .descendants()
+            .find(|node| is_caldav(node, "filter"))
+            .ok_or("no CALDAV:filter element")?;
+        let root = filter
+            .children()
+            .find(|node| is_caldav(node, "comp-filter"))
+            .ok_or("CALDAV:filter without comp-filter")?;
+        Ok(Filter::new(parse_comp_filter(&root)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::{Frequency, RecurrenceRule, Related, Todo};
+
+    fn at(d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, d, h, 0, 0).unwrap()
+    }
+
+    fn events(filter: CompFilter) -> Filter {
+        Filter::new(CompFilter::new("VCALENDAR").comp_filter(filter))
+    }
+
+    #[test]
+    fn component_existence() {
+        let event = CalendarComponent::from(Event::new().summary("x").done());
+        assert!(events(CompFilter::new("VEVENT")).matches_component(&event));
+        assert!(!events(CompFilter::new("VTODO")).matches_component(&event));
+        assert!(events(CompFilter::new("VTODO").is_not_defined()).matches_component(&event));
+        assert!(!Filter::new(CompFilter::new("VEVENT")).matches_component(&event));
+    }
+
+    #[test]
+    fn text_and_param_match() {
+        let event = CalendarComponent::from(
+            Event::new()
+                .summary("Team Meeting")
+                .append_multi_property(
+                    Property::new("ATTENDEE", "mailto:jane@example.com")
+                        .add_parameter("PARTSTAT", "NEEDS-ACTION")
+                        .done(),
+                )
+                .done(),
+        );
+        let summary = |text_match| {
+            events(
+                CompFilter::new("VEVENT")
+                    .prop_filter(PropFilter::new("SUMMARY").text_match(text_match)),
+            )
+            .matches_component(&event)
+        };
+        assert!(summary(TextMatch::new("meeting")));
+        assert!(!summary(
+            TextMatch::new("meeting").collation(Collation::Octet)
+        ));
+        assert!(summary(
+            TextMatch::new("Meeting").collation(Collation::Octet)
+        ));
+        assert!(summary(TextMatch::new("lunch").negate()));
+
+        let attendee = |param_filter| {
+            events(
+                CompFilter::new("VEVENT")
+                    .prop_filter(PropFilter::new("ATTENDEE").param_filter(param_filter)),
+            )
+            .matches_component(&event)
+        };
+        assert!(attendee(
+            ParamFilter::new("PARTSTAT").text_match(TextMatch::new("needs-action"))
+        ));
+        assert!(!attendee(
+            ParamFilter::new("PARTSTAT").text_match(TextMatch::new("accepted"))
+        ));
+        assert!(attendee(ParamFilter::new("ROLE").is_not_defined()));
+    }
+
+    #[test]
+    fn recurring_time_range() {
+        let weekly = CalendarComponent::from(
+            Event::new()
+                .starts(at(1, 9))
+                .ends(at(1, 10))
+                .repeats(RecurrenceRule::new(Frequency::Weekly).count(3))
+                .done(),
+        );
+        let in_range = |start, end| {
+            events(CompFilter::new("VEVENT").time_range(TimeRange::between(start, end)))
+                .matches_component(&weekly)
+        };
+        assert!(in_range(at(15, 9), at(15, 10)));
+        assert!(in_range(at(8, 9) + Duration::minutes(59), at(8, 11)));
+        assert!(!in_range(at(8, 10), at(15, 9)));
+        assert!(!in_range(at(22, 0), at(31, 0)));
+        assert!(
+            events(CompFilter::new("VEVENT").time_range(TimeRange::starting(at(15, 0))))
+                .matches_component(&weekly)
+        );
+    }
+
+    #[test]
+    fn todo_time_range() {
+        let due = CalendarComponent::from(Todo::new().due(at(10, 12)).done());
+        let todos = |time_range| events(CompFilter::new("VTODO").time_range(time_range));
+        assert!(todos(TimeRange::between(at(10, 0), at(11, 0))).matches_component(&due));
+        assert!(!todos(TimeRange::between(at(11, 0), at(12, 0))).matches_component(&due));
+        let undated = CalendarComponent::from(Todo::new().summary("someday").done());
+        assert!(todos(TimeRange::between(at(11, 0), at(12, 0))).matches_component(&undated));
+    }
+
+    #[test]
+    fn alarm_time_range() {
+        let event = CalendarComponent::from(
+            Event::new()
+                .starts(at(1, 9))
+                .ends(at(1, 10))
+                .repeats(RecurrenceRule::new(Frequency::Daily))
+                .alarm(Alarm::display(
+                    "wrap up",
+                    (-Duration::minutes(5), Related::End),
+                ))
+                .done(),
+        );
+        let alarms =
+            |start, end| {
+                events(CompFilter::new("VEVENT").comp_filter(
+                    CompFilter::new("VALARM").time_range(TimeRange::between(start, end)),
+                ))
+                .matches_component(&event)
+            };
+        assert!(alarms(at(20, 9), at(20, 10)));
+        assert!(!alarms(at(20, 10), at(21, 9)));
+    }
+
+    #[test]
+    fn overridden_occurrences() {
+        let master = Event::new()
+            .uid("standup")
+            .starts(at(1, 9))
+            .ends(at(1, 10))
+            .repeats(RecurrenceRule::new(Frequency::Daily).count(3))
+            .done();
+        let moved = Event::new()
+            .uid("standup")
+            .add_property("RECURRENCE-ID", "20240102T090000Z")
+            .starts(at(2, 14))
+            .ends(at(2, 15))
+            .done();
+        let calendar = Calendar::new().push(master).push(moved).done();
+        let in_range = |start, end| {
+            events(CompFilter::new("VEVENT").time_range(TimeRange::between(start, end)))
+                .matches(&calendar)
+        };
+        assert!(in_range(at(1, 9), at(1, 10)));
+        assert!(!in_range(at(2, 9), at(2, 10)));
+        assert!(in_range(at(2, 14), at(2, 15)));
+    }
+
+    #[test]
+    fn names_are_case_insensitive() {
+        let event = CalendarComponent::from(Event::new().summary("x").done());
+        let filter = Filter::new(
+            CompFilter::new("vcalendar")
+                .comp_filter(CompFilter::new("vevent").prop_filter(PropFilter::new("summary"))),
+        );
+        assert!(filter.matches_component(&event));
+        assert_eq!(ParamFilter::new("partstat"), ParamFilter::new("PARTSTAT"));
+    }
+
+    #[test]
+    fn calendar_properties() {
+        let calendar = Calendar::new()
+            .name("work")
+            .push(Event::new().summary("x").done())
+            .done();
+        let named = |name| {
+            Filter::new(
+                CompFilter::new("VCALENDAR")
+                    .prop_filter(PropFilter::new("X-WR-CALNAME").text_match(TextMatch::new(name))),
+            )
+            .matches(&calendar)
+        };
+        assert!(named("work"));
+        assert!(!named("home"));
+    }
+
+    #[test]
+    fn parse_xml() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8" ?>
+            <C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+              <D:prop><D:getetag/></D:prop>
+              <C:filter>
+                <C:comp-filter name="VCALENDAR">
+                  <C:comp-filter name="VEVENT">
+                    <C:time-range start="20060104T000000Z" end="20060105T000000Z"/>
+                    <C:prop-filter name="SUMMARY">
+                      <C:text-match collation="i;octet" negate-condition="yes">Lunch</C:text-match>
+                    </C:prop-filter>
+                    <C:prop-filter name="ATTENDEE">
+                      <C:param-filter name="PARTSTAT">
+                        <C:text-match>NEEDS-ACTION</C:text-match>
+                      </C:param-filter>
+                    </C:prop-filter>
+                    <C:prop-filter name="X-ABC"><C:is-not-defined/></C:prop-filter>
+                  </C:comp-filter>
+                </C:comp-filter>
+              </C:filter>
+            </C:calendar-query>"#;
+        let expected = events(
+            CompFilter::new("VEVENT")
+                .time_range(TimeRange::between(
+                    Utc.with_ymd_and_hms(2006, 1, 4, 0, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2006, 1, 5, 0, 0, 0).unwrap(),
+                ))
+                .prop_filter(
+                    PropFilter::new("SUMMARY")
+                        .text_match(TextMatch::new("Lunch").collation(Collation::Octet).negate()),
+                )
+                .prop_filter(PropFilter::new("ATTENDEE").param_filter(
+                    ParamFilter::new("PARTSTAT").text_match(TextMatch::new("NEEDS-ACTION")),
+                ))
+                .prop_filter(PropFilter::new("X-ABC").is_not_defined()),
+        );
+        assert_eq!(xml.parse::<Filter>(), Ok(expected));
+    }
+
+    #[test]
+    fn render_xml() {
+        let filter = events(
+            CompFilter::new("VEVENT")
+                .time_range(TimeRange::starting(at(1, 0)))
+                .prop_filter(
+                    PropFilter::new("SUMMARY").text_match(
+                        TextMatch::new("R&D <team>")
+                            .collation(Collation::Octet)
+                            .negate(),
+                    ),
+                )
+                .prop_filter(PropFilter::new("ATTENDEE").param_filter(
+                    ParamFilter::new("PARTSTAT").text_match(TextMatch::new("NEEDS-ACTION")),
+                ))
+                .prop_filter(PropFilter::new("X-ABC").is_not_defined())
+                .comp_filter(CompFilter::new("VALARM").is_not_defined()),
+        );
+        let xml = filter.to_string();
+        assert!(xml.starts_with(
+            r#"<C:filter xmlns:C="urn:ietf:params:xml:ns:caldav"><C:comp-filter name="VCALENDAR"><C:comp-filter name="VEVENT"><C:time-range start="20240101T000000Z"/>"#
+        ));
+        assert!(xml.contains(
+            r#"<C:text-match collation="i;octet" negate-condition="yes">R&amp;D &lt;team&gt;</C:text-match>"#
+        ));
+        assert_eq!(xml.parse::<Filter>(), Ok(filter));
+    }
+
+    #[test]
+    fn parse_xml_errors() {
+        for xml in [
+            "<filter/>",
+            r#"<C:filter xmlns:C="urn:ietf:params:xml:ns:caldav"/>"#,
+            r#"<C:filter xmlns:C="urn:ietf:params:xml:ns:caldav"><C:comp-filter/></C:filter>"#,
+            r#"<C:filter xmlns:C="urn:ietf:params:xml:ns:caldav"><C:comp-filter name="VCALENDAR"><C:time-range/></C:comp-filter></C:filter>"#,
+            r#"<C:filter xmlns:C="urn:ietf:params:xml:ns:caldav"><C:comp-filter name="VCALENDAR"><C:prop-filter name="X"><C:text-match collation="i;unknown">x</C:text-match></C:prop-filter></C:comp-filter></C:filter>"#,
+        ] {
+            assert!(xml.parse::<Filter>().is_err(), "{xml}");
+        }
+    }
+}
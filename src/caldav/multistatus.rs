@@ -0,0 +1,248 @@
+//! `DAV:multistatus` responses of `PROPFIND` and `REPORT` requests
+//!
+//! [RFC 4918, Section 13](https://datatracker.ietf.org/doc/html/rfc4918#section-13)
+
+use roxmltree::Node;
+
+use super::{CALDAV_NS, DAV_NS};
+
+const CALENDARSERVER_NS: &str = "http://calendarserver.org/ns/";
+const APPLE_ICAL_NS: &str = "http://apple.com/ns/ical/";
+
+/// The properties of a resource that were found, others are left out
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Props {
+    pub etag: Option<String>,
+    pub calendar_data: Option<String>,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub ctag: Option<String>,
+    pub sync_token: Option<String>,
+    pub is_calendar: bool,
+    pub current_user_principal: Option<String>,
+    pub calendar_home_set: Option<String>,
+    pub components: Vec<String>,
+}
+
+/// A `DAV:response`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub href: String,
+    /// Status of the resource itself, like 404 for members removed since a sync-token
+    pub status: Option<u16>,
+    pub props: Props,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Multistatus {
+    pub responses: Vec<Response>,
+    /// New token of a sync-collection report
+    pub sync_token: Option<String>,
+}
+
+fn is(node: &Node<'_, '_>, namespace: &str, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().name() == name
+        && node.tag_name().namespace() == Some(namespace)
+}
+
+fn child<'a, 'input>(
+    node: &Node<'a, 'input>,
+    namespace: &str,
+    name: &str,
+) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| is(child, namespace, name))
+}
+
+/// All text inside a node, CDATA included
+fn text(node: &Node<'_, '_>) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter_map(|text| text.text())
+        .collect()
+}
+
+fn href(node: &Node<'_, '_>) -> Option<String> {
+    node.descendants()
+        .find(|child| is(child, DAV_NS, "href"))
+        .map(|href| text(&href).trim().to_owned())
+}
+
+/// The code of a status line like `HTTP/1.1 200 OK`
+fn status(node: &Node<'_, '_>) -> Option<u16> {
+    let line = text(&child(node, DAV_NS, "status")?);
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn read_prop(props: &mut Props, prop: &Node<'_, '_>) {
+    let name = prop.tag_name().name();
+    let value = || Some(text(prop));
+    match prop.tag_name().namespace() {
+        Some(DAV_NS) => match name {
+            "getetag" => props.etag = value(),
+            "displayname" => props.display_name = value(),
+            "sync-token" => props.sync_token = value(),
+            "resourcetype" => props.is_calendar = child(prop, CALDAV_NS, "calendar").is_some(),
+            "current-user-principal" => props.current_user_principal = href(prop),
+            _ => {}
+        },
+        Some(CALDAV_NS) => match name {
+            "calendar-data" => props.calendar_data = value(),
+            "calendar-description" => props.description = value(),
+            "calendar-home-set" => props.calendar_home_set = href(prop),
+            "supported-calendar-component-set" => {
+                props.components = prop
+                    .children()
+                    .filter(|comp| is(comp, CALDAV_NS, "comp"))
+                    .filter_map(|comp| comp.attribute("name"))
+                    .map(str::to_owned)
+                    .collect()
+            }
+            _ => {}
+        },
+        Some(CALENDARSERVER_NS) if name == "getctag" => props.ctag = value(),
+        Some(APPLE_ICAL_NS) if name == "calendar-color" => props.color = value(),
+        _ => {}
+    }
+}
+
+fn read_response(node: &Node<'_, '_>) -> Result<Response, String> {
+    let href = child(node, DAV_NS, "href")
+        .map(|href| text(&href).trim().to_owned())
+        .ok_or("DAV:response without DAV:href")?;
+    let mut props = Props::default();
+    for propstat in node
+        .children()
+        .filter(|child| is(child, DAV_NS, "propstat"))
+    {
+        // properties that were not found come with their own status
+        if !status(&propstat).map_or(true, |status| (200..300).contains(&status)) {
+            continue;
+        }
+        let found = child(&propstat, DAV_NS, "prop");
+        for prop in found
+            .iter()
+            .flat_map(Node::children)
+            .filter(Node::is_element)
+        {
+            read_prop(&mut props, &prop);
+        }
+    }
+    Ok(Response {
+        href,
+        status: status(node),
+        props,
+    })
+}
+
+impl std::str::FromStr for Multistatus {
+    type Err = String;
+
+    fn from_str(xml: &str) -> Result<Self, Self::Err> {
+        let document = roxmltree::Document::parse(xml).map_err(|error| error.to_string())?;
+        let root = document.root_element();
+        if !is(&root, DAV_NS, "multistatus") {
+            return Err(format!(
+                "expected DAV:multistatus, found {}",
+                root.tag_name().name()
+            ));
+        }
+        Ok(Multistatus {
+            responses: root
+                .children()
+                .filter(|child| is(child, DAV_NS, "response"))
+                .map(|response| read_response(&response))
+                .collect::<Result<_, _>>()?,
+            sync_token: child(&root, DAV_NS, "sync-token").map(|token| text(&token)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn calendar_collections() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+            <d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav"
+                           xmlns:cs="http://calendarserver.org/ns/" xmlns:ic="http://apple.com/ns/ical/">
+              <d:response>
+                <d:href>/calendars/alice/work/</d:href>
+                <d:propstat>
+                  <d:prop>
+                    <d:resourcetype><d:collection/><cal:calendar/></d:resourcetype>
+                    <d:displayname>Work</d:displayname>
+                    <ic:calendar-color>#ff0000</ic:calendar-color>
+                    <cs:getctag>42</cs:getctag>
+                    <cal:supported-calendar-component-set>
+                      <cal:comp name="VEVENT"/><cal:comp name="VTODO"/>
+                    </cal:supported-calendar-component-set>
+                  </d:prop>
+                  <d:status>HTTP/1.1 200 OK</d:status>
+                </d:propstat>
+                <d:propstat>
+                  <d:prop><cal:calendar-description/></d:prop>
+                  <d:status>HTTP/1.1 404 Not Found</d:status>
+                </d:propstat>
+              </d:response>
+            </d:multistatus>"#;
+        let multistatus = xml.parse::<Multistatus>().unwrap();
+        assert_eq!(
+            multistatus.responses,
+            vec![Response {
+                href: String::from("/calendars/alice/work/"),
+                status: None,
+                props: Props {
+                    display_name: Some(String::from("Work")),
+                    color: Some(String::from("#ff0000")),
+                    ctag: Some(String::from("42")),
+                    is_calendar: true,
+                    components: vec![String::from("VEVENT"), String::from("VTODO")],
+                    ..Props::default()
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn sync_collection() {
+        let xml = r#"<multistatus xmlns="DAV:">
+              <response>
+                <href>/cal/a.ics</href>
+                <propstat>
+                  <prop><getetag>"1"</getetag><calendar-data xmlns="urn:ietf:params:xml:ns:caldav"><![CDATA[BEGIN:VCALENDAR
+END:VCALENDAR
+]]></calendar-data></prop>
+                  <status>HTTP/1.1 200 OK</status>
+                </propstat>
+              </response>
+              <response><href>/cal/b.ics</href><status>HTTP/1.1 404 Not Found</status></response>
+              <sync-token>http://example.com/sync/2</sync-token>
+            </multistatus>"#;
+        let multistatus = xml.parse::<Multistatus>().unwrap();
+        let [changed, deleted] = <[Response; 2]>::try_from(multistatus.responses).unwrap();
+        assert_eq!(changed.props.etag.as_deref(), Some("\"1\""));
+        assert_eq!(
+            changed.props.calendar_data.as_deref(),
+            Some("BEGIN:VCALENDAR\nEND:VCALENDAR\n")
+        );
+        assert_eq!(deleted.status, Some(404));
+        assert_eq!(
+            multistatus.sync_token.as_deref(),
+            Some("http://example.com/sync/2")
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        assert!("<html/>".parse::<Multistatus>().is_err());
+        assert!("<multistatus".parse::<Multistatus>().is_err());
+        assert!(r#"<multistatus xmlns="DAV:"><response/></multistatus>"#
+            .parse::<Multistatus>()
+            .is_err());
+    }
+}
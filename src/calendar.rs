@@ -1,11 +1,27 @@
-use chrono::Duration;
-use std::{fmt, mem, ops::Deref};
-
-use crate::{components::*, Parameter, Property};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::{collections::HashMap, fmt, mem, ops::Deref};
+
+use crate::{
+    components::{
+        date_time::{format_utc_date_time, parse_utc_date_time},
+        *,
+    },
+    typed::{typed_property, Cardinality, TypedProperty},
+    vendor::wr,
+    Alarm, Color, DatePerhapsTime, IcalDuration, Image, Parameter, Property, WriteOptions,
+};
 
 mod calendar_component;
+mod diff;
+mod index;
+mod merge;
 
 pub use calendar_component::CalendarComponent;
+pub use diff::{
+    CalendarDiff, ComponentChange, ComponentKey, DiffOptions, ParameterChange, PropertyChange,
+};
+pub use index::{CalendarIndex, EntryId, IndexedOccurrence};
+pub use merge::{LatestRevision, MergeConflict, MergeReport, MergeStrategy, Winner};
 
 /// Represents a calendar
 ///
@@ -107,6 +123,8 @@ impl Calendar {
     }
 
     /// Moves all the elements of other into Self, leaving other empty.
+    ///
+    /// This does not deduplicate anything, see [`Calendar::merge()`] for that.
     pub fn append(&mut self, other: &mut Calendar) {
         self.components.append(&mut other.components);
     }
@@ -118,15 +136,46 @@ impl Calendar {
     }
 
     /// Gets the value of a property.
+    ///
+    /// The key is case-insensitive.
     pub fn property_value(&self, key: &str) -> Option<&str> {
         Some(
             self.properties
                 .iter()
-                .find(|property| property.key() == key)?
+                .find(|property| property.key().eq_ignore_ascii_case(key))?
                 .value(),
         )
     }
 
+    /// Sets a calendar property declared as [`TypedProperty`], replacing it unless it may occur multiple times.
+    pub fn set<P: TypedProperty>(&mut self, value: P::Value) -> &mut Self {
+        if P::CARDINALITY == Cardinality::Once {
+            self.properties.retain(|property| property.key() != P::NAME);
+        }
+        self.append_property(typed_property::<P>(value))
+    }
+
+    /// Replaces any properties with the same key, for properties that may occur only once.
+    fn replace_property(&mut self, property: impl Into<Property>) -> &mut Self {
+        let property = property.into();
+        self.properties
+            .retain(|existing| existing.key() != property.key());
+        self.append_property(property)
+    }
+
+    /// Gets the value of a calendar property declared as [`TypedProperty`].
+    pub fn get<P: TypedProperty>(&self) -> Option<P::Value> {
+        self.properties_by_key(P::NAME)
+            .find_map(|property| P::from_property(property))
+    }
+
+    /// Iterates over all top-level properties with the given key.
+    fn properties_by_key<'c>(&'c self, key: &'c str) -> impl Iterator<Item = &'c Property> + 'c {
+        self.properties
+            .iter()
+            .filter(move |property| property.key() == key)
+    }
+
     /// Extends this `Calendar` with the contends of another.
     pub fn extend<T, U>(&mut self, other: T)
     where
@@ -142,12 +191,168 @@ impl Calendar {
         self
     }
 
+    /// Finds the first component with the given [`UID`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.7).
+    pub fn find_by_uid(&self, uid: &str) -> Option<&CalendarComponent> {
+        self.components
+            .iter()
+            .find(|component| component.uid() == Some(uid))
+    }
+
+    /// Finds the first component with the given [`UID`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.7) for editing.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut calendar = Calendar::new()
+    ///     .push(Event::new().uid("lunch").summary("Lunch").done())
+    ///     .done();
+    /// if let Some(event) = calendar
+    ///     .find_by_uid_mut("lunch")
+    ///     .and_then(CalendarComponent::as_event_mut)
+    /// {
+    ///     event.summary("Brunch");
+    /// }
+    /// assert_eq!(calendar[0].as_event().unwrap().get_summary(), Some("Brunch"));
+    /// ```
+    pub fn find_by_uid_mut(&mut self, uid: &str) -> Option<&mut CalendarComponent> {
+        self.components
+            .iter_mut()
+            .find(|component| component.uid() == Some(uid))
+    }
+
+    /// Removes all components with the given [`UID`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.7),
+    /// including overridden occurrences of recurring ones.
+    pub fn remove_by_uid(&mut self, uid: &str) -> Vec<CalendarComponent> {
+        let (removed, kept) = mem::take(&mut self.components)
+            .into_iter()
+            .partition(|component| component.uid() == Some(uid));
+        self.components = kept;
+        removed
+    }
+
+    /// `RECURRENCE-ID`s of overrides by the kind and `UID` of the component whose occurrences they replace.
+    fn overrides(&self) -> HashMap<(String, &str), Vec<DatePerhapsTime>> {
+        let mut overrides = HashMap::new();
+        for component in &self.components {
+            let recurrence_id = component
+                .inner()
+                .properties
+                .get("RECURRENCE-ID")
+                .and_then(DatePerhapsTime::from_property);
+            if let (Some(uid), Some(recurrence_id)) = (component.uid(), recurrence_id) {
+                overrides
+                    .entry((component.component_kind(), uid))
+                    .or_insert_with(Vec::new)
+                    .push(recurrence_id);
+            }
+        }
+        overrides
+    }
+
+    /// Events and todos with views to expand, which leave out the occurrences overrides replace.
+    fn occurrence_views(&self) -> Vec<(&CalendarComponent, Event)> {
+        let overrides = self.overrides();
+        self.components
+            .iter()
+            .filter(|component| {
+                matches!(
+                    component,
+                    CalendarComponent::Event(_) | CalendarComponent::Todo(_)
+                )
+            })
+            .map(|component| {
+                let overridden = match component.uid() {
+                    Some(uid) if component.property_value("RECURRENCE-ID").is_none() => overrides
+                        .get(&(component.component_kind(), uid))
+                        .cloned()
+                        .unwrap_or_default(),
+                    _ => Vec::new(),
+                };
+                (component, component.occurrence_view(overridden))
+            })
+            .collect()
+    }
+
+    /// Gets the starts of all occurrences of events and todos within `window_start..window_end`, sorted.
+    ///
+    /// Unlike [`EventLike::occurrences()`] this leaves out occurrences replaced by a component with the
+    /// same `UID` and a `RECURRENCE-ID`, which is an occurrence of its own. Floating date-times and dates
+    /// are read in `local`, like [`chrono::Local`] or a `chrono_tz::Tz`, pass [`Utc`] to treat them as UTC.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let start = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    /// let moved = start + Duration::days(1) + Duration::hours(2);
+    /// let calendar = Calendar::new()
+    ///     .push(
+    ///         Event::new()
+    ///             .uid("standup")
+    ///             .starts(start)
+    ///             .repeats(RecurrenceRule::new(Frequency::Daily).count(3))
+    ///             .done(),
+    ///     )
+    ///     .push(
+    ///         Event::new()
+    ///             .uid("standup")
+    ///             .add_property("RECURRENCE-ID", "20240102T090000Z")
+    ///             .starts(moved)
+    ///             .done(),
+    ///     )
+    ///     .done();
+    ///
+    /// let starts = calendar
+    ///     .occurrences(start, start + Duration::days(7), &Utc)
+    ///     .into_iter()
+    ///     .map(|(at, _, _)| at)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(starts, vec![start, moved, start + Duration::days(2)]);
+    /// ```
+    pub fn occurrences<Tz: TimeZone>(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+        local: &Tz,
+    ) -> Vec<(DateTime<Utc>, DatePerhapsTime, &CalendarComponent)> {
+        let mut occurrences = Vec::new();
+        for (component, view) in self.occurrence_views() {
+            occurrences.extend(
+                recurrence::occurrence_starts(&view, local)
+                    .skip_while(|(at, _)| *at < window_start)
+                    .take_while(|(at, _)| *at < window_end)
+                    .map(|(at, occurrence)| (at, occurrence, component)),
+            );
+        }
+        occurrences.sort_by_key(|(at, _, _)| *at);
+        occurrences
+    }
+
+    /// Gets every instant an [`Alarm`] of an event or todo fires at within `window_start..window_end`, sorted.
+    ///
+    /// Alarms are resolved like in [`EventLike::alarm_times()`] against the [occurrences](Calendar::occurrences),
+    /// so a moved occurrence fires the alarms of its override at its new time.
+    pub fn alarm_times<Tz: TimeZone>(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+        local: &Tz,
+    ) -> Vec<(DateTime<Utc>, &CalendarComponent, Alarm)> {
+        let mut times = Vec::new();
+        for (component, view) in self.occurrence_views() {
+            times.extend(
+                recurrence::alarm_times(&view, window_start, window_end, local)
+                    .into_iter()
+                    .map(|(at, alarm)| (at, component, alarm)),
+            );
+        }
+        times.sort_by_key(|(at, _, _)| *at);
+        times
+    }
+
     /// Set the `NAME` and `X-WR-CALNAME` `Property`s
     // TODO: where is `NAME` specified? it's not in rfc5545 or rfc2445
     pub fn name(&mut self, name: &str) -> &mut Self {
         self.append_property(Property::new("NAME", name));
-        self.append_property(Property::new("X-WR-CALNAME", name));
-        self
+        self.set::<wr::CalName>(name.to_owned())
     }
 
     /// Gets the value of the `NAME` or `X-WR-CALNAME` property.
@@ -159,8 +364,7 @@ impl Calendar {
     /// Set the [`DESCRIPTION`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.5) and `X-WR-CALDESC` `Property`s
     pub fn description(&mut self, description: &str) -> &mut Self {
         self.append_property(Property::new("DESCRIPTION", description));
-        self.append_property(Property::new("X-WR-CALDESC", description));
-        self
+        self.set::<wr::CalDesc>(description.to_owned())
     }
 
     /// Gets the value of the `DESCRIPTION` or `X-WR-CALDESC` property.
@@ -172,9 +376,75 @@ impl Calendar {
     /// Set the `TIMEZONE-ID` and `X-WR-TIMEZONE` `Property`s
     // TODO: where is `TIMEZONE-ID` specified? it's not in rfc5545 or rfc2445
     pub fn timezone(&mut self, timezone: &str) -> &mut Self {
-        self.append_property(Property::new("TIMEZONE-ID", timezone));
-        self.append_property(Property::new("X-WR-TIMEZONE", timezone));
-        self
+        self.replace_property(Property::new("TIMEZONE-ID", timezone));
+        self.set::<wr::Timezone>(timezone.to_owned())
+    }
+
+    /// Set the [`UID`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.3) [`Property`]
+    pub fn uid(&mut self, uid: &str) -> &mut Self {
+        self.replace_property(Property::new("UID", uid))
+    }
+
+    /// Gets the value of the [`UID`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.3) property.
+    pub fn get_uid(&self) -> Option<&str> {
+        self.property_value("UID")
+    }
+
+    /// Set the [`LAST-MODIFIED`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.4) [`Property`]
+    pub fn last_modified(&mut self, dt: DateTime<Utc>) -> &mut Self {
+        self.replace_property(Property::new("LAST-MODIFIED", format_utc_date_time(dt)))
+    }
+
+    /// Gets the value of the [`LAST-MODIFIED`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.4) property.
+    pub fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        parse_utc_date_time(self.property_value("LAST-MODIFIED")?)
+    }
+
+    /// Set the [`URL`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.5) [`Property`]
+    pub fn url(&mut self, url: &str) -> &mut Self {
+        self.replace_property(Property::new("URL", url))
+    }
+
+    /// Gets the value of the [`URL`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.5) property.
+    pub fn get_url(&self) -> Option<&str> {
+        self.property_value("URL")
+    }
+
+    /// Set the [`SOURCE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.8) [`Property`],
+    /// the location this calendar can be refreshed from.
+    pub fn source(&mut self, source: &str) -> &mut Self {
+        self.replace_property(
+            Property::new("SOURCE", source)
+                .append_parameter(Parameter::new("VALUE", "URI"))
+                .done(),
+        )
+    }
+
+    /// Gets the value of the [`SOURCE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.8) property.
+    pub fn get_source(&self) -> Option<&str> {
+        self.property_value("SOURCE")
+    }
+
+    /// Set the [`COLOR`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.9) [`Property`]
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.replace_property(color)
+    }
+
+    /// Gets the value of the [`COLOR`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.9) property.
+    pub fn get_color(&self) -> Option<Color> {
+        self.property_value("COLOR")?.parse().ok()
+    }
+
+    /// Adds an [`IMAGE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.10) [`Property`]
+    pub fn image(&mut self, image: Image) -> &mut Self {
+        self.append_property(image)
+    }
+
+    /// Gets all [`IMAGE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.10) properties.
+    pub fn get_images(&self) -> Vec<Image> {
+        self.properties_by_key("IMAGE")
+            .filter_map(|property| Image::try_from(property).ok())
+            .collect()
     }
 
     /// Gets the value of the `TIMEZONE_ID` or `X-WR-TIMEZONE` property.
@@ -184,30 +454,27 @@ impl Calendar {
     }
 
     /// Set the `REFRESH-INTERVAL` and `X-PUBLISHED-TTL` `Property`s
-    pub fn ttl(&mut self, duration: &Duration) -> &mut Self {
-        let duration_string = duration.to_string();
+    pub fn ttl(&mut self, duration: impl Into<IcalDuration>) -> &mut Self {
+        let duration = duration.into();
         self.append_property(
-            Property::new("REFRESH-INTERVAL", &duration_string)
+            Property::new("REFRESH-INTERVAL", duration.to_string())
                 .append_parameter(Parameter::new("VALUE", "DURATION"))
                 .done(),
         );
-        self.append_property(Property::new("X-PUBLISHED-TTL", &duration_string));
-        self
+        self.set::<wr::PublishedTtl>(duration)
     }
 
     /// Gets the value of the `REFRESH-INTERVAL` or `X-PUBLISHED-TTL` property.
+    ///
+    /// Returns [`None`] if it is too long for a [`Duration`].
     pub fn get_ttl(&self) -> Option<Duration> {
         self.property_value("REFRESH-INTERVAL")
-            .and_then(|refresh_interval| iso8601::duration(refresh_interval).ok())
+            .and_then(|refresh_interval| refresh_interval.parse::<IcalDuration>().ok())
             .or_else(|| {
                 self.property_value("X-PUBLISHED-TTL")
-                    .and_then(|published_ttl| iso8601::duration(published_ttl).ok())
+                    .and_then(|published_ttl| published_ttl.parse::<IcalDuration>().ok())
             })
-            .map(std::time::Duration::from)
-            .map(Duration::from_std)
-            .transpose()
-            .ok()
-            .flatten()
+            .and_then(|ttl| ttl.try_to_chrono())
     }
 
     /// End of builder pattern.
@@ -221,16 +488,34 @@ impl Calendar {
 
     /// Writes `Calendar` into a `Writer` using `std::fmt`.
     fn fmt_write<W: fmt::Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
-        write_crlf!(out, "BEGIN:VCALENDAR")?;
-        for property in &self.properties {
-            property.fmt_write(out)?;
+        self.fmt_write_with(out, &WriteOptions::default())
+    }
+
+    /// Writes `Calendar` into a `Writer` using `std::fmt` as configured by [`WriteOptions`].
+    pub fn fmt_write_with<W: fmt::Write>(
+        &self,
+        out: &mut W,
+        options: &WriteOptions,
+    ) -> Result<(), fmt::Error> {
+        options.write_line(out, "BEGIN:VCALENDAR")?;
+        for property in options.calendar_properties(&self.properties) {
+            property.fmt_write_with(out, options)?;
         }
 
         for component in &self.components {
-            component.fmt_write(out)?;
+            component.fmt_write_with(out, options)?;
         }
-        write_crlf!(out, "END:VCALENDAR")?;
-        Ok(())
+        options.write_line(out, "END:VCALENDAR")
+    }
+
+    /// Serializes this calendar as configured by [`WriteOptions`].
+    ///
+    /// # Panic
+    /// this can panic if [`std::fmt::write`] returns an Error
+    pub fn to_string_with(&self, options: &WriteOptions) -> String {
+        let mut out_string = String::new();
+        self.fmt_write_with(&mut out_string, options).unwrap();
+        out_string
     }
 
     /// Prints to stdout
@@ -310,6 +595,100 @@ fn from_adds_default_properties() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Frequency, RecurrenceRule};
+
+    #[test]
+    fn alarms_of_overridden_floating_occurrences() {
+        use chrono::{FixedOffset, NaiveDate};
+
+        let wall_clock = |d: u32, h: u32| {
+            NaiveDate::from_ymd_opt(2024, 1, d)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+        };
+        let alarm = || Alarm::display("soon", -Duration::minutes(15));
+        let calendar = Calendar::new()
+            .push(
+                Event::new()
+                    .uid("standup")
+                    .starts(wall_clock(1, 9))
+                    .repeats(RecurrenceRule::new(Frequency::Daily).count(3))
+                    .alarm(alarm())
+                    .done(),
+            )
+            .push(
+                Event::new()
+                    .uid("standup")
+                    .add_property("RECURRENCE-ID", "20240102T090000")
+                    .starts(wall_clock(2, 14))
+                    .alarm(alarm())
+                    .done(),
+            )
+            .done();
+        let times = |local: &dyn Fn(u32, u32) -> DateTime<Utc>, offset: &FixedOffset| {
+            let fires = calendar
+                .alarm_times(local(1, 0), local(31, 0), offset)
+                .into_iter()
+                .map(|(at, _, _)| at)
+                .collect::<Vec<_>>();
+            let expected = [local(1, 9), local(2, 14), local(3, 9)]
+                .map(|start| start - Duration::minutes(15))
+                .to_vec();
+            assert_eq!(fires, expected);
+        };
+
+        let utc = |d, h| Utc.from_utc_datetime(&wall_clock(d, h));
+        times(&utc, &FixedOffset::east_opt(0).unwrap());
+        let plus_one = |d, h| utc(d, h) - Duration::hours(1);
+        times(&plus_one, &FixedOffset::east_opt(3600).unwrap());
+    }
+
+    #[test]
+    fn edit_by_uid() {
+        let mut calendar = Calendar::new()
+            .push(
+                Event::new()
+                    .uid("meeting")
+                    .summary("Planning")
+                    .append_multi_property(("ATTENDEE", "mailto:a@example.com"))
+                    .append_multi_property(("ATTENDEE", "mailto:b@example.com"))
+                    .alarm(Alarm::display("soon", -Duration::minutes(10)))
+                    .done(),
+            )
+            .push(
+                Event::new()
+                    .uid("meeting")
+                    .add_property("RECURRENCE-ID", "20240108T090000Z")
+                    .done(),
+            )
+            .push(Todo::new().uid("chore").done())
+            .done();
+
+        let event = calendar
+            .find_by_uid_mut("meeting")
+            .and_then(CalendarComponent::as_event_mut)
+            .unwrap();
+        event.retain_multi("ATTENDEE", |attendee| {
+            attendee.value().ends_with("a@example.com")
+        });
+        assert_eq!(event.remove_property("SUMMARY").len(), 1);
+        let alarm = event.components_mut().first_mut().unwrap();
+        *alarm.property_mut("DESCRIPTION").unwrap() = Property::new("DESCRIPTION", "now");
+
+        let event = calendar.find_by_uid("meeting").unwrap().as_event().unwrap();
+        assert_eq!(event.get_summary(), None);
+        assert_eq!(event.multi_properties()["ATTENDEE"].len(), 1);
+        assert_eq!(
+            event.alarms().first().unwrap().get_description(),
+            Some("now")
+        );
+
+        assert_eq!(calendar.remove_by_uid("meeting").len(), 2);
+        assert!(calendar.find_by_uid("meeting").is_none());
+        assert!(calendar.find_by_uid("chore").is_some());
+        assert_eq!(calendar.len(), 1);
+    }
 
     #[test]
     fn calendar_extend_components() {
@@ -362,6 +741,65 @@ mod tests {
         assert_eq!(calendar.get_timezone(), Some("timezone"));
     }
 
+    #[test]
+    fn ttl_out_of_range() {
+        let calendar = Calendar::new().ttl(Duration::hours(1)).done();
+        assert_eq!(calendar.get_ttl(), Some(Duration::hours(1)));
+
+        let calendar = Calendar::new()
+            .append_property(
+                Property::new("REFRESH-INTERVAL", "PT9223372036854775807S")
+                    .add_parameter("VALUE", "DURATION")
+                    .done(),
+            )
+            .done();
+        assert_eq!(calendar.get_ttl(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn rfc7986_properties() {
+        use chrono::TimeZone;
+        use std::str::FromStr;
+
+        let modified = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let image = Image::new("https://example.com/calendar.png").fmttype("image/png");
+        let original = Calendar::new()
+            .uid("5FC53010-1267-4F8E-BC28-1D7AE55A7C99")
+            .last_modified(modified)
+            .url("https://example.com/calendar")
+            .source("https://example.com/calendar.ics")
+            .color(Color::from_str("darkorange").unwrap())
+            .image(image.clone())
+            .done();
+        let replaced = Calendar::new()
+            .uid("first")
+            .uid("second")
+            .last_modified(modified)
+            .last_modified(modified)
+            .done();
+        assert_eq!(replaced.get_uid(), Some("second"));
+        assert_eq!(replaced.properties_by_key("UID").count(), 1);
+        assert_eq!(replaced.properties_by_key("LAST-MODIFIED").count(), 1);
+
+        let parsed = Calendar::from_str(&original.to_string()).unwrap();
+        assert_eq!(
+            parsed.get_uid(),
+            Some("5FC53010-1267-4F8E-BC28-1D7AE55A7C99")
+        );
+        assert_eq!(parsed.get_last_modified(), Some(modified));
+        assert_eq!(parsed.get_url(), Some("https://example.com/calendar"));
+        assert_eq!(
+            parsed.get_source(),
+            Some("https://example.com/calendar.ics")
+        );
+        assert_eq!(
+            parsed.get_color().map(|color| color.as_str()),
+            Some("darkorange")
+        );
+        assert_eq!(parsed.get_images(), vec![image]);
+    }
+
     #[test]
     #[cfg(feature = "parser")]
     fn emit_parse_icalendar() {
@@ -1,9 +1,9 @@
-use crate::Component;
+use crate::{components::InnerComponent, Component, DatePerhapsTime, EventLike, WriteOptions};
 
-use super::{Event, Other, Todo, Venue};
+use super::{Event, FreeBusy, Other, Todo, Venue};
 use std::fmt;
 
-/// Wrapper for [`Todo`], [`Event`] or [`Venue`]
+/// Wrapper for [`Todo`], [`Event`], [`Venue`] or [`FreeBusy`]
 #[allow(missing_docs)]
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -11,6 +11,7 @@ pub enum CalendarComponent {
     Todo(Todo),
     Event(Event),
     Venue(Venue),
+    FreeBusy(FreeBusy),
     #[doc(hidden)]
     Other(Other),
 }
@@ -30,6 +31,34 @@ impl CalendarComponent {
             _ => None,
         }
     }
+    /// Attempt to access the containted [`FreeBusy`], if it is one
+    pub fn as_free_busy(&self) -> Option<&FreeBusy> {
+        match self {
+            Self::FreeBusy(ref free_busy) => Some(free_busy),
+            _ => None,
+        }
+    }
+    /// Attempt to mutably access the containted [`Event`], if it is one
+    pub fn as_event_mut(&mut self) -> Option<&mut Event> {
+        match self {
+            Self::Event(ref mut event) => Some(event),
+            _ => None,
+        }
+    }
+    /// Attempt to mutably access the containted [`Todo`], if it is one
+    pub fn as_todo_mut(&mut self) -> Option<&mut Todo> {
+        match self {
+            Self::Todo(ref mut todo) => Some(todo),
+            _ => None,
+        }
+    }
+    /// Attempt to mutably access the containted [`FreeBusy`], if it is one
+    pub fn as_free_busy_mut(&mut self) -> Option<&mut FreeBusy> {
+        match self {
+            Self::FreeBusy(ref mut free_busy) => Some(free_busy),
+            _ => None,
+        }
+    }
 }
 
 impl From<Event> for CalendarComponent {
@@ -50,6 +79,12 @@ impl From<Venue> for CalendarComponent {
     }
 }
 
+impl From<FreeBusy> for CalendarComponent {
+    fn from(val: FreeBusy) -> Self {
+        CalendarComponent::FreeBusy(val)
+    }
+}
+
 impl From<Other> for CalendarComponent {
     fn from(val: Other) -> Self {
         CalendarComponent::Other(val)
@@ -57,12 +92,116 @@ impl From<Other> for CalendarComponent {
 }
 
 impl CalendarComponent {
-    pub(crate) fn fmt_write<W: fmt::Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
+    /// Copies this component into an [`Other`] of the same kind.
+    pub(crate) fn to_other(&self) -> Other {
+        match self {
+            CalendarComponent::Todo(todo) => crate::components::to_other(todo),
+            CalendarComponent::Event(event) => crate::components::to_other(event),
+            CalendarComponent::Venue(venue) => crate::components::to_other(venue),
+            CalendarComponent::FreeBusy(free_busy) => crate::components::to_other(free_busy),
+            CalendarComponent::Other(other) => other.clone(),
+        }
+    }
+
+    /// This component as an [`Event`] to expand its occurrences, leaving out the `overridden` ones.
+    pub(crate) fn occurrence_view(
+        &self,
+        overridden: impl IntoIterator<Item = DatePerhapsTime>,
+    ) -> Event {
+        let mut view = Event::from(InnerComponent::from(self.to_other()));
+        for recurrence_id in overridden {
+            view.exdate(recurrence_id);
+        }
+        view
+    }
+
+    /// Wraps an [`Other`] into the matching variant by its kind.
+    pub(crate) fn from_other(other: Other) -> Self {
+        let kind = other.component_kind();
+        let inner = InnerComponent::from(other);
+        match kind.as_str() {
+            "VEVENT" => Event::from(inner).into(),
+            "VTODO" => Todo::from(inner).into(),
+            "VVENUE" => Venue::from(inner).into(),
+            "VFREEBUSY" => FreeBusy::from(inner).into(),
+            _ => Other::from((kind, inner)).into(),
+        }
+    }
+
+    /// Tells you what kind of component this is, like `VEVENT` or `VTIMEZONE`
+    pub fn component_kind(&self) -> String {
+        match self {
+            CalendarComponent::Todo(todo) => todo.component_kind(),
+            CalendarComponent::Event(event) => event.component_kind(),
+            CalendarComponent::Venue(venue) => venue.component_kind(),
+            CalendarComponent::FreeBusy(free_busy) => free_busy.component_kind(),
+            CalendarComponent::Other(other) => other.component_kind(),
+        }
+    }
+
+    /// The `UID` of the contained component, if it has one
+    pub fn uid(&self) -> Option<&str> {
+        self.property_value("UID")
+    }
+
+    /// Gets the value of a property of the contained component
+    pub fn property_value(&self, key: &str) -> Option<&str> {
+        Some(self.inner().properties.get(key)?.value())
+    }
+
+    pub(crate) fn inner(&self) -> &InnerComponent {
+        match self {
+            CalendarComponent::Todo(todo) => &todo.inner,
+            CalendarComponent::Event(event) => &event.inner,
+            CalendarComponent::Venue(venue) => &venue.inner,
+            CalendarComponent::FreeBusy(free_busy) => &free_busy.inner,
+            CalendarComponent::Other(other) => &other.inner,
+        }
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut InnerComponent {
+        match self {
+            CalendarComponent::Todo(todo) => &mut todo.inner,
+            CalendarComponent::Event(event) => &mut event.inner,
+            CalendarComponent::Venue(venue) => &mut venue.inner,
+            CalendarComponent::FreeBusy(free_busy) => &mut free_busy.inner,
+            CalendarComponent::Other(other) => &mut other.inner,
+        }
+    }
+
+    pub(crate) fn fmt_write_with<W: fmt::Write>(
+        &self,
+        out: &mut W,
+        options: &WriteOptions,
+    ) -> Result<(), fmt::Error> {
         match *self {
-            CalendarComponent::Todo(ref todo) => todo.fmt_write(out),
-            CalendarComponent::Event(ref event) => event.fmt_write(out),
-            CalendarComponent::Venue(ref venue) => venue.fmt_write(out),
-            CalendarComponent::Other(ref other) => other.fmt_write(out),
+            CalendarComponent::Todo(ref todo) => todo.fmt_write_with(out, options),
+            CalendarComponent::Event(ref event) => event.fmt_write_with(out, options),
+            CalendarComponent::Venue(ref venue) => venue.fmt_write_with(out, options),
+            CalendarComponent::FreeBusy(ref free_busy) => free_busy.fmt_write_with(out, options),
+            CalendarComponent::Other(ref other) => other.fmt_write_with(out, options),
+        }
+    }
+
+    /// The [`Component::canonical_form()`] of the contained component
+    pub fn canonical_form(&self) -> String {
+        match self {
+            CalendarComponent::Todo(todo) => todo.canonical_form(),
+            CalendarComponent::Event(event) => event.canonical_form(),
+            CalendarComponent::Venue(venue) => venue.canonical_form(),
+            CalendarComponent::FreeBusy(free_busy) => free_busy.canonical_form(),
+            CalendarComponent::Other(other) => other.canonical_form(),
+        }
+    }
+
+    /// The [`Component::content_hash()`] of the contained component
+    pub fn content_hash(&self) -> u64 {
+        match self {
+            CalendarComponent::Todo(todo) => todo.content_hash(),
+            CalendarComponent::Event(event) => event.content_hash(),
+            CalendarComponent::Venue(venue) => venue.content_hash(),
+            CalendarComponent::FreeBusy(free_busy) => free_busy.content_hash(),
+            CalendarComponent::Other(other) => other.content_hash(),
         }
     }
 }
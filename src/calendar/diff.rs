@@ -0,0 +1,772 @@
+//! Semantic differences between two versions of a calendar
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+use crate::{
+    components::{InnerComponent, Other},
+    properties::{normalize_name, MULTI_PROPERTIES, VOLATILE_PROPERTIES},
+    Calendar, CalendarComponent, Component, Parameter, Property,
+};
+
+/// Tunes what [`Calendar::diff_with()`] reports.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiffOptions {
+    ignored: BTreeSet<String>,
+}
+
+impl DiffOptions {
+    /// Creates options that report every difference.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Ignores all properties with the given name.
+    pub fn ignore(mut self, name: &str) -> Self {
+        self.ignored.insert(normalize_name(name).into_owned());
+        self
+    }
+
+    /// Ignores [`DTSTAMP`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.2)
+    /// and [`LAST-MODIFIED`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.3),
+    /// which change whenever a calendar is exported.
+    pub fn ignore_volatile(self) -> Self {
+        VOLATILE_PROPERTIES
+            .iter()
+            .fold(self, |options, name| options.ignore(name))
+    }
+
+    fn is_ignored(&self, name: &str) -> bool {
+        self.ignored.contains(name)
+    }
+}
+
+/// Identifies a component across versions by its kind,
+/// [`UID`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.7) and
+/// [`RECURRENCE-ID`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.4).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ComponentKey {
+    /// Kind of the component, like `VEVENT`
+    pub kind: String,
+    /// Value of the `UID` property
+    pub uid: String,
+    /// Value of the `RECURRENCE-ID` property if the component overrides a single occurrence
+    pub recurrence_id: Option<String>,
+}
+
+impl ComponentKey {
+    pub(crate) fn of(kind: String, inner: &InnerComponent) -> Option<Self> {
+        Some(ComponentKey {
+            kind,
+            uid: inner.properties.get("UID")?.value().to_owned(),
+            recurrence_id: inner
+                .properties
+                .get("RECURRENCE-ID")
+                .map(|property| property.value().to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for ComponentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.uid)?;
+        if let Some(recurrence_id) = &self.recurrence_id {
+            write!(f, " (RECURRENCE-ID {})", recurrence_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// A difference in the parameters of a [`Property`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParameterChange {
+    /// The parameter only exists in the new version
+    Added(Parameter),
+    /// The parameter only exists in the old version
+    Removed(Parameter),
+    /// The parameter exists in both versions with different values
+    Modified {
+        /// The parameter in the old version
+        old: Parameter,
+        /// The parameter in the new version
+        new: Parameter,
+    },
+}
+
+impl fmt::Display for ParameterChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterChange::Added(parameter) => {
+                write!(f, "+ {}={}", parameter.key(), parameter.value())
+            }
+            ParameterChange::Removed(parameter) => {
+                write!(f, "- {}={}", parameter.key(), parameter.value())
+            }
+            ParameterChange::Modified { old, new } => {
+                write!(f, "~ {}: {} -> {}", old.key(), old.value(), new.value())
+            }
+        }
+    }
+}
+
+/// A difference in the properties of a component or calendar
+///
+/// Properties that may occur more than once, like `ATTENDEE`, are paired up by their value,
+/// so a changed `PARTSTAT` shows up as a modification while a changed address does not.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PropertyChange {
+    /// The property only exists in the new version
+    Added(Property),
+    /// The property only exists in the old version
+    Removed(Property),
+    /// The property exists in both versions with a different value or parameters
+    Modified {
+        /// The property in the old version
+        old: Property,
+        /// The property in the new version
+        new: Property,
+        /// How the parameters changed
+        parameters: Vec<ParameterChange>,
+    },
+}
+
+impl PropertyChange {
+    /// Name of the changed property
+    pub fn name(&self) -> &str {
+        match self {
+            PropertyChange::Added(property)
+            | PropertyChange::Removed(property)
+            | PropertyChange::Modified { new: property, .. } => property.key(),
+        }
+    }
+
+    fn modified(old: &Property, new: &Property) -> Self {
+        let mut keys = old
+            .params()
+            .keys()
+            .chain(new.params().keys())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        let parameters = keys
+            .into_iter()
+            .filter_map(|key| match (old.params().get(key), new.params().get(key)) {
+                (Some(old), Some(new)) if old == new => None,
+                (Some(old), Some(new)) => Some(ParameterChange::Modified {
+                    old: old.clone(),
+                    new: new.clone(),
+                }),
+                (Some(old), None) => Some(ParameterChange::Removed(old.clone())),
+                (None, Some(new)) => Some(ParameterChange::Added(new.clone())),
+                (None, None) => None,
+            })
+            .collect();
+        PropertyChange::Modified {
+            old: old.clone(),
+            new: new.clone(),
+            parameters,
+        }
+    }
+
+    fn write_report<W: fmt::Write>(&self, out: &mut W, indent: usize) -> fmt::Result {
+        match self {
+            PropertyChange::Added(property) => writeln!(
+                out,
+                "{:indent$}+ {}: {}",
+                "",
+                property.key(),
+                property.value(),
+                indent = indent
+            ),
+            PropertyChange::Removed(property) => writeln!(
+                out,
+                "{:indent$}- {}: {}",
+                "",
+                property.key(),
+                property.value(),
+                indent = indent
+            ),
+            PropertyChange::Modified {
+                old,
+                new,
+                parameters,
+            } => {
+                if old.value() == new.value() {
+                    writeln!(
+                        out,
+                        "{:indent$}~ {}: {}",
+                        "",
+                        new.key(),
+                        new.value(),
+                        indent = indent
+                    )?;
+                } else {
+                    writeln!(
+                        out,
+                        "{:indent$}~ {}: {} -> {}",
+                        "",
+                        new.key(),
+                        old.value(),
+                        new.value(),
+                        indent = indent
+                    )?;
+                }
+                for parameter in parameters {
+                    writeln!(out, "{:indent$}{}", "", parameter, indent = indent + 4)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A difference in the components of a calendar or of a component
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ComponentChange {
+    /// The component only exists in the new version
+    Added(CalendarComponent),
+    /// The component only exists in the old version
+    Removed(CalendarComponent),
+    /// A component with the same [`ComponentKey`] differs between the versions
+    Modified {
+        /// Identifies the component in both versions
+        key: ComponentKey,
+        /// How the properties changed
+        properties: Vec<PropertyChange>,
+        /// How nested components like `VALARM` changed
+        components: Vec<ComponentChange>,
+    },
+}
+
+impl ComponentChange {
+    fn write_report<W: fmt::Write>(&self, out: &mut W, indent: usize) -> fmt::Result {
+        let (sign, component) = match self {
+            ComponentChange::Added(component) => ('+', component),
+            ComponentChange::Removed(component) => ('-', component),
+            ComponentChange::Modified {
+                key,
+                properties,
+                components,
+            } => {
+                writeln!(out, "{:indent$}~ {}", "", key, indent = indent)?;
+                for property in properties {
+                    property.write_report(out, indent + 4)?;
+                }
+                for component in components {
+                    component.write_report(out, indent + 4)?;
+                }
+                return Ok(());
+            }
+        };
+        let kind = component.component_kind();
+        match ComponentKey::of(kind.clone(), component.inner()) {
+            Some(key) => writeln!(out, "{:indent$}{} {}", "", sign, key, indent = indent),
+            None => writeln!(out, "{:indent$}{} {}", "", sign, kind, indent = indent),
+        }
+    }
+}
+
+/// Structured differences between two calendars, see [`Calendar::diff()`]
+///
+/// [`Display`](fmt::Display) renders a readable report,
+/// [`Calendar::apply()`] replays the changes onto the old version.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CalendarDiff {
+    /// Changes of the top-level calendar properties
+    pub properties: Vec<PropertyChange>,
+    /// Added, removed and modified components
+    pub components: Vec<ComponentChange>,
+}
+
+impl CalendarDiff {
+    /// Returns `true` if both calendars are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty() && self.components.is_empty()
+    }
+}
+
+impl fmt::Display for CalendarDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for property in &self.properties {
+            property.write_report(f, 0)?;
+        }
+        for component in &self.components {
+            component.write_report(f, 0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Either a top-level [`CalendarComponent`] or a nested [`Other`]
+trait Node: Sized {
+    fn kind(&self) -> String;
+    fn inner(&self) -> &InnerComponent;
+    fn inner_mut(&mut self) -> &mut InnerComponent;
+    fn from_calendar_component(component: &CalendarComponent) -> Self;
+    fn to_calendar_component(&self) -> CalendarComponent;
+
+    fn key(&self) -> Option<ComponentKey> {
+        ComponentKey::of(self.kind(), self.inner())
+    }
+}
+
+impl Node for CalendarComponent {
+    fn kind(&self) -> String {
+        self.component_kind()
+    }
+    fn inner(&self) -> &InnerComponent {
+        CalendarComponent::inner(self)
+    }
+    fn inner_mut(&mut self) -> &mut InnerComponent {
+        CalendarComponent::inner_mut(self)
+    }
+    fn from_calendar_component(component: &CalendarComponent) -> Self {
+        component.clone()
+    }
+    fn to_calendar_component(&self) -> CalendarComponent {
+        self.clone()
+    }
+}
+
+impl Node for Other {
+    fn kind(&self) -> String {
+        self.component_kind()
+    }
+    fn inner(&self) -> &InnerComponent {
+        &self.inner
+    }
+    fn inner_mut(&mut self) -> &mut InnerComponent {
+        &mut self.inner
+    }
+    fn from_calendar_component(component: &CalendarComponent) -> Self {
+        component.to_other()
+    }
+    fn to_calendar_component(&self) -> CalendarComponent {
+        CalendarComponent::from_other(self.clone())
+    }
+}
+
+fn all_properties(inner: &InnerComponent) -> impl Iterator<Item = &Property> + Clone {
+    inner
+        .properties
+        .values()
+        .chain(inner.multi_properties.values().flatten())
+}
+
+fn diff_properties<'p>(
+    old: impl Iterator<Item = &'p Property> + Clone,
+    new: impl Iterator<Item = &'p Property> + Clone,
+    options: &DiffOptions,
+) -> Vec<PropertyChange> {
+    let mut names = Vec::<&str>::new();
+    for property in old.clone().chain(new.clone()) {
+        if !names.contains(&property.key()) && !options.is_ignored(property.key()) {
+            names.push(property.key());
+        }
+    }
+
+    let mut changes = Vec::new();
+    for name in names {
+        let mut removed = old
+            .clone()
+            .filter(|property| property.key() == name)
+            .collect::<Vec<_>>();
+        let mut added = new
+            .clone()
+            .filter(|property| property.key() == name)
+            .collect::<Vec<_>>();
+
+        removed.retain(|old| match added.iter().position(|new| new == old) {
+            Some(position) => {
+                added.remove(position);
+                false
+            }
+            None => true,
+        });
+        removed.retain(
+            |old| match added.iter().position(|new| new.value() == old.value()) {
+                Some(position) => {
+                    changes.push(PropertyChange::modified(old, added.remove(position)));
+                    false
+                }
+                None => true,
+            },
+        );
+        if let ([old], [new]) = (removed.as_slice(), added.as_slice()) {
+            if !MULTI_PROPERTIES.contains(&name) {
+                changes.push(PropertyChange::modified(old, new));
+                continue;
+            }
+        }
+        changes.extend(
+            removed
+                .into_iter()
+                .map(|property| PropertyChange::Removed(property.clone())),
+        );
+        changes.extend(
+            added
+                .into_iter()
+                .map(|property| PropertyChange::Added(property.clone())),
+        );
+    }
+    changes
+}
+
+/// Keys of the components, duplicates after the first one are treated like components without `UID`.
+fn keys<N: Node>(components: &[N]) -> Vec<Option<ComponentKey>> {
+    let mut seen = BTreeSet::new();
+    components
+        .iter()
+        .map(|component| component.key().filter(|key| seen.insert(key.clone())))
+        .collect()
+}
+
+fn diff_components<N: Node>(old: &[N], new: &[N], options: &DiffOptions) -> Vec<ComponentChange> {
+    let old_keys = keys(old);
+    let new_keys = keys(new);
+    let positions = new_keys
+        .iter()
+        .enumerate()
+        .filter_map(|(position, key)| Some((key.as_ref()?, position)))
+        .collect::<HashMap<_, _>>();
+    let mut matched = vec![false; new.len()];
+    let mut changes = Vec::new();
+
+    for (old_component, old_key) in old.iter().zip(old_keys) {
+        let counterpart = match &old_key {
+            Some(key) => positions.get(key).copied(),
+            None => new.iter().zip(&new_keys).zip(&matched).position(
+                |((new_component, new_key), matched)| {
+                    !matched
+                        && new_key.is_none()
+                        && new_component.kind() == old_component.kind()
+                        && diff_inner(old_component.inner(), new_component.inner(), options)
+                            .is_none()
+                },
+            ),
+        };
+        let new_component = counterpart.and_then(|position| {
+            *matched.get_mut(position)? = true;
+            new.get(position)
+        });
+        match (new_component, old_key) {
+            (Some(new_component), Some(key)) => {
+                if let Some((properties, components)) =
+                    diff_inner(old_component.inner(), new_component.inner(), options)
+                {
+                    changes.push(ComponentChange::Modified {
+                        key,
+                        properties,
+                        components,
+                    });
+                }
+            }
+            (Some(_), None) => {}
+            (None, _) => changes.push(ComponentChange::Removed(
+                old_component.to_calendar_component(),
+            )),
+        }
+    }
+
+    changes.extend(
+        new.iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(component, _)| ComponentChange::Added(component.to_calendar_component())),
+    );
+    changes
+}
+
+/// Returns `None` if the components are equivalent.
+fn diff_inner(
+    old: &InnerComponent,
+    new: &InnerComponent,
+    options: &DiffOptions,
+) -> Option<(Vec<PropertyChange>, Vec<ComponentChange>)> {
+    let properties = diff_properties(all_properties(old), all_properties(new), options);
+    let components = diff_components(&old.components, &new.components, options);
+    if properties.is_empty() && components.is_empty() {
+        None
+    } else {
+        Some((properties, components))
+    }
+}
+
+/// Where patched properties are looked up and stored
+trait PropertyStore {
+    fn find_mut(&mut self, property: &Property) -> Option<&mut Property>;
+    fn take(&mut self, property: &Property) -> Option<Property>;
+    fn add(&mut self, property: Property);
+}
+
+impl PropertyStore for Vec<Property> {
+    fn find_mut(&mut self, property: &Property) -> Option<&mut Property> {
+        self.iter_mut().find(|candidate| *candidate == property)
+    }
+
+    fn take(&mut self, property: &Property) -> Option<Property> {
+        let position = self.iter().position(|candidate| candidate == property)?;
+        Some(self.remove(position))
+    }
+
+    fn add(&mut self, property: Property) {
+        self.push(property);
+    }
+}
+
+impl PropertyStore for InnerComponent {
+    fn find_mut(&mut self, property: &Property) -> Option<&mut Property> {
+        match self.properties.get_mut(property.key()) {
+            Some(candidate) if candidate == property => Some(candidate),
+            _ => self
+                .multi_properties
+                .get_mut(property.key())?
+                .iter_mut()
+                .find(|candidate| *candidate == property),
+        }
+    }
+
+    fn take(&mut self, property: &Property) -> Option<Property> {
+        if self.properties.get(property.key()) == Some(property) {
+            return self.properties.remove(property.key());
+        }
+        let multi = self.multi_properties.get_mut(property.key())?;
+        let taken = multi.take(property)?;
+        if multi.is_empty() {
+            self.multi_properties.remove(property.key());
+        }
+        Some(taken)
+    }
+
+    fn add(&mut self, property: Property) {
+        if MULTI_PROPERTIES.contains(&property.key())
+            || self.multi_properties.contains_key(property.key())
+            || self.properties.contains_key(property.key())
+        {
+            self.insert_multi(property);
+        } else {
+            self.properties.insert(property.key().to_owned(), property);
+        }
+    }
+}
+
+fn apply_properties<S: PropertyStore>(
+    store: &mut S,
+    changes: &[PropertyChange],
+) -> Result<(), String> {
+    for change in changes {
+        match change {
+            PropertyChange::Added(property) => store.add(property.clone()),
+            PropertyChange::Removed(property) => {
+                store
+                    .take(property)
+                    .ok_or_else(|| format!("property to remove not found: {}", property.key()))?;
+            }
+            PropertyChange::Modified { old, new, .. } => {
+                *store
+                    .find_mut(old)
+                    .ok_or_else(|| format!("property to modify not found: {}", old.key()))? =
+                    new.clone();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_components<N: Node>(
+    components: &mut Vec<N>,
+    changes: &[ComponentChange],
+) -> Result<(), String> {
+    for change in changes {
+        match change {
+            ComponentChange::Added(component) => {
+                components.push(N::from_calendar_component(component));
+            }
+            ComponentChange::Removed(component) => {
+                let kind = component.component_kind();
+                let position = match ComponentKey::of(kind.clone(), component.inner()) {
+                    Some(key) => components
+                        .iter()
+                        .position(|candidate| candidate.key().as_ref() == Some(&key)),
+                    None => components.iter().position(|candidate| {
+                        candidate.kind() == kind && candidate.inner() == component.inner()
+                    }),
+                }
+                .ok_or_else(|| format!("component to remove not found: {}", kind))?;
+                components.remove(position);
+            }
+            ComponentChange::Modified {
+                key,
+                properties,
+                components: children,
+            } => {
+                let target = components
+                    .iter_mut()
+                    .find(|candidate| candidate.key().as_ref() == Some(key))
+                    .ok_or_else(|| format!("component to modify not found: {}", key))?
+                    .inner_mut();
+                apply_properties(target, properties)?;
+                apply_components(&mut target.components, children)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Calendar {
+    /// Compares this calendar with a newer version of it.
+    ///
+    /// Components are matched by kind, `UID` and `RECURRENCE-ID`,
+    /// components without a `UID` only match identical ones.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let old = Calendar::empty()
+    ///     .push(Event::new().uid("standup").summary("Standup").done())
+    ///     .done();
+    /// let new = Calendar::empty()
+    ///     .push(Event::new().uid("standup").summary("Daily standup").done())
+    ///     .push(Todo::new().uid("retro").summary("Prepare retro").done())
+    ///     .done();
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(
+    ///     diff.to_string(),
+    ///     "~ VEVENT standup\n    ~ SUMMARY: Standup -> Daily standup\n+ VTODO retro\n"
+    /// );
+    /// ```
+    pub fn diff(&self, other: &Calendar) -> CalendarDiff {
+        self.diff_with(other, &DiffOptions::default())
+    }
+
+    /// Like [`Calendar::diff()`] but skips what the [`DiffOptions`] ignore.
+    pub fn diff_with(&self, other: &Calendar, options: &DiffOptions) -> CalendarDiff {
+        CalendarDiff {
+            properties: diff_properties(self.properties.iter(), other.properties.iter(), options),
+            components: diff_components(&self.components, &other.components, options),
+        }
+    }
+
+    /// Applies a [`CalendarDiff`] as a patch.
+    ///
+    /// Fails without touching the calendar if a removed or modified property or component is missing.
+    pub fn apply(&mut self, diff: &CalendarDiff) -> Result<(), String> {
+        let mut properties = self.properties.clone();
+        let mut components = self.components.clone();
+        apply_properties(&mut properties, &diff.properties)?;
+        apply_components(&mut components, &diff.components)?;
+        self.properties = properties;
+        self.components = components;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Alarm, Event, EventLike, Todo, Trigger};
+
+    fn attendee(address: &str, partstat: &str) -> Property {
+        Property::new("ATTENDEE", address)
+            .add_parameter("PARTSTAT", partstat)
+            .done()
+    }
+
+    fn old() -> Calendar {
+        Calendar::new()
+            .push(
+                Event::new()
+                    .uid("meeting")
+                    .summary("Planning")
+                    .add_property("DTSTAMP", "20240101T000000Z")
+                    .append_multi_property(attendee("mailto:a@example.com", "NEEDS-ACTION"))
+                    .append_multi_property(attendee("mailto:b@example.com", "NEEDS-ACTION"))
+                    .alarm(Alarm::display("soon", -chrono::Duration::minutes(10)))
+                    .done(),
+            )
+            .push(Todo::new().uid("chore").summary("Dishes").done())
+            .push(Event::new().summary("no uid").done())
+            .done()
+    }
+
+    fn new() -> Calendar {
+        Calendar::new()
+            .push(
+                Event::new()
+                    .uid("meeting")
+                    .summary("Planning")
+                    .add_property("DTSTAMP", "20240102T000000Z")
+                    .append_multi_property(attendee("mailto:a@example.com", "ACCEPTED"))
+                    .append_multi_property(attendee("mailto:c@example.com", "NEEDS-ACTION"))
+                    .alarm(Alarm::display("soon", -chrono::Duration::minutes(5)))
+                    .done(),
+            )
+            .push(Event::new().summary("no uid").done())
+            .push(
+                Event::new()
+                    .uid("meeting")
+                    .add_property("RECURRENCE-ID", "20240108T090000Z")
+                    .summary("Planning, moved")
+                    .done(),
+            )
+            .done()
+    }
+
+    #[test]
+    fn reports_changes() {
+        let diff = old().diff_with(&new(), &DiffOptions::new().ignore_volatile());
+        assert!(diff.properties.is_empty());
+
+        let expected = "\
+~ VEVENT meeting
+    ~ ATTENDEE: mailto:a@example.com
+        ~ PARTSTAT: NEEDS-ACTION -> ACCEPTED
+    - ATTENDEE: mailto:b@example.com
+    + ATTENDEE: mailto:c@example.com
+    - VALARM
+    + VALARM
+- VTODO chore
++ VEVENT meeting (RECURRENCE-ID 20240108T090000Z)
+";
+        assert_eq!(diff.to_string(), expected);
+    }
+
+    #[test]
+    fn volatile_properties() {
+        let diff = old().diff(&new());
+        match diff.components.first() {
+            Some(ComponentChange::Modified { properties, .. }) => {
+                assert!(properties.iter().any(|change| change.name() == "DTSTAMP"));
+            }
+            other => panic!("expected a modification, got {:?}", other),
+        }
+
+        let options = DiffOptions::new().ignore("dtstamp").ignore("Last-Modified");
+        assert_eq!(options, DiffOptions::new().ignore_volatile());
+    }
+
+    #[test]
+    fn apply_as_patch() {
+        let (mut old, new) = (old(), new());
+        old.apply(&old.diff(&new)).unwrap();
+        assert!(old.diff(&new).is_empty());
+        assert_eq!(old, new);
+
+        let mut unrelated = Calendar::new();
+        let diff = self::old().diff(&new);
+        assert!(unrelated.apply(&diff).is_err());
+        assert_eq!(unrelated, Calendar::new());
+    }
+
+    #[test]
+    fn identical() {
+        assert!(old().diff(&old()).is_empty());
+        let trigger = Trigger::from(chrono::Duration::minutes(-5));
+        let calendar = Calendar::from(Event::new().alarm(Alarm::audio(trigger)).done());
+        assert!(calendar.diff(&calendar).is_empty());
+    }
+}
@@ -0,0 +1,639 @@
+//! Time-range index over the occurrences of calendar components
+
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{components::recurrence, Calendar, CalendarComponent, Component, DatePerhapsTime};
+
+/// Identifies a component inside a [`CalendarIndex`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryId(u64);
+
+/// An occurrence found in a [`CalendarIndex`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IndexedOccurrence<'i> {
+    /// Identifies the component within the index
+    pub id: EntryId,
+    /// The component this is an occurrence of
+    pub component: &'i CalendarComponent,
+    /// Start of this occurrence in UTC
+    pub start: DateTime<Utc>,
+    /// End of this occurrence in UTC, equal to the start for instantaneous components
+    pub end: DateTime<Utc>,
+}
+
+type Key = (DateTime<Utc>, EntryId, u32);
+type Link = Option<Box<Node>>;
+
+/// Node of a treap ordered by start and augmented with the latest end of its subtree.
+#[derive(Clone, Debug)]
+struct Node {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    id: EntryId,
+    seq: u32,
+    priority: u64,
+    max_end: DateTime<Utc>,
+    left: Link,
+    right: Link,
+}
+
+impl Node {
+    fn key(&self) -> Key {
+        (self.start, self.id, self.seq)
+    }
+
+    fn update(&mut self) {
+        self.max_end = [&self.left, &self.right]
+            .into_iter()
+            .flatten()
+            .map(|child| child.max_end)
+            .fold(self.end, DateTime::max);
+    }
+}
+
+/// Splits into nodes before `key` and nodes at or after `key`.
+fn split(link: Link, key: &Key) -> (Link, Link) {
+    match link {
+        None => (None, None),
+        Some(mut node) if node.key() < *key => {
+            let (left, right) = split(node.right.take(), key);
+            node.right = left;
+            node.update();
+            (Some(node), right)
+        }
+        Some(mut node) => {
+            let (left, right) = split(node.left.take(), key);
+            node.left = right;
+            node.update();
+            (left, Some(node))
+        }
+    }
+}
+
+fn merge(left: Link, right: Link) -> Link {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+fn insert(link: Link, mut node: Box<Node>) -> Box<Node> {
+    match link {
+        None => node,
+        Some(root) if node.priority > root.priority => {
+            let (left, right) = split(Some(root), &node.key());
+            node.left = left;
+            node.right = right;
+            node.update();
+            node
+        }
+        Some(mut root) => {
+            if node.key() < root.key() {
+                root.left = Some(insert(root.left.take(), node));
+            } else {
+                root.right = Some(insert(root.right.take(), node));
+            }
+            root.update();
+            root
+        }
+    }
+}
+
+fn remove(link: &mut Link, key: &Key) {
+    if let Some(node) = link {
+        match key.cmp(&node.key()) {
+            Ordering::Less => remove(&mut node.left, key),
+            Ordering::Greater => remove(&mut node.right, key),
+            Ordering::Equal => {
+                let (left, right) = (node.left.take(), node.right.take());
+                *link = merge(left, right);
+                return;
+            }
+        }
+        node.update();
+    }
+}
+
+/// Collects nodes overlapping `start..end` in order, instantaneous ones count if they lie within.
+fn overlapping<'n>(
+    link: &'n Link,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    out: &mut Vec<&'n Node>,
+) {
+    if let Some(node) = link {
+        if node.max_end < start {
+            return;
+        }
+        overlapping(&node.left, start, end, out);
+        if node.start >= end {
+            return;
+        }
+        if node.end > start || node.start >= start {
+            out.push(node);
+        }
+        overlapping(&node.right, start, end, out);
+    }
+}
+
+/// Collects up to `limit` nodes starting at or after `after` in order.
+fn starting_from<'n>(link: &'n Link, after: DateTime<Utc>, limit: usize, out: &mut Vec<&'n Node>) {
+    if let Some(node) = link {
+        if out.len() >= limit {
+            return;
+        }
+        if node.start >= after {
+            starting_from(&node.left, after, limit, out);
+            if out.len() < limit {
+                out.push(node);
+            }
+        }
+        starting_from(&node.right, after, limit, out);
+    }
+}
+
+/// Expands a component into its occurrences within `horizon_start..horizon_end` in UTC,
+/// leaving out the `overridden` ones.
+fn expand(
+    component: &CalendarComponent,
+    overridden: Vec<DatePerhapsTime>,
+    horizon_start: DateTime<Utc>,
+    horizon_end: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let view = component.occurrence_view(overridden);
+    match view.get_start() {
+        Some(dtstart) => {
+            let length = recurrence::length(&view, &dtstart, &Utc)
+                .and_then(|length| length.try_to_chrono())
+                .unwrap_or_else(Duration::zero)
+                .max(Duration::zero());
+            recurrence::occurrence_starts(&view, &Utc)
+                .take_while(|(start, _)| *start < horizon_end)
+                .filter_map(|(start, _)| {
+                    let end = start.checked_add_signed(length)?;
+                    (end > horizon_start || start >= horizon_start).then(|| (start, end))
+                })
+                .collect()
+        }
+        // todos without a start take place at their due date
+        None => view
+            .properties()
+            .get("DUE")
+            .and_then(DatePerhapsTime::from_property)
+            .as_ref()
+            .and_then(recurrence::instant)
+            .filter(|due| horizon_start <= *due && *due < horizon_end)
+            .map(|due| vec![(due, due)])
+            .unwrap_or_default(),
+    }
+}
+
+/// Answers "what is happening between T1 and T2" without scanning every component.
+///
+/// Occurrences are expanded once within a horizon, since recurrences may go on forever,
+/// and kept in an interval tree in UTC. Floating times are treated as UTC.
+/// Occurrences replaced by a component with the same `UID` and a `RECURRENCE-ID` are left out.
+///
+/// ```
+/// # use icalendar::*;
+/// # use chrono::*;
+/// let monday = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+/// let calendar = Calendar::new()
+///     .push(
+///         Event::new()
+///             .summary("standup")
+///             .starts(monday + Duration::hours(9))
+///             .ends(monday + Duration::hours(9) + Duration::minutes(15))
+///             .repeats(RecurrenceRule::new(Frequency::Daily))
+///             .done(),
+///     )
+///     .push(
+///         Event::new()
+///             .summary("lunch")
+///             .starts(monday + Duration::hours(12))
+///             .ends(monday + Duration::hours(13))
+///             .done(),
+///     )
+///     .done();
+///
+/// let mut index = CalendarIndex::from_calendar(&calendar, monday, monday + Duration::days(365));
+/// assert_eq!(index.overlapping(monday, monday + Duration::days(1)).len(), 2);
+/// assert_eq!(index.overlapping(monday + Duration::days(1), monday + Duration::days(8)).len(), 7);
+///
+/// let id = index.push(Event::new().summary("review").starts(monday + Duration::hours(10)).done());
+/// let next = index.next(monday + Duration::hours(9) + Duration::minutes(1), 2);
+/// assert_eq!(next[0].component.as_event().unwrap().get_summary(), Some("review"));
+/// assert_eq!(next[1].component.as_event().unwrap().get_summary(), Some("lunch"));
+///
+/// index.remove(id);
+/// assert_eq!(index.at(monday + Duration::hours(10)).len(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CalendarIndex {
+    horizon_start: DateTime<Utc>,
+    horizon_end: DateTime<Utc>,
+    root: Link,
+    entries: BTreeMap<EntryId, (CalendarComponent, Vec<Key>)>,
+    uids: BTreeMap<String, Vec<EntryId>>,
+    next_id: u64,
+    seed: u64,
+}
+
+impl CalendarIndex {
+    /// Creates an empty index expanding occurrences within `horizon_start..horizon_end`.
+    pub fn new(horizon_start: DateTime<Utc>, horizon_end: DateTime<Utc>) -> Self {
+        CalendarIndex {
+            horizon_start,
+            horizon_end,
+            root: None,
+            entries: BTreeMap::new(),
+            uids: BTreeMap::new(),
+            next_id: 0,
+            seed: 0x853c_49e6_748f_ea9b,
+        }
+    }
+
+    /// Creates an index of all components of a calendar.
+    pub fn from_calendar(
+        calendar: &Calendar,
+        horizon_start: DateTime<Utc>,
+        horizon_end: DateTime<Utc>,
+    ) -> Self {
+        let mut index = CalendarIndex::new(horizon_start, horizon_end);
+        // add every component before expanding, so masters know their overrides up front
+        let ids = calendar
+            .components
+            .iter()
+            .map(|component| index.add_entry(component.clone()))
+            .collect::<Vec<_>>();
+        for id in ids {
+            index.index_occurrences(id);
+        }
+        index
+    }
+
+    /// Pseudo random treap priorities, splitmix64
+    fn next_priority(&mut self) -> u64 {
+        self.seed = self.seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Adds a component and all its occurrences within the horizon.
+    ///
+    /// Adding an override drops the occurrence it replaces from its master.
+    pub fn push(&mut self, component: impl Into<CalendarComponent>) -> EntryId {
+        let id = self.add_entry(component.into());
+        for master in self.masters_of(id) {
+            self.index_occurrences(master);
+        }
+        self.index_occurrences(id);
+        id
+    }
+
+    /// Removes a component and all its occurrences.
+    ///
+    /// Removing an override restores the occurrence it replaced in its master.
+    pub fn remove(&mut self, id: EntryId) -> Option<CalendarComponent> {
+        let masters = self.masters_of(id);
+        self.remove_occurrences(id);
+        let (component, _) = self.entries.remove(&id)?;
+        if let Some(uid) = component.uid() {
+            if let Some(ids) = self.uids.get_mut(uid) {
+                ids.retain(|other| *other != id);
+                if ids.is_empty() {
+                    self.uids.remove(uid);
+                }
+            }
+        }
+        for master in masters {
+            self.index_occurrences(master);
+        }
+        Some(component)
+    }
+
+    /// Stores a component without indexing its occurrences yet.
+    fn add_entry(&mut self, component: CalendarComponent) -> EntryId {
+        let id = EntryId(self.next_id);
+        self.next_id += 1;
+        if let Some(uid) = component.uid() {
+            self.uids.entry(uid.to_owned()).or_default().push(id);
+        }
+        self.entries.insert(id, (component, Vec::new()));
+        id
+    }
+
+    /// Entries with the same kind and `UID` as `id`, excluding `id` itself.
+    fn siblings(&self, id: EntryId) -> Vec<(EntryId, &CalendarComponent)> {
+        let component = match self.get(id) {
+            Some(component) => component,
+            None => return Vec::new(),
+        };
+        let kind = component.component_kind();
+        component
+            .uid()
+            .and_then(|uid| self.uids.get(uid))
+            .into_iter()
+            .flatten()
+            .filter(|sibling| **sibling != id)
+            .filter_map(|sibling| Some((*sibling, self.get(*sibling)?)))
+            .filter(|(_, sibling)| sibling.component_kind() == kind)
+            .collect()
+    }
+
+    /// Masters whose occurrences the override `id` replaces, none if `id` is no override.
+    fn masters_of(&self, id: EntryId) -> Vec<EntryId> {
+        match self.get(id) {
+            Some(component) if component.property_value("RECURRENCE-ID").is_some() => self
+                .siblings(id)
+                .into_iter()
+                .filter(|(_, sibling)| sibling.property_value("RECURRENCE-ID").is_none())
+                .map(|(sibling, _)| sibling)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// `RECURRENCE-ID`s of the overrides replacing occurrences of the master `id`.
+    fn overridden(&self, id: EntryId) -> Vec<DatePerhapsTime> {
+        match self.get(id) {
+            Some(component) if component.property_value("RECURRENCE-ID").is_none() => self
+                .siblings(id)
+                .into_iter()
+                .filter_map(|(_, sibling)| sibling.inner().properties.get("RECURRENCE-ID"))
+                .filter_map(DatePerhapsTime::from_property)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Expands an entry again, replacing the occurrences indexed before.
+    fn index_occurrences(&mut self, id: EntryId) {
+        let occurrences = match self.get(id) {
+            Some(component) => expand(
+                component,
+                self.overridden(id),
+                self.horizon_start,
+                self.horizon_end,
+            ),
+            None => return,
+        };
+        self.remove_occurrences(id);
+
+        let mut keys = Vec::new();
+        for (seq, (start, end)) in (0..).zip(occurrences) {
+            let node = Box::new(Node {
+                start,
+                end,
+                id,
+                seq,
+                priority: self.next_priority(),
+                max_end: end,
+                left: None,
+                right: None,
+            });
+            keys.push(node.key());
+            self.root = Some(insert(self.root.take(), node));
+        }
+        if let Some((_, indexed)) = self.entries.get_mut(&id) {
+            *indexed = keys;
+        }
+    }
+
+    fn remove_occurrences(&mut self, id: EntryId) {
+        let keys = match self.entries.get_mut(&id) {
+            Some((_, keys)) => std::mem::take(keys),
+            None => return,
+        };
+        for key in &keys {
+            remove(&mut self.root, key);
+        }
+    }
+
+    /// Gets an indexed component.
+    pub fn get(&self, id: EntryId) -> Option<&CalendarComponent> {
+        self.entries.get(&id).map(|(component, _)| component)
+    }
+
+    /// Iterates over all indexed components.
+    pub fn components(&self) -> impl Iterator<Item = (EntryId, &CalendarComponent)> {
+        self.entries
+            .iter()
+            .map(|(id, (component, _))| (*id, component))
+    }
+
+    /// Number of indexed components.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no components are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn resolve<'i>(&'i self, nodes: Vec<&Node>) -> Vec<IndexedOccurrence<'i>> {
+        nodes
+            .into_iter()
+            .filter_map(|node| {
+                Some(IndexedOccurrence {
+                    id: node.id,
+                    component: self.get(node.id)?,
+                    start: node.start,
+                    end: node.end,
+                })
+            })
+            .collect()
+    }
+
+    /// All occurrences overlapping `start..end`, ordered by their start.
+    pub fn overlapping(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<IndexedOccurrence<'_>> {
+        let mut nodes = Vec::new();
+        overlapping(&self.root, start, end, &mut nodes);
+        self.resolve(nodes)
+    }
+
+    /// All occurrences taking place at `point`, ordered by their start.
+    pub fn at(&self, point: DateTime<Utc>) -> Vec<IndexedOccurrence<'_>> {
+        match point.checked_add_signed(Duration::nanoseconds(1)) {
+            Some(end) => self.overlapping(point, end),
+            None => Vec::new(),
+        }
+    }
+
+    /// The next `count` occurrences starting at or after `after`.
+    pub fn next(&self, after: DateTime<Utc>, count: usize) -> Vec<IndexedOccurrence<'_>> {
+        let mut nodes = Vec::new();
+        starting_from(&self.root, after, count, &mut nodes);
+        self.resolve(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Event, EventLike, Frequency, RecurrenceRule, Todo};
+
+    fn at(d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, d, h, 0, 0).unwrap()
+    }
+
+    fn index() -> CalendarIndex {
+        CalendarIndex::new(at(1, 0), at(31, 0))
+    }
+
+    fn starts(occurrences: Vec<IndexedOccurrence<'_>>) -> Vec<DateTime<Utc>> {
+        occurrences.into_iter().map(|o| o.start).collect()
+    }
+
+    #[test]
+    fn recurring_within_horizon() {
+        let mut index = index();
+        index.push(
+            Event::new()
+                .starts(at(1, 9))
+                .ends(at(1, 10))
+                .repeats(RecurrenceRule::new(Frequency::Weekly))
+                .done(),
+        );
+        assert_eq!(
+            starts(index.overlapping(at(1, 0), at(31, 0))),
+            [at(1, 9), at(8, 9), at(15, 9), at(22, 9), at(29, 9)]
+        );
+        assert_eq!(starts(index.at(at(8, 9))), [at(8, 9)]);
+        assert!(index.at(at(8, 10)).is_empty());
+        assert_eq!(starts(index.next(at(8, 10), 2)), [at(15, 9), at(22, 9)]);
+    }
+
+    #[test]
+    fn instantaneous_and_all_day() {
+        let mut index = index();
+        index.push(Event::new().starts(at(5, 12)).done());
+        index.push(Todo::new().due(at(6, 12)).done());
+        index.push(
+            Event::new()
+                .all_day(chrono::NaiveDate::from_ymd_opt(2024, 1, 7).unwrap())
+                .done(),
+        );
+        assert_eq!(starts(index.at(at(5, 12))), [at(5, 12)]);
+        assert!(index.overlapping(at(6, 0), at(6, 12)).is_empty());
+        assert_eq!(starts(index.overlapping(at(6, 0), at(6, 13))), [at(6, 12)]);
+        assert_eq!(starts(index.at(at(7, 23))), [at(7, 0)]);
+    }
+
+    #[test]
+    fn push_and_remove() {
+        let mut index = index();
+        let first = index.push(Event::new().starts(at(2, 9)).ends(at(2, 17)).done());
+        let second = index.push(Event::new().starts(at(2, 12)).ends(at(2, 13)).done());
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.at(at(2, 12)).len(), 2);
+
+        assert!(index.remove(first).is_some());
+        assert!(index.remove(first).is_none());
+        let remaining = index.at(at(2, 12));
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining.first().unwrap().id, second);
+        assert_eq!(index.at(at(2, 10)).len(), 0);
+    }
+
+    #[test]
+    fn overrides_replace_occurrences() {
+        let master = Event::new()
+            .uid("standup")
+            .starts(at(1, 9))
+            .ends(at(1, 10))
+            .repeats(RecurrenceRule::new(Frequency::Daily).count(3))
+            .done();
+        let moved = Event::new()
+            .uid("standup")
+            .add_property("RECURRENCE-ID", "20240102T090000Z")
+            .starts(at(2, 15))
+            .ends(at(2, 16))
+            .done();
+        let expected = [at(1, 9), at(2, 15), at(3, 9)];
+
+        let calendar = Calendar::new()
+            .push(master.clone())
+            .push(moved.clone())
+            .done();
+        let index = CalendarIndex::from_calendar(&calendar, at(1, 0), at(31, 0));
+        assert_eq!(starts(index.overlapping(at(1, 0), at(31, 0))), expected);
+
+        // the order of pushing makes no difference
+        let mut index = self::index();
+        let override_id = index.push(moved);
+        let master_id = index.push(master);
+        assert_eq!(starts(index.overlapping(at(1, 0), at(31, 0))), expected);
+
+        index.remove(override_id);
+        assert_eq!(
+            starts(index.overlapping(at(1, 0), at(31, 0))),
+            [at(1, 9), at(2, 9), at(3, 9)]
+        );
+        index.remove(master_id);
+        assert!(index.is_empty());
+        assert!(index.overlapping(at(1, 0), at(31, 0)).is_empty());
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        // pseudo random intervals of up to a day within January
+        let mut seed = 42_u64;
+        let mut random = move |below: i64| {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            i64::try_from(seed >> 33).unwrap() % below
+        };
+        let mut index = index();
+        let mut intervals = Vec::new();
+        for _ in 0..500 {
+            let start = at(1, 0) + Duration::minutes(random(60 * 24 * 29));
+            let end = start + Duration::minutes(random(60 * 24));
+            let id = index.push(Event::new().starts(start).ends(end).done());
+            intervals.push((id, start, end));
+        }
+        for (id, _, _) in intervals.iter().filter(|(id, _, _)| id.0 % 3 == 0) {
+            index.remove(*id);
+        }
+        intervals.retain(|(id, _, _)| id.0 % 3 != 0);
+
+        for _ in 0..200 {
+            let start = at(1, 0) + Duration::minutes(random(60 * 24 * 30));
+            let end = start + Duration::minutes(random(60 * 24 * 3));
+            let mut expected = intervals
+                .iter()
+                .filter(|(_, s, e)| *s < end && (*e > start || *s >= start))
+                .map(|(id, s, _)| (*s, *id))
+                .collect::<Vec<_>>();
+            expected.sort();
+            let found = index
+                .overlapping(start, end)
+                .into_iter()
+                .map(|o| (o.start, o.id))
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Merging calendars without duplicating components
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use chrono::{DateTime, Utc};
+
+use super::ComponentKey;
+use crate::{
+    components::{date_time::parse_utc_date_time, InnerComponent},
+    properties::MULTI_PROPERTIES,
+    Calendar, CalendarComponent, Property,
+};
+
+/// Which version a merge keeps
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Winner {
+    /// The version already in the calendar that is merged into
+    Ours,
+    /// The version from the calendar that is merged in
+    Theirs,
+}
+
+/// Decides which of two versions of the same component [`Calendar::merge_with()`] keeps.
+///
+/// Closures taking both components implement this trait.
+pub trait MergeStrategy {
+    /// Picks between two differing components with the same `UID` and `RECURRENCE-ID`.
+    fn resolve(&self, ours: &CalendarComponent, theirs: &CalendarComponent) -> Winner;
+}
+
+impl<F> MergeStrategy for F
+where
+    F: Fn(&CalendarComponent, &CalendarComponent) -> Winner,
+{
+    fn resolve(&self, ours: &CalendarComponent, theirs: &CalendarComponent) -> Winner {
+        self(ours, theirs)
+    }
+}
+
+/// Keeps the most recent revision of a component.
+///
+/// The higher [`SEQUENCE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.4) wins,
+/// then the later [`LAST-MODIFIED`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.3),
+/// then the later [`DTSTAMP`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.2).
+/// Ties keep our version.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LatestRevision;
+
+fn revision(inner: &InnerComponent) -> (u32, Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let value = |key| inner.properties.get(key).map(Property::value);
+    (
+        value("SEQUENCE")
+            .and_then(|sequence| sequence.parse().ok())
+            .unwrap_or_default(),
+        value("LAST-MODIFIED").and_then(parse_utc_date_time),
+        value("DTSTAMP").and_then(parse_utc_date_time),
+    )
+}
+
+impl MergeStrategy for LatestRevision {
+    fn resolve(&self, ours: &CalendarComponent, theirs: &CalendarComponent) -> Winner {
+        match revision(theirs.inner()).cmp(&revision(ours.inner())) {
+            Ordering::Greater => Winner::Theirs,
+            Ordering::Less | Ordering::Equal => Winner::Ours,
+        }
+    }
+}
+
+/// A difference between the merged calendars that had to be resolved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// Both calendars contain differing versions of a component
+    Component {
+        /// Identifies the component
+        key: ComponentKey,
+        /// The version that was kept
+        winner: Winner,
+    },
+    /// Both calendars define a `VTIMEZONE` with the same `TZID` differently,
+    /// resolved by [`LatestRevision`]
+    Timezone {
+        /// The `TZID` of both definitions
+        tzid: String,
+        /// The definition that was kept
+        winner: Winner,
+    },
+    /// Both calendars have a different value for a calendar property, ours is kept
+    Property {
+        /// Name of the property, like `PRODID`
+        name: String,
+        /// Our value, which was kept
+        ours: String,
+        /// Their value, which was dropped
+        theirs: String,
+    },
+}
+
+/// Outcome of [`Calendar::merge()`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Number of components that were only in their calendar
+    pub added: usize,
+    /// Number of components that were identical in both calendars
+    pub duplicates: usize,
+    /// Conflicts that were resolved
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Remembers where a component with a key or `TZID` is, unless an earlier one has the same.
+fn index(
+    by_key: &mut HashMap<ComponentKey, usize>,
+    by_tzid: &mut HashMap<String, usize>,
+    component: &CalendarComponent,
+    position: usize,
+) {
+    match ComponentKey::of(component.component_kind(), component.inner()) {
+        Some(key) => {
+            by_key.entry(key).or_insert(position);
+        }
+        None => {
+            if let Some(tzid) = tzid(component) {
+                by_tzid.entry(tzid.to_owned()).or_insert(position);
+            }
+        }
+    }
+}
+
+fn tzid(component: &CalendarComponent) -> Option<&str> {
+    if component.component_kind() != "VTIMEZONE" {
+        return None;
+    }
+    component
+        .inner()
+        .properties
+        .get("TZID")
+        .map(Property::value)
+}
+
+impl Calendar {
+    /// Merges another export of the same calendar into this one, keeping the latest revisions.
+    ///
+    /// See [`Calendar::merge_with()`] for details.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut ours = Calendar::new()
+    ///     .push(Event::new().uid("standup").summary("Standup").sequence(1).done())
+    ///     .push(Event::new().uid("lunch").summary("Lunch").done())
+    ///     .done();
+    /// let theirs = Calendar::new()
+    ///     .push(Event::new().uid("standup").summary("Daily standup").sequence(2).done())
+    ///     .push(Event::new().uid("lunch").summary("Lunch").done())
+    ///     .done();
+    ///
+    /// let report = ours.merge(theirs);
+    /// assert_eq!(ours.len(), 2);
+    /// assert_eq!(ours[0].as_event().unwrap().get_summary(), Some("Daily standup"));
+    /// assert_eq!(report.duplicates, 1);
+    /// assert_eq!(report.conflicts.len(), 1);
+    /// ```
+    pub fn merge(&mut self, other: Calendar) -> MergeReport {
+        self.merge_with(other, LatestRevision)
+    }
+
+    /// Merges another calendar into this one, resolving conflicts with the given strategy.
+    ///
+    /// Components are deduplicated by kind, `UID` and `RECURRENCE-ID`, time zones by `TZID`
+    /// and components without `UID` only if they are identical.
+    /// Calendar properties that are missing here are taken over,
+    /// for properties that may only occur once our differing value is kept.
+    pub fn merge_with(&mut self, other: Calendar, strategy: impl MergeStrategy) -> MergeReport {
+        let mut report = MergeReport::default();
+
+        for theirs in other.properties {
+            let ours = self
+                .properties
+                .iter()
+                .find(|ours| ours.key() == theirs.key());
+            match ours {
+                _ if self.properties.contains(&theirs) => {}
+                Some(ours) if !MULTI_PROPERTIES.contains(&ours.key()) => {
+                    report.conflicts.push(MergeConflict::Property {
+                        name: ours.key().to_owned(),
+                        ours: ours.value().to_owned(),
+                        theirs: theirs.value().to_owned(),
+                    });
+                }
+                _ => self.properties.push(theirs),
+            }
+        }
+
+        let mut by_key = HashMap::new();
+        let mut by_tzid = HashMap::new();
+        for (position, ours) in self.components.iter().enumerate() {
+            index(&mut by_key, &mut by_tzid, ours, position);
+        }
+
+        for theirs in other.components {
+            let key = ComponentKey::of(theirs.component_kind(), theirs.inner());
+            let position = match (&key, tzid(&theirs)) {
+                (Some(key), _) => by_key.get(key).copied(),
+                (None, Some(theirs)) => by_tzid.get(theirs).copied(),
+                (None, None) => self.components.iter().position(|ours| *ours == theirs),
+            };
+            let ours = match position.and_then(|position| self.components.get_mut(position)) {
+                Some(ours) => ours,
+                None => {
+                    index(&mut by_key, &mut by_tzid, &theirs, self.components.len());
+                    self.components.push(theirs);
+                    report.added += 1;
+                    continue;
+                }
+            };
+            if *ours == theirs {
+                report.duplicates += 1;
+                continue;
+            }
+
+            let (winner, conflict) = match (key, tzid(&theirs)) {
+                (Some(key), _) => {
+                    let winner = strategy.resolve(ours, &theirs);
+                    (winner, MergeConflict::Component { key, winner })
+                }
+                (None, tzid) => {
+                    let winner = LatestRevision.resolve(ours, &theirs);
+                    let tzid = tzid.unwrap_or_default().to_owned();
+                    (winner, MergeConflict::Timezone { tzid, winner })
+                }
+            };
+            if winner == Winner::Theirs {
+                *ours = theirs;
+            }
+            report.conflicts.push(conflict);
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{components::Other, Component, Event, Todo};
+
+    fn timezone(offset: &str) -> CalendarComponent {
+        let mut standard = Other::from(("STANDARD".to_owned(), InnerComponent::default()));
+        standard
+            .add_property("DTSTART", "19701025T030000")
+            .add_property("TZOFFSETFROM", "+0200")
+            .add_property("TZOFFSETTO", offset);
+        Other::from(("VTIMEZONE".to_owned(), InnerComponent::default()))
+            .add_property("TZID", "Europe/Berlin")
+            .append_component(standard)
+            .clone()
+            .into()
+    }
+
+    #[test]
+    fn deduplicates_exports() {
+        let export = || {
+            Calendar::new()
+                .push(timezone("+0100"))
+                .push(Event::new().uid("a").summary("A").done())
+                .push(
+                    Event::new()
+                        .uid("a")
+                        .add_property("RECURRENCE-ID", "20240108T090000Z")
+                        .summary("A, moved")
+                        .done(),
+                )
+                .push(Todo::new().summary("no uid").done())
+                .done()
+        };
+        let mut calendar = export();
+        let report = calendar.merge(export());
+        assert_eq!(calendar, export());
+        assert_eq!(
+            report,
+            MergeReport {
+                added: 0,
+                duplicates: 4,
+                conflicts: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn latest_revision_wins() {
+        let stamp = |day| Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap();
+        let mut ours = Calendar::from([
+            Event::new()
+                .uid("sequence")
+                .sequence(2)
+                .summary("ours")
+                .done(),
+            Event::new()
+                .uid("modified")
+                .last_modified(stamp(2))
+                .summary("ours")
+                .done(),
+            Event::new()
+                .uid("stamped")
+                .timestamp(stamp(1))
+                .summary("ours")
+                .done(),
+        ]);
+        let theirs = Calendar::from([
+            Event::new()
+                .uid("sequence")
+                .sequence(1)
+                .summary("theirs")
+                .done(),
+            Event::new()
+                .uid("modified")
+                .last_modified(stamp(3))
+                .summary("theirs")
+                .done(),
+            Event::new()
+                .uid("stamped")
+                .timestamp(stamp(2))
+                .summary("theirs")
+                .done(),
+            Event::new().uid("new").summary("theirs").done(),
+        ]);
+
+        let report = ours.merge(theirs);
+        let summaries = ours
+            .iter()
+            .filter_map(|component| component.as_event()?.get_summary())
+            .collect::<Vec<_>>();
+        assert_eq!(summaries, ["ours", "theirs", "theirs", "theirs"]);
+        assert_eq!(report.added, 1);
+        let winners = report
+            .conflicts
+            .iter()
+            .map(|conflict| match conflict {
+                MergeConflict::Component { key, winner } => (key.uid.as_str(), *winner),
+                other => panic!("unexpected conflict {:?}", other),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            winners,
+            [
+                ("sequence", Winner::Ours),
+                ("modified", Winner::Theirs),
+                ("stamped", Winner::Theirs)
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_strategy() {
+        let mut ours = Calendar::from(Event::new().uid("a").sequence(2).summary("ours").done());
+        let theirs = Calendar::from(Event::new().uid("a").summary("theirs").done());
+        ours.merge_with(theirs, |_: &CalendarComponent, _: &CalendarComponent| {
+            Winner::Theirs
+        });
+        assert_eq!(
+            ours.first().and_then(|c| c.as_event()?.get_summary()),
+            Some("theirs")
+        );
+    }
+
+    #[test]
+    fn timezones_and_calendar_properties() {
+        let mut ours = Calendar::new().push(timezone("+0100")).done();
+        let mut theirs = Calendar::empty()
+            .push(timezone("+0000"))
+            .append_property(("PRODID", "OTHER"))
+            .append_property(("X-WR-CALNAME", "Work"))
+            .done();
+        theirs.properties.push(Property::new("VERSION", "2.0"));
+
+        let report = ours.merge(theirs);
+        assert_eq!(ours.len(), 1);
+        assert_eq!(ours.first(), Some(&timezone("+0100")));
+        assert_eq!(ours.property_value("PRODID"), Some("ICALENDAR-RS"));
+        assert_eq!(ours.property_value("X-WR-CALNAME"), Some("Work"));
+        assert_eq!(
+            report.conflicts,
+            [
+                MergeConflict::Property {
+                    name: "PRODID".into(),
+                    ours: "ICALENDAR-RS".into(),
+                    theirs: "OTHER".into()
+                },
+                MergeConflict::Timezone {
+                    tzid: "Europe/Berlin".into(),
+                    winner: Winner::Ours
+                }
+            ]
+        );
+    }
+}
@@ -1,22 +1,40 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use uuid::Uuid;
 
-use std::{collections::BTreeMap, fmt, mem};
-
-use crate::properties::*;
+use std::{borrow::Cow, collections::BTreeMap, fmt, mem};
+
+use crate::{
+    period::Period,
+    properties::*,
+    typed::{
+        self, typed_property, Cardinality, Description, PercentComplete, Priority, Sequence,
+        Summary, TypedProperty, Uid, Url,
+    },
+    IcalDuration, RecurrenceRule, ValueType, WriteOptions,
+};
 use date_time::{format_utc_date_time, naive_date_to_property, parse_utc_date_time};
 
 pub mod alarm;
+mod canonical;
 pub(crate) mod date_time;
 mod event;
+mod free_busy;
+mod location;
 mod other;
+mod participant;
+pub(crate) mod recurrence;
+mod resource;
 mod todo;
 mod venue;
 
 use alarm::*;
 use date_time::{CalendarDateTime, DatePerhapsTime};
 pub use event::*;
+pub use free_busy::*;
+pub use location::*;
 pub use other::*;
+pub use participant::*;
+pub use resource::*;
 pub use todo::*;
 pub use venue::*;
 
@@ -60,11 +78,86 @@ impl InnerComponent {
             .or_insert(vec![property.to_owned()]);
         self
     }
+}
 
-    #[cfg(test)]
-    pub fn property_value(&self, key: &str) -> Option<&str> {
-        Some(self.properties.get(key)?.value())
-    }
+/// Iterates over all properties with the given key, regardless of whether they were added once or multiple times.
+pub(crate) fn properties_by_key<'c, C: Component + ?Sized>(
+    component: &'c C,
+    key: &'c str,
+) -> impl Iterator<Item = &'c Property> + 'c {
+    component
+        .properties()
+        .get(key)
+        .into_iter()
+        .chain(component.multi_properties().get(key).into_iter().flatten())
+}
+
+/// Collects the values of all list-valued properties with the given key.
+pub(crate) fn list_values<C: Component + ?Sized>(component: &C, key: &str) -> Vec<String> {
+    properties_by_key(component, key)
+        .flat_map(Property::values)
+        .collect()
+}
+
+/// Parses the dates of all list-valued properties with the given key, such as `EXDATE`.
+pub(crate) fn list_dates<C: Component + ?Sized>(component: &C, key: &str) -> Vec<DatePerhapsTime> {
+    properties_by_key(component, key)
+        .flat_map(|property| {
+            property.values().into_iter().filter_map(move |value| {
+                DatePerhapsTime::from_property(&Property {
+                    val: value,
+                    ..property.clone()
+                })
+            })
+        })
+        .collect()
+}
+
+/// Reads all [`ATTACH`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.1) properties, skipping undecodable ones.
+pub(crate) fn attachments<C: Component + ?Sized>(component: &C) -> Vec<Attachment> {
+    properties_by_key(component, "ATTACH")
+        .filter_map(|property| Attachment::try_from(property).ok())
+        .collect()
+}
+
+/// Reads all [`STRUCTURED-DATA`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.6) properties.
+pub(crate) fn structured_data<C: Component + ?Sized>(component: &C) -> Vec<StructuredData> {
+    properties_by_key(component, "STRUCTURED-DATA")
+        .filter_map(|property| StructuredData::try_from(property).ok())
+        .collect()
+}
+
+/// Reads all [`STYLED-DESCRIPTION`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.5) properties.
+pub(crate) fn styled_descriptions<C: Component + ?Sized>(component: &C) -> Vec<StyledDescription> {
+    properties_by_key(component, "STYLED-DESCRIPTION")
+        .filter_map(|property| StyledDescription::try_from(property).ok())
+        .collect()
+}
+
+/// Converts all child components of the given kind, like `PARTICIPANT` or `VLOCATION`.
+pub(crate) fn sub_components<C, T>(component: &C, kind: &str) -> Vec<T>
+where
+    C: Component + ?Sized,
+    T: From<InnerComponent>,
+{
+    component
+        .components()
+        .iter()
+        .filter(|child| child.component_kind() == kind)
+        .map(|child| T::from(child.inner.clone()))
+        .collect()
+}
+
+/// Copies any component into an [`Other`] of the same kind.
+pub(crate) fn to_other<C: Component + ?Sized>(component: &C) -> Other {
+    Other::from((
+        component.component_kind(),
+        InnerComponent {
+            properties: component.properties().clone(),
+            multi_properties: component.multi_properties().clone(),
+            components: component.components().to_vec(),
+        },
+    ))
 }
 
 /// Implemented by everything that goes into a `Calendar`
@@ -85,38 +178,90 @@ pub trait Component {
     /// Read-only access to `multi_properties`
     fn multi_properties(&self) -> &BTreeMap<String, Vec<Property>>;
 
+    /// Mutable access to the inner properties map.
+    fn properties_mut(&mut self) -> &mut BTreeMap<String, Property>;
+
+    /// Mutable access to `multi_properties`
+    fn multi_properties_mut(&mut self) -> &mut BTreeMap<String, Vec<Property>>;
+
+    /// Mutable access to the inner's child components, like nested alarms.
+    fn components_mut(&mut self) -> &mut Vec<Other>;
+
     /// Gets the value of a property.
+    ///
+    /// The key is case-insensitive.
     fn property_value(&self, key: &str) -> Option<&str> {
-        Some(self.properties().get(key)?.value())
+        Some(self.properties().get(&*normalize_name(key))?.value())
     }
 
-    /// Writes [`Component`] using [`std::fmt`].
-    fn fmt_write<W: fmt::Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
-        write_crlf!(out, "BEGIN:{}", self.component_kind())?;
-
-        if !self.properties().contains_key("DTSTAMP") {
-            let now = Utc::now();
-            write_crlf!(out, "DTSTAMP:{}", format_utc_date_time(now))?;
+    /// Sets a [`TypedProperty`], replacing it unless it may occur multiple times.
+    ///
+    /// This does not check [`TypedProperty::allowed_in()`].
+    ///
+    /// ```
+    /// # use icalendar::{*, typed::Priority};
+    /// let event = Event::new().set::<Priority>(1).done();
+    /// assert_eq!(event.get_priority(), Some(1));
+    /// ```
+    fn set<P: TypedProperty>(&mut self, value: P::Value) -> &mut Self {
+        let property = typed_property::<P>(value);
+        match P::CARDINALITY {
+            Cardinality::Once => self.append_property(property),
+            Cardinality::Many => self.append_multi_property(property),
         }
+    }
 
-        for property in self.properties().values() {
-            property.fmt_write(out)?;
-        }
+    /// Gets the value of a [`TypedProperty`], the first valid one if there are multiple.
+    fn get<P: TypedProperty>(&self) -> Option<P::Value> {
+        properties_by_key(self, P::NAME).find_map(P::from_property)
+    }
 
-        if !self.properties().contains_key("UID") {
-            write_crlf!(out, "UID:{}", Uuid::new_v4())?;
-        }
+    /// Gets all valid values of a [`TypedProperty`].
+    fn get_all<P: TypedProperty>(&self) -> Vec<P::Value> {
+        properties_by_key(self, P::NAME)
+            .filter_map(P::from_property)
+            .collect()
+    }
+
+    /// Writes [`Component`] using [`std::fmt`].
+    fn fmt_write<W: fmt::Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
+        self.fmt_write_with(out, &WriteOptions::default())
+    }
 
-        for property in self.multi_properties().values().flatten() {
-            property.fmt_write(out)?;
+    /// Writes [`Component`] using [`std::fmt`] as configured by [`WriteOptions`].
+    fn fmt_write_with<W: fmt::Write>(
+        &self,
+        out: &mut W,
+        options: &WriteOptions,
+    ) -> Result<(), fmt::Error> {
+        options.write_line(out, &format!("BEGIN:{}", self.component_kind()))?;
+
+        let missing = |key| options.get_inject_defaults() && !self.properties().contains_key(key);
+        let dtstamp =
+            missing("DTSTAMP").then(|| Property::new("DTSTAMP", format_utc_date_time(Utc::now())));
+        let uid = missing("UID").then(|| Property::new("UID", Uuid::new_v4().to_string()));
+        let mut properties = dtstamp
+            .map(Cow::Owned)
+            .into_iter()
+            .chain(self.properties().values().map(Cow::Borrowed))
+            .chain(uid.map(Cow::Owned))
+            .chain(
+                self.multi_properties()
+                    .values()
+                    .flatten()
+                    .map(Cow::Borrowed),
+            )
+            .collect::<Vec<_>>();
+        options.sort_component_properties(&mut properties);
+        for property in properties {
+            property.fmt_write_with(out, options)?;
         }
 
         for component in self.components() {
-            component.fmt_write(out)?;
+            component.fmt_write_with(out, options)?;
         }
 
-        write_crlf!(out, "END:{}", self.component_kind())?;
-        Ok(())
+        options.write_line(out, &format!("END:{}", self.component_kind()))
     }
 
     /// Serializes this component into [`rfc5545`](http://tools.ietf.org/html/rfc5545) again
@@ -135,6 +280,44 @@ pub trait Component {
         Ok(out_string)
     }
 
+    /// Serializes this component as configured by [`WriteOptions`]
+    ///
+    /// # Panic
+    /// this can panic if [`std::fmt::write`] returns an Error
+    fn to_string_with(&self, options: &WriteOptions) -> String {
+        let mut out_string = String::new();
+        self.fmt_write_with(&mut out_string, options).unwrap();
+        out_string
+    }
+
+    /// Serializes this component in a canonical form, for comparing content rather than bytes
+    ///
+    /// Volatile properties like `DTSTAMP` and `LAST-MODIFIED` are left out,
+    /// redundant `VALUE` parameters are dropped, date-times with a known `TZID` are converted to UTC,
+    /// and properties, parameters and child components are sorted.
+    /// Text is written with the escaping of [`WriteOptions::canonical()`] regardless of how it was read.
+    ///
+    /// # Panic
+    /// this can panic if [`std::fmt::write`] returns an Error
+    fn canonical_form(&self) -> String {
+        canonical::canonical_form(self).unwrap()
+    }
+
+    /// Stable 64 bit hash of the [`canonical_form()`](Component::canonical_form), e.g. for `ETag`s
+    ///
+    /// Identical events from different exports hash equally,
+    /// and the hash does not change between platforms or releases of this crate.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let first = Event::new().uid("lunch").summary("Lunch").done();
+    /// let second = Event::new().summary("Lunch").uid("lunch").done();
+    /// assert_eq!(first.content_hash(), second.content_hash());
+    /// ```
+    fn content_hash(&self) -> u64 {
+        canonical::fnv1a(self.canonical_form().as_bytes())
+    }
+
     /// Append a given [`Property`]
     fn append_property(&mut self, property: impl Into<Property>) -> &mut Self;
 
@@ -144,6 +327,68 @@ pub trait Component {
     /// Adds a [`Property`] of which there may be many
     fn append_multi_property(&mut self, property: impl Into<Property>) -> &mut Self;
 
+    /// Removes all properties with the given key, regardless of whether they were added once or multiple times.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut event = Event::new().summary("Lunch").location("Cafeteria").done();
+    /// assert_eq!(event.remove_property("LOCATION").len(), 1);
+    /// assert_eq!(event.get_location(), None);
+    /// ```
+    fn remove_property(&mut self, key: &str) -> Vec<Property> {
+        let key = &*normalize_name(key);
+        let mut removed = self
+            .properties_mut()
+            .remove(key)
+            .into_iter()
+            .collect::<Vec<_>>();
+        removed.extend(self.multi_properties_mut().remove(key).unwrap_or_default());
+        removed
+    }
+
+    /// Gets mutable access to a property, falling back to the first one added multiple times.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut event = Event::new().summary("Lunch").done();
+    /// if let Some(summary) = event.property_mut("SUMMARY") {
+    ///     summary.add_parameter("LANGUAGE", "en");
+    /// }
+    /// assert_eq!(event.properties()["SUMMARY"].params()["LANGUAGE"].value(), "en");
+    /// ```
+    fn property_mut(&mut self, key: &str) -> Option<&mut Property> {
+        let key = &*normalize_name(key);
+        if self.properties().contains_key(key) {
+            return self.properties_mut().get_mut(key);
+        }
+        self.multi_properties_mut().get_mut(key)?.first_mut()
+    }
+
+    /// Keeps only the properties with the given key that were added multiple times and satisfy the predicate.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut event = Event::new()
+    ///     .append_multi_property(("ATTENDEE", "mailto:alice@example.com"))
+    ///     .append_multi_property(("ATTENDEE", "mailto:bob@example.com"))
+    ///     .done();
+    /// event.retain_multi("ATTENDEE", |attendee| !attendee.value().contains("bob"));
+    /// assert_eq!(event.multi_properties()["ATTENDEE"].len(), 1);
+    /// ```
+    fn retain_multi<F>(&mut self, key: &str, f: F) -> &mut Self
+    where
+        F: FnMut(&Property) -> bool,
+    {
+        let key = &*normalize_name(key);
+        if let Some(properties) = self.multi_properties_mut().get_mut(key) {
+            properties.retain(f);
+            if properties.is_empty() {
+                self.multi_properties_mut().remove(key);
+            }
+        }
+        self
+    }
+
     /// Construct and append a [`Property`]
     fn add_property(&mut self, key: impl Into<String>, val: impl Into<String>) -> &mut Self {
         self.append_property(Property::new(key, val))
@@ -186,8 +431,7 @@ pub trait Component {
     ///
     /// Ranges from 0 to 10, larger values will be truncated
     fn priority(&mut self, priority: u32) -> &mut Self {
-        let priority = std::cmp::min(priority, 10);
-        self.add_property("PRIORITY", priority.to_string())
+        self.set::<Priority>(priority)
     }
 
     // /// Add the [`ATTACH`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.1) property
@@ -201,12 +445,7 @@ pub trait Component {
     ///
     /// Ranges from 0 to 10.
     fn get_priority(&self) -> Option<u32> {
-        let priority = self.property_value("PRIORITY")?.parse().ok()?;
-        if priority <= 10 {
-            Some(priority)
-        } else {
-            None
-        }
+        self.get::<Priority>()
     }
 
     /// Prints to stdout
@@ -219,7 +458,7 @@ pub trait Component {
 
     /// Set the summary
     fn summary(&mut self, desc: &str) -> &mut Self {
-        self.add_property("SUMMARY", desc)
+        self.set::<Summary>(desc.to_owned())
     }
 
     /// Gets the summary
@@ -229,7 +468,7 @@ pub trait Component {
 
     /// Set the description
     fn description(&mut self, desc: &str) -> &mut Self {
-        self.add_property("DESCRIPTION", desc)
+        self.set::<Description>(desc.to_owned())
     }
 
     /// Gets the description
@@ -245,7 +484,7 @@ pub trait Component {
 
     /// Set the UID
     fn uid(&mut self, uid: &str) -> &mut Self {
-        self.add_property("UID", uid)
+        self.set::<Uid>(uid.to_owned())
     }
 
     /// Gets the UID
@@ -255,27 +494,27 @@ pub trait Component {
 
     /// Set the sequence
     fn sequence(&mut self, sequence: u32) -> &mut Self {
-        self.add_property("SEQUENCE", sequence.to_string())
+        self.set::<Sequence>(sequence)
     }
 
     /// Gets the SEQUENCE
     fn get_sequence(&self) -> Option<u32> {
-        self.property_value("SEQUENCE").and_then(|s| s.parse().ok())
+        self.get::<Sequence>()
     }
 
     /// Set the visibility class
     fn class(&mut self, class: Class) -> &mut Self {
-        self.append_property(class)
+        self.set::<Class>(class)
     }
 
     /// Gets the visibility class
     fn get_class(&self) -> Option<Class> {
-        Class::from_str(self.property_value("CLASS")?)
+        self.get::<Class>()
     }
 
     /// Sets the URL.
     fn url(&mut self, url: &str) -> &mut Self {
-        self.add_property("URL", url)
+        self.set::<Url>(url.to_owned())
     }
 
     /// Gets the URL.
@@ -283,16 +522,21 @@ pub trait Component {
         self.property_value("URL")
     }
 
-    /// Set the [`LAST_MODIFIED`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.3) [`Property`]
+    /// Set the [`LAST-MODIFIED`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.3) [`Property`]
     ///
     /// This must be a UTC date-time value.
     fn last_modified(&mut self, dt: DateTime<Utc>) -> &mut Self {
-        self.add_property("LAST_MODIFIED", format_utc_date_time(dt))
+        self.add_property("LAST-MODIFIED", format_utc_date_time(dt))
     }
 
-    /// Gets the [`LAST_MODIFIED`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.3) property.
+    /// Gets the [`LAST-MODIFIED`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.3) property.
+    ///
+    /// Also reads `LAST_MODIFIED`, which older versions of this crate wrote by mistake.
     fn get_last_modified(&self) -> Option<DateTime<Utc>> {
-        parse_utc_date_time(self.property_value("LAST_MODIFIED")?)
+        parse_utc_date_time(
+            self.property_value("LAST-MODIFIED")
+                .or_else(|| self.property_value("LAST_MODIFIED"))?,
+        )
     }
 
     /// Set the [`CREATED`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.1) [`Property`]
@@ -348,7 +592,7 @@ pub trait EventLike: Component {
     /// Set the LOCATION
     /// 3.8.1.7.  Location
     fn location(&mut self, location: &str) -> &mut Self {
-        self.add_property("LOCATION", location)
+        self.set::<typed::LocationText>(location.to_owned())
     }
 
     /// Gets the location
@@ -356,12 +600,254 @@ pub trait EventLike: Component {
         self.property_value("LOCATION")
     }
 
+    /// Adds a [`CATEGORIES`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.2) property
+    ///
+    /// All categories are written into one property, commas inside of a category are escaped.
+    fn categories<I, S>(&mut self, categories: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.append_multi_property(Property::new_list("CATEGORIES", categories))
+    }
+
+    /// Gets all categories of all [`CATEGORIES`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.2) properties
+    fn get_categories(&self) -> Vec<String> {
+        list_values(self, "CATEGORIES")
+    }
+
+    /// Adds a [`RESOURCES`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.10) property
+    fn resources<I, S>(&mut self, resources: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.append_multi_property(Property::new_list("RESOURCES", resources))
+    }
+
+    /// Set the [`COLOR`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.9) [`Property`]
+    fn color(&mut self, color: Color) -> &mut Self {
+        self.append_property(color)
+    }
+
+    /// Gets the [`COLOR`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.9) property.
+    fn get_color(&self) -> Option<Color> {
+        self.property_value("COLOR")?.parse().ok()
+    }
+
+    /// Adds an [`IMAGE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.10) [`Property`]
+    fn image(&mut self, image: Image) -> &mut Self {
+        self.append_multi_property(image)
+    }
+
+    /// Gets all [`IMAGE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.10) properties
+    fn get_images(&self) -> Vec<Image> {
+        properties_by_key(self, "IMAGE")
+            .filter_map(|property| Image::try_from(property).ok())
+            .collect()
+    }
+
+    /// Adds a [`CONFERENCE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.11) [`Property`]
+    fn conference(&mut self, conference: Conference) -> &mut Self {
+        self.append_multi_property(conference)
+    }
+
+    /// Gets all [`CONFERENCE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.11) properties
+    fn get_conferences(&self) -> Vec<Conference> {
+        properties_by_key(self, "CONFERENCE")
+            .filter_map(|property| Conference::try_from(property).ok())
+            .collect()
+    }
+
+    /// Adds an [`ATTACH`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.1) [`Property`]
+    fn attach(&mut self, attachment: Attachment) -> &mut Self {
+        self.append_multi_property(attachment)
+    }
+
+    /// Gets all [`ATTACH`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.1) properties
+    fn get_attachments(&self) -> Vec<Attachment> {
+        attachments(self)
+    }
+
+    /// Gets all resources of all [`RESOURCES`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.10) properties
+    fn get_resources(&self) -> Vec<String> {
+        list_values(self, "RESOURCES")
+    }
+
+    /// Adds an [`EXDATE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.1) [`Property`]
+    ///
+    /// See [`DatePerhapsTime`] for info how are different [`chrono`] types converted automatically.
+    fn exdate<T: Into<DatePerhapsTime>>(&mut self, dt: T) -> &mut Self {
+        let calendar_dt = dt.into();
+        self.append_multi_property(calendar_dt.to_property("EXDATE"))
+    }
+
+    /// Gets all dates of all [`EXDATE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.1) properties
+    fn exdates(&self) -> Vec<DatePerhapsTime> {
+        list_dates(self, "EXDATE")
+    }
+
+    /// Adds an [`RDATE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.2) [`Property`]
+    ///
+    /// See [`DatePerhapsTime`] for info how are different [`chrono`] types converted automatically.
+    fn rdate<T: Into<DatePerhapsTime>>(&mut self, dt: T) -> &mut Self {
+        let calendar_dt = dt.into();
+        self.append_multi_property(calendar_dt.to_property("RDATE"))
+    }
+
+    /// Gets all dates of all [`RDATE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.2) properties
+    ///
+    /// Periods are skipped, see [`EventLike::rdate_periods()`].
+    fn rdates(&self) -> Vec<DatePerhapsTime> {
+        list_dates(self, "RDATE")
+    }
+
+    /// Adds an [`RDATE;VALUE=PERIOD`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.2) [`Property`]
+    fn rdate_period(&mut self, period: &Period) -> &mut Self {
+        for mut property in Period::to_properties("RDATE", [period]) {
+            self.append_multi_property(property.append_parameter(ValueType::Period).done());
+        }
+        self
+    }
+
+    /// Gets all periods of all [`RDATE;VALUE=PERIOD`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.2) properties
+    fn rdate_periods(&self) -> Vec<Period> {
+        properties_by_key(self, "RDATE")
+            .flat_map(Period::from_property)
+            .collect()
+    }
+
+    /// Sets the [`RRULE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3) [`Property`]
+    fn repeats(&mut self, rule: RecurrenceRule) -> &mut Self {
+        self.add_property("RRULE", rule.to_string())
+    }
+
+    /// Gets the [`RRULE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3) property
+    fn get_recurrence_rule(&self) -> Option<RecurrenceRule> {
+        self.property_value("RRULE")?.parse().ok()
+    }
+
+    /// Gets the starts of all occurrences within `window_start..window_end`, sorted.
+    ///
+    /// Combines `DTSTART`, [`RRULE`](EventLike::repeats), [`RDATE`](EventLike::rdate) and
+    /// [`EXDATE`](EventLike::exdate). Floating date-times and dates are treated as UTC,
+    /// occurrences in a timezone that can not be resolved (e.g. without the `chrono-tz` feature) are skipped.
+    /// [`Calendar::occurrences()`](crate::Calendar::occurrences) also knows about overrides and the local time zone.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let start = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    /// let event = Event::new()
+    ///     .starts(start)
+    ///     .repeats(RecurrenceRule::new(Frequency::Daily).count(5))
+    ///     .exdate(start + Duration::days(1))
+    ///     .done();
+    /// let occurrences = event.occurrences(start, start + Duration::days(3));
+    /// assert_eq!(occurrences, vec![start.into(), (start + Duration::days(2)).into()]);
+    /// ```
+    fn occurrences(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<DatePerhapsTime> {
+        recurrence::occurrences(self, window_start, window_end, &Utc)
+    }
+
+    /// Gets every instant an [`Alarm`] fires at within `window_start..window_end`, sorted.
+    ///
+    /// Relative triggers are resolved against each [occurrence](EventLike::occurrences), using
+    /// `DTEND`, `DUE` or `DURATION` for alarms related to the end. Repetitions by `REPEAT` and
+    /// `DURATION` are included, instants at or before `ACKNOWLEDGED` are skipped.
+    /// [`Calendar::alarm_times()`](crate::Calendar::alarm_times) also knows about overrides and the local time zone.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let start = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    /// let event = Event::new()
+    ///     .starts(start)
+    ///     .repeats(RecurrenceRule::new(Frequency::Weekly))
+    ///     .alarm(Alarm::display("standup", -Duration::minutes(10)))
+    ///     .done();
+    /// let times = event.alarm_times(start, start + Duration::weeks(2));
+    /// assert_eq!(times.len(), 2);
+    /// assert_eq!(times[0].0, start + Duration::weeks(1) - Duration::minutes(10));
+    /// ```
+    fn alarm_times(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, Alarm)> {
+        recurrence::alarm_times(self, window_start, window_end, &Utc)
+    }
+
     /// Set the ALARM for this event
     /// [3.6.6.  Alarm Component](https://datatracker.ietf.org/doc/html/rfc5545#section-3.6.6)
     fn alarm<A: Into<Alarm>>(&mut self, alarm: A) -> &mut Self {
         let alarm: Alarm = alarm.into();
         self.append_component(alarm)
     }
+
+    /// Gets all [`Alarm`]s, including those read by the parser
+    fn alarms(&self) -> Vec<Alarm> {
+        self.components()
+            .iter()
+            .filter_map(|component| Alarm::try_from(component.clone()).ok())
+            .collect()
+    }
+
+    /// Adds a [`STRUCTURED-DATA`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.6) [`Property`]
+    fn structured_data(&mut self, data: StructuredData) -> &mut Self {
+        self.append_multi_property(data)
+    }
+
+    /// Gets all [`STRUCTURED-DATA`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.6) properties
+    fn get_structured_data(&self) -> Vec<StructuredData> {
+        structured_data(self)
+    }
+
+    /// Adds a [`STYLED-DESCRIPTION`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.5) [`Property`]
+    fn styled_description(&mut self, description: StyledDescription) -> &mut Self {
+        self.append_multi_property(description)
+    }
+
+    /// Gets all [`STYLED-DESCRIPTION`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.5) properties
+    fn get_styled_descriptions(&self) -> Vec<StyledDescription> {
+        styled_descriptions(self)
+    }
+
+    /// Adds a [`PARTICIPANT`](https://datatracker.ietf.org/doc/html/rfc9073#section-7.1) component
+    fn participant(&mut self, participant: Participant) -> &mut Self {
+        self.append_component(participant)
+    }
+
+    /// Gets all [`PARTICIPANT`](https://datatracker.ietf.org/doc/html/rfc9073#section-7.1) components
+    fn get_participants(&self) -> Vec<Participant> {
+        sub_components(self, "PARTICIPANT")
+    }
+
+    /// Adds a [`VLOCATION`](https://datatracker.ietf.org/doc/html/rfc9073#section-7.2) component
+    ///
+    /// Unlike [`EventLike::location`] this describes the location in a structured way.
+    fn vlocation(&mut self, location: Location) -> &mut Self {
+        self.append_component(location)
+    }
+
+    /// Gets all [`VLOCATION`](https://datatracker.ietf.org/doc/html/rfc9073#section-7.2) components
+    fn get_vlocations(&self) -> Vec<Location> {
+        sub_components(self, "VLOCATION")
+    }
+
+    /// Adds a [`VRESOURCE`](https://datatracker.ietf.org/doc/html/rfc9073#section-7.3) component
+    fn vresource(&mut self, resource: Resource) -> &mut Self {
+        self.append_component(resource)
+    }
+
+    /// Gets all [`VRESOURCE`](https://datatracker.ietf.org/doc/html/rfc9073#section-7.3) components
+    fn get_vresources(&self) -> Vec<Resource> {
+        sub_components(self, "VRESOURCE")
+    }
 }
 
 macro_rules! event_impl {
@@ -395,6 +881,21 @@ macro_rules! component_impl {
                 &self.inner.multi_properties
             }
 
+            /// Mutable access to `properties`
+            fn properties_mut(&mut self) -> &mut BTreeMap<String, Property> {
+                &mut self.inner.properties
+            }
+
+            /// Mutable access to `multi_properties`
+            fn multi_properties_mut(&mut self) -> &mut BTreeMap<String, Vec<Property>> {
+                &mut self.inner.multi_properties
+            }
+
+            /// Mutable access to child `components`
+            fn components_mut(&mut self) -> &mut Vec<Other> {
+                &mut self.inner.components
+            }
+
             /// Adds a [`Property`]
             fn append_property(&mut self, property: impl Into<Property>) -> &mut Self {
                 let property = property.into();
@@ -444,14 +945,41 @@ component_impl! { Todo , String::from("VTODO")}
 event_impl! { Todo}
 
 component_impl! { Venue , String::from("VVENUE")}
+component_impl! { FreeBusy , String::from("VFREEBUSY")}
 component_impl! { Alarm, String::from("VALARM") }
 
+component_impl! { Participant, String::from("PARTICIPANT") }
+component_impl! { Location, String::from("VLOCATION") }
+component_impl! { Resource, String::from("VRESOURCE") }
+
 #[cfg(test)]
 mod tests {
     use chrono::TimeZone;
 
     use super::*;
 
+    #[test]
+    fn case_insensitive_names() {
+        let mut event = Event::new()
+            .add_property("x-room", "B")
+            .append_multi_property(
+                Property::new("attendee", "mailto:a@example.com")
+                    .add_parameter("cn", "A")
+                    .done(),
+            )
+            .done();
+        assert_eq!(event.property_value("X-ROOM"), Some("B"));
+        assert_eq!(event.property_value("x-Room"), Some("B"));
+        assert!(event.to_string().contains("X-ROOM:B\r\n"));
+        let attendee = event.property_mut("Attendee").unwrap();
+        assert_eq!(attendee.key(), "ATTENDEE");
+        assert_eq!(
+            attendee.get_param_as("Cn", |cn| Some(cn.to_owned())),
+            Some("A".into())
+        );
+        assert_eq!(event.remove_property("attendee").len(), 1);
+    }
+
     #[test]
     fn get_url() {
         let url = "http://hoodie.de/";
@@ -549,6 +1077,229 @@ mod tests {
         assert_eq!(event.get_end(), Some(date_time_tzid.into()));
     }
 
+    #[test]
+    fn get_list_properties() {
+        let event = Event::new()
+            .categories(["APPOINTMENT", "EDUCATION, SCIENCE"])
+            .categories(["MEETING"])
+            .resources(["EASEL", "PROJECTOR"])
+            .done();
+        assert_eq!(
+            event.get_categories(),
+            vec!["APPOINTMENT", "EDUCATION, SCIENCE", "MEETING"]
+        );
+        assert_eq!(event.get_resources(), vec!["EASEL", "PROJECTOR"]);
+    }
+
+    #[test]
+    fn get_exdates() {
+        let utc_date_time = Utc.with_ymd_and_hms(2001, 3, 13, 14, 15, 16).unwrap();
+        let naive_date = NaiveDate::from_ymd_opt(2001, 3, 14).unwrap();
+        let event = Event::new()
+            .exdate(utc_date_time)
+            .exdate(naive_date)
+            .append_multi_property(
+                Property::new_list("EXDATE", ["20010315T141516", "20010316T141516"])
+                    .add_parameter("TZID", "Europe/Berlin")
+                    .done(),
+            )
+            .rdate(naive_date)
+            .done();
+
+        let with_tz = |day| {
+            DatePerhapsTime::from(CalendarDateTime::WithTimezone {
+                date_time: NaiveDate::from_ymd_opt(2001, 3, day)
+                    .unwrap()
+                    .and_hms_opt(14, 15, 16)
+                    .unwrap(),
+                tzid: "Europe/Berlin".into(),
+            })
+        };
+        assert_eq!(
+            event.exdates(),
+            vec![
+                utc_date_time.into(),
+                naive_date.into(),
+                with_tz(15),
+                with_tz(16),
+            ]
+        );
+        assert_eq!(event.rdates(), vec![naive_date.into()]);
+    }
+
+    #[test]
+    fn get_rdate_periods() {
+        let start = Utc.with_ymd_and_hms(1996, 4, 3, 2, 0, 0).unwrap();
+        let period = Period::with_duration(start, chrono::Duration::hours(2)).unwrap();
+        let event = Event::new().rdate(start).rdate_period(&period).done();
+
+        assert_eq!(event.rdates(), vec![start.into()]);
+        assert_eq!(event.rdate_periods(), vec![period]);
+        assert!(event
+            .to_string()
+            .contains("RDATE;VALUE=PERIOD:19960403T020000Z/PT2H\r\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn reparse_list_properties() {
+        let event = Event::new()
+            .categories(["APPOINTMENT", "EDUCATION, SCIENCE"])
+            .done();
+        let reparsed = Other::from(
+            crate::parser::Component::<'_>::try_from(event.to_string().as_str()).unwrap(),
+        );
+
+        assert!(event
+            .to_string()
+            .contains("CATEGORIES:APPOINTMENT,EDUCATION\\, SCIENCE\r\n"));
+        assert_eq!(
+            list_values(&reparsed, "CATEGORIES"),
+            vec!["APPOINTMENT", "EDUCATION, SCIENCE"]
+        );
+        let categories = reparsed.multi_properties().get("CATEGORIES").unwrap();
+        assert_eq!(
+            categories.first().unwrap().value(),
+            "APPOINTMENT,EDUCATION, SCIENCE"
+        );
+    }
+
+    #[test]
+    fn list_property_value_is_unescaped() {
+        let event = Event::new()
+            .append_property(Property::new_list(
+                "RESOURCES",
+                ["EASEL", "PROJECTOR, large"],
+            ))
+            .done();
+        assert_eq!(
+            event.property_value("RESOURCES"),
+            Some("EASEL,PROJECTOR, large")
+        );
+        assert_eq!(event.get_resources(), vec!["EASEL", "PROJECTOR, large"]);
+        assert!(event
+            .to_string()
+            .contains("RESOURCES:EASEL,PROJECTOR\\, large\r\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn reparse_attachments() {
+        let icon = Attachment::Binary {
+            fmttype: Some("image/png".into()),
+            bytes: (0..=255).cycle().take(1000).collect(),
+        };
+        let event = Event::new()
+            .attach(Attachment::Uri("https://example.com/agenda.pdf".into()))
+            .attach(icon.clone())
+            .done();
+        let unfolded = crate::parser::unfold(&event.to_string());
+        let reparsed =
+            Other::from(crate::parser::Component::<'_>::try_from(unfolded.as_str()).unwrap());
+
+        assert_eq!(
+            attachments(&reparsed),
+            vec![
+                Attachment::Uri("https://example.com/agenda.pdf".into()),
+                icon
+            ]
+        );
+    }
+
+    #[test]
+    fn last_modified_key() {
+        let modified = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let event = Event::new().last_modified(modified).done();
+        assert!(event
+            .to_string()
+            .contains("LAST-MODIFIED:20240501T120000Z\r\n"));
+        assert_eq!(event.get_last_modified(), Some(modified));
+
+        let legacy = Event::new()
+            .add_property("LAST_MODIFIED", "20240501T120000Z")
+            .done();
+        assert_eq!(legacy.get_last_modified(), Some(modified));
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn reparse_rfc7986_properties() {
+        let conference = Conference::new("https://chat.example.com/audio?id=123456")
+            .feature(Feature::Audio)
+            .feature(Feature::Video)
+            .label("Attendee dial-in");
+        let image = Image::new("https://example.com/party.png").display(ImageDisplay::Thumbnail);
+        let event = Event::new()
+            .color("red".parse().unwrap())
+            .conference(conference.clone())
+            .image(image.clone())
+            .done();
+        let reparsed = event
+            .to_string()
+            .parse::<crate::CalendarComponent>()
+            .unwrap();
+        let reparsed = reparsed.as_event().unwrap();
+
+        assert_eq!(
+            reparsed.get_color().map(|color| color.as_str()),
+            Some("red")
+        );
+        assert_eq!(reparsed.get_conferences(), vec![conference]);
+        assert_eq!(reparsed.get_images(), vec![image]);
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn reparse_rfc9073_components() {
+        let speaker = Participant::new(ParticipantType::Speaker)
+            .uid("speaker")
+            .summary("Jane Doe")
+            .done();
+        let location = Location::new()
+            .uid("hall")
+            .name("Main Hall")
+            .location_type(["conference-center", "hall"])
+            .done();
+        let event = Event::new()
+            .participant(speaker.clone())
+            .vlocation(location.clone())
+            .vresource(Resource::new().uid("projector").done())
+            .styled_description(StyledDescription::Text {
+                fmttype: "text/html".into(),
+                text: "<p>Opening; with coffee</p>".into(),
+            })
+            .done();
+
+        let reparsed = event
+            .to_string()
+            .parse::<crate::CalendarComponent>()
+            .unwrap();
+        let reparsed = reparsed.as_event().unwrap();
+
+        // sub-components get a DTSTAMP when written, so compare what was set
+        let participants = reparsed.get_participants();
+        let participant = participants.first().unwrap();
+        assert_eq!(participant.get_uid(), speaker.get_uid());
+        assert_eq!(participant.get_summary(), Some("Jane Doe"));
+        assert_eq!(
+            participant.get_participant_type(),
+            Some(ParticipantType::Speaker)
+        );
+
+        let locations = reparsed.get_vlocations();
+        let reparsed_location = locations.first().unwrap();
+        assert_eq!(reparsed_location.get_name(), location.get_name());
+        assert_eq!(
+            reparsed_location.get_location_types(),
+            vec!["conference-center", "hall"]
+        );
+        assert_eq!(reparsed.get_vresources().len(), 1);
+        assert_eq!(
+            reparsed.get_styled_descriptions(),
+            event.get_styled_descriptions()
+        );
+    }
+
     #[test]
     fn get_dates_naive() {
         let naive_date = NaiveDate::from_ymd_opt(2001, 3, 13).unwrap();
@@ -1,7 +1,7 @@
 use chrono::Duration;
 use std::{collections::HashMap, fmt::Debug, str::FromStr};
 
-pub use self::properties::{Related, Trigger};
+pub use self::properties::{Action, Proximity, Related, Trigger};
 
 use self::properties::*;
 use super::*;
@@ -16,7 +16,7 @@ use super::*;
 /// ## Triggers
 ///
 /// An alarm can either be triggered at an absolute time or in relation to its event,
-/// so the Trigger can either be an absolute [`CalendarDateTime`] or a [`Duration`] plus a
+/// so the Trigger can either be an absolute [`CalendarDateTime`] or an [`IcalDuration`] plus a
 /// [relation tag](`Related`), which defines wether the duration is related to the events start or
 /// end.
 ///
@@ -27,7 +27,7 @@ use super::*;
 ///
 /// 1. Display
 /// 2. Audio
-/// 3. Email (not yet implemented)
+/// 3. Email
 ///
 /// ```rust
 ///  # use chrono::*;
@@ -105,6 +105,39 @@ impl Alarm {
             .done()
     }
 
+    /// Creates a new Audio-
+    /// [Alarm Component](https://datatracker.ietf.org/doc/html/rfc5545#section-3.6.6)
+    /// with an inline sound, which is written as base64 encoded `ATTACH`.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let alarm = Alarm::audio_with_sound(-Duration::minutes(5), "audio/basic", vec![0x2e, 0x73]);
+    /// assert_eq!(alarm.get_attachments()[0].as_bytes(), Some(&[0x2e, 0x73][..]));
+    /// ```
+    pub fn audio_with_sound<T: Into<Trigger>>(
+        trigger: T,
+        fmttype: &str,
+        bytes: impl Into<Vec<u8>>,
+    ) -> Self {
+        Alarm::audio(trigger).attach(Attachment::Binary {
+            fmttype: Some(fmttype.to_owned()),
+            bytes: bytes.into(),
+        })
+    }
+
+    /// Adds an [`ATTACH`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.1) property,
+    /// audio alarms should carry at most one.
+    pub fn attach(mut self, attachment: Attachment) -> Self {
+        self.append_multi_property(attachment);
+        self
+    }
+
+    /// Gets all [`ATTACH`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.1) properties of this [`Alarm`].
+    pub fn get_attachments(&self) -> Vec<Attachment> {
+        attachments(self)
+    }
+
     /// Creates a new Display-
     /// [Alarm Component](https://datatracker.ietf.org/doc/html/rfc5545#section-3.6.6)
     ///
@@ -135,10 +168,26 @@ impl Alarm {
     }
 
     /// Creates a new Email-
-    ///
     /// [Alarm Component](https://datatracker.ietf.org/doc/html/rfc5545#section-3.6.6)
     ///
-    /// TODO: this requires [Attendee support](https://www.rfc-editor.org/rfc/rfc5545#section-3.8.4.1)
+    /// The email is sent to every `attendee`, usually given as `mailto:` addresses,
+    /// attachments can be added with [`Alarm::attach`].
+    /// Returns [`None`] without attendees or with an empty one, an email alarm needs a recipient.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let alarm = Alarm::email(
+    ///     "The meeting starts in an hour",
+    ///     -Duration::hours(1),
+    ///     "Reminder",
+    ///     ["mailto:john@example.com", "mailto:jane@example.com"],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(alarm.get_action(), Some(Action::Email));
+    /// assert_eq!(alarm.get_summary(), Some("Reminder"));
+    /// assert_eq!(alarm.get_attendees(), ["mailto:john@example.com", "mailto:jane@example.com"]);
+    /// ```
     ///
     /// ## Definition
     ///
@@ -162,15 +211,45 @@ impl Alarm {
     ///  and MAY occur more than once.
     ///
     /// `attach / x-prop / iana-prop`
-    #[allow(dead_code)]
-    fn email(description: &str, trigger: impl Into<Trigger>, summary: &str) -> Self {
+    pub fn email<S: AsRef<str>>(
+        description: &str,
+        trigger: impl Into<Trigger>,
+        summary: &str,
+        attendees: impl IntoIterator<Item = S>,
+    ) -> Option<Self> {
+        let attendees = attendees.into_iter().collect::<Vec<_>>();
+        if attendees.is_empty() || attendees.iter().any(|a| a.as_ref().is_empty()) {
+            return None;
+        }
         let trigger: Trigger = trigger.into();
-        Alarm::default()
+        let mut alarm = Alarm::default()
             .append_property(Action::Email)
             .append_property(trigger)
             .add_property("DESCRIPTION", description)
             .add_property("SUMMARY", summary)
-            .done()
+            .done();
+        for attendee in attendees {
+            alarm = alarm.attendee(attendee.as_ref());
+        }
+        Some(alarm)
+    }
+
+    /// Adds an [`ATTENDEE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.1),
+    /// a recipient of an email alarm.
+    pub fn attendee(mut self, attendee: &str) -> Self {
+        self.append_multi_property(
+            Property::new("ATTENDEE", attendee)
+                .append_parameter(ValueType::CalAddress)
+                .done(),
+        );
+        self
+    }
+
+    /// Gets all [`ATTENDEE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.1)s of this [`Alarm`].
+    pub fn get_attendees(&self) -> Vec<String> {
+        properties_by_key(self, "ATTENDEE")
+            .map(|attendee| attendee.value().to_owned())
+            .collect()
     }
 
     /// Sets duration the
@@ -179,48 +258,168 @@ impl Alarm {
     /// which must not occur independent from one another
     pub fn duration_and_repeat<R: Copy + Clone + Into<Repeat>>(
         mut self,
-        duration: Duration,
+        duration: impl Into<IcalDuration>,
         repeat_count: R,
     ) -> Self {
         // self.add_property("ACTION", action.as_str());
-        self.append_property(duration);
+        self.append_property(duration.into());
 
         let repeat: Repeat = repeat_count.into();
         self.append_property(repeat);
         self
     }
 
-    /// Returns the get action of this [`Alarm`].
-    #[cfg(test)]
-    pub(self) fn get_action(&self) -> Option<Action> {
+    /// Sets the [`ACKNOWLEDGED`](https://datatracker.ietf.org/doc/html/rfc9074#section-6) property,
+    /// the time the user dismissed or snoozed this alarm.
+    pub fn acknowledged(mut self, dt: DateTime<Utc>) -> Self {
+        self.add_property("ACKNOWLEDGED", format_utc_date_time(dt));
+        self
+    }
+
+    /// Gets the [`ACKNOWLEDGED`](https://datatracker.ietf.org/doc/html/rfc9074#section-6) property.
+    pub fn get_acknowledged(&self) -> Option<DateTime<Utc>> {
+        parse_utc_date_time(self.property_value("ACKNOWLEDGED")?)
+    }
+
+    /// Creates a [snooze alarm](https://datatracker.ietf.org/doc/html/rfc9074#section-7) for this [`Alarm`].
+    ///
+    /// The snooze alarm triggers at `until`, does the same as this alarm and is linked to it through
+    /// `RELATED-TO;RELTYPE=SNOOZE`. Returns [`None`] if this alarm has no
+    /// [`UID`](https://datatracker.ietf.org/doc/html/rfc9074#section-4) to link to,
+    /// which is set with [`Component::uid()`].
+    /// Add it next to this alarm and mark this one as [`acknowledged`](Alarm::acknowledged).
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let alarm = Alarm::display("wake up", -Duration::minutes(5)).uid("wake-up").done();
+    /// let snooze = alarm.snooze(Utc::now() + Duration::minutes(10)).unwrap();
+    /// assert_eq!(snooze.get_snoozed_uid(), Some("wake-up"));
+    /// ```
+    pub fn snooze(&self, until: DateTime<Utc>) -> Option<Alarm> {
+        const NOT_INHERITED: [&str; 10] = [
+            "ACKNOWLEDGED",
+            "DEFAULT-ALARM",
+            "DURATION",
+            "PROXIMITY",
+            "RELATED-TO",
+            "REPEAT",
+            "TRIGGER",
+            "UID",
+            "X-APPLE-DEFAULT-ALARM",
+            "DTSTAMP",
+        ];
+        let snoozed_uid = self.get_uid()?;
+
+        let mut snooze = Alarm::default();
+        for property in self.properties().values() {
+            if !NOT_INHERITED.contains(&property.key()) {
+                snooze.append_property(property.clone());
+            }
+        }
+        for property in self.multi_properties().values().flatten() {
+            snooze.append_multi_property(property.clone());
+        }
+        Some(
+            snooze
+                .append_property(Trigger::from(until))
+                .add_property("UID", Uuid::new_v4().to_string())
+                .append_property(
+                    Property::new("RELATED-TO", snoozed_uid)
+                        .add_parameter("RELTYPE", "SNOOZE")
+                        .done(),
+                )
+                .done(),
+        )
+    }
+
+    /// Returns the `UID` of the alarm this one [snoozes](https://datatracker.ietf.org/doc/html/rfc9074#section-7), if it is a snooze alarm.
+    pub fn get_snoozed_uid(&self) -> Option<&str> {
+        properties_by_key(self, "RELATED-TO")
+            .find(|property| {
+                property
+                    .params()
+                    .get("RELTYPE")
+                    .map_or(false, |reltype| reltype.value() == "SNOOZE")
+            })
+            .map(Property::value)
+    }
+
+    /// Marks this as a [default alarm](https://datatracker.ietf.org/doc/html/rfc9074#section-9),
+    /// writing both `DEFAULT-ALARM` and the `X-APPLE-DEFAULT-ALARM` understood by Apple clients.
+    pub fn default_alarm(mut self) -> Self {
+        self.add_property("DEFAULT-ALARM", "TRUE");
+        self.add_property("X-APPLE-DEFAULT-ALARM", "TRUE");
+        self
+    }
+
+    /// Returns `true` if either `DEFAULT-ALARM` or `X-APPLE-DEFAULT-ALARM` is set to `TRUE`.
+    pub fn is_default_alarm(&self) -> bool {
+        ["DEFAULT-ALARM", "X-APPLE-DEFAULT-ALARM"]
+            .iter()
+            .filter_map(|key| self.property_value(key))
+            .any(|value| value.eq_ignore_ascii_case("TRUE"))
+    }
+
+    /// Makes this a [proximity alarm](https://datatracker.ietf.org/doc/html/rfc9074#section-8),
+    /// which triggers when arriving at or leaving the given [`Location`].
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let office = Location::new()
+    ///     .name("Office")
+    ///     .add_property("GEO", "37.386013;-122.082932")
+    ///     .done();
+    /// let alarm = Alarm::display("check your mail", -Duration::zero())
+    ///     .proximity(Proximity::Arrive, office);
+    /// assert_eq!(alarm.get_proximity(), Some(Proximity::Arrive));
+    /// assert_eq!(alarm.get_vlocations().len(), 1);
+    /// ```
+    pub fn proximity(mut self, proximity: Proximity, location: Location) -> Self {
+        self.append_property(proximity);
+        self.append_component(location);
+        self
+    }
+
+    /// Gets the [`PROXIMITY`](https://datatracker.ietf.org/doc/html/rfc9074#section-8.1) property.
+    pub fn get_proximity(&self) -> Option<Proximity> {
+        self.property_value("PROXIMITY")?.parse().ok()
+    }
+
+    /// Gets the [`Location`]s of a proximity alarm.
+    pub fn get_vlocations(&self) -> Vec<Location> {
+        sub_components(self, "VLOCATION")
+    }
+
+    /// Gets the [`ACTION`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.6.1) of this [`Alarm`].
+    pub fn get_action(&self) -> Option<Action> {
         self.property_value("ACTION")
             .and_then(|p| Action::from_str(p).ok())
     }
 
-    /// Returns the trigger of this [`Alarm`].
-    #[cfg(test)]
-    pub(self) fn get_trigger(&self) -> Option<Trigger> {
+    /// Gets the [`TRIGGER`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.6.3) of this [`Alarm`].
+    pub fn get_trigger(&self) -> Option<Trigger> {
         self.inner
             .properties
             .get("TRIGGER")
             .and_then(|prop| Trigger::try_from(prop).ok())
     }
 
-    /// Returns the description of this [`Alarm`].
-    #[cfg(test)]
-    pub(self) fn get_description(&self) -> Option<&str> {
-        self.inner.property_value("DESCRIPTION")
-    }
-
-    /// Returns the repeat count of this [`Alarm`].
-    #[cfg(test)]
-    pub(self) fn get_repeat(&self) -> usize {
-        self.inner
-            .property_value("REPEAT")
+    /// Gets the [`REPEAT`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.6.2) count of this [`Alarm`],
+    /// `0` if it does not repeat.
+    pub fn get_repeat(&self) -> usize {
+        self.property_value("REPEAT")
             .and_then(|repeat| repeat.parse().ok())
             .unwrap_or(0)
     }
 
+    /// Gets the [`DURATION`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.5)
+    /// between repetitions of this [`Alarm`].
+    pub fn get_duration(&self) -> Option<IcalDuration> {
+        self.property_value("DURATION")?.parse().ok()
+    }
+
     /// End of builder pattern.
     /// copies over everything
     pub fn done(&mut self) -> Self {
@@ -234,6 +433,19 @@ impl Alarm {
     //}
 }
 
+impl TryFrom<Other> for Alarm {
+    type Error = Other;
+
+    /// Converts a `VALARM`, e.g. a nested one from the parser, and hands back anything else.
+    fn try_from(other: Other) -> Result<Self, Self::Error> {
+        if other.component_kind() == "VALARM" {
+            Ok(Alarm { inner: other.inner })
+        } else {
+            Err(other)
+        }
+    }
+}
+
 #[test]
 fn test_audio() {
     let alarm = Alarm::audio((Duration::minutes(15), Related::Start))
@@ -243,13 +455,13 @@ fn test_audio() {
     assert_eq!(
         alarm.get_trigger(),
         Some(Trigger::Duration(
-            Duration::minutes(15),
+            IcalDuration::minutes(15),
             Related::Start.into()
         ))
     );
     assert_eq!(
         alarm.get_trigger().unwrap().as_duration(),
-        Some(&Duration::minutes(15))
+        Some(&IcalDuration::minutes(15))
     );
     assert_eq!(alarm.get_trigger().unwrap().related(), Some(Related::Start));
     assert_eq!(alarm.get_repeat(), 3);
@@ -267,28 +479,132 @@ fn test_display() {
 }
 
 #[test]
-#[ignore]
+fn uid_through_component() {
+    fn set_uid(alarm: &mut Alarm) {
+        alarm.uid("alarm-1");
+    }
+    let mut alarm = Alarm::display("stand-up", -Duration::minutes(5));
+    set_uid(&mut alarm);
+    assert_eq!(alarm.get_uid(), Some("alarm-1"));
+}
+
+#[test]
+fn test_snooze() {
+    use chrono::TimeZone;
+
+    let acknowledged = Utc.with_ymd_and_hms(2024, 5, 1, 8, 55, 0).unwrap();
+    let until = Utc.with_ymd_and_hms(2024, 5, 1, 9, 5, 0).unwrap();
+
+    let alarm = Alarm::display("stand-up", -Duration::minutes(5))
+        .duration_and_repeat(Duration::minutes(1), 2)
+        .uid("alarm-1")
+        .done();
+    assert_eq!(Alarm::display("no uid", until).snooze(until), None);
+
+    let snooze = alarm.snooze(until).unwrap();
+    let alarm = alarm.acknowledged(acknowledged);
+
+    assert_eq!(alarm.get_acknowledged(), Some(acknowledged));
+    assert_eq!(snooze.get_snoozed_uid(), Some("alarm-1"));
+    assert_ne!(snooze.get_uid(), alarm.get_uid());
+    assert_eq!(snooze.get_action(), Some(Action::Display));
+    assert_eq!(snooze.get_description(), Some("stand-up"));
+    assert_eq!(snooze.get_trigger(), Some(Trigger::from(until)));
+    assert_eq!(snooze.get_repeat(), 0);
+    assert_eq!(snooze.get_acknowledged(), None);
+}
+
+#[test]
+fn test_default_alarm() {
+    let alarm = Alarm::audio(-Duration::minutes(15)).default_alarm();
+    assert!(alarm.is_default_alarm());
+    assert!(!Alarm::audio(-Duration::minutes(15)).is_default_alarm());
+
+    let apple_only = Alarm::audio(-Duration::minutes(15))
+        .append_property(("X-APPLE-DEFAULT-ALARM", "TRUE"))
+        .done();
+    assert!(apple_only.is_default_alarm());
+}
+
+#[test]
 fn test_email() {
     let now = CalendarDateTime::now();
 
-    let alarm = Alarm::email("test alarm with email", now.clone(), "important email");
+    let alarm = Alarm::email(
+        "test alarm with email",
+        now.clone(),
+        "important email",
+        ["mailto:john@example.com"],
+    )
+    .unwrap()
+    .attendee("mailto:jane@example.com")
+    .attach(Attachment::Uri("https://example.com/agenda.pdf".into()));
     assert_eq!(alarm.get_action(), Some(Action::Email));
     assert_eq!(alarm.get_trigger().unwrap().as_date_time().unwrap(), &now);
     assert_eq!(alarm.get_description(), Some("test alarm with email"));
     assert_eq!(alarm.get_summary(), Some("important email"));
-    todo!("add attendee handling");
-    // assert_eq!(alarm.get_attendees(), Vec(todo!()));
+    assert_eq!(
+        alarm.get_attendees(),
+        ["mailto:john@example.com", "mailto:jane@example.com"]
+    );
+    assert_eq!(alarm.get_attachments().len(), 1);
 }
 
-pub mod properties {
+#[test]
+fn test_email_needs_recipient() {
+    let no_one: [&str; 0] = [];
+    assert_eq!(
+        Alarm::email("text", -Duration::hours(1), "mail", no_one),
+        None
+    );
+    assert_eq!(
+        Alarm::email(
+            "text",
+            -Duration::hours(1),
+            "mail",
+            ["mailto:a@example.com", ""]
+        ),
+        None
+    );
+}
 
-    use crate::components::date_time::parse_duration;
+#[test]
+#[cfg(feature = "parser")]
+fn test_nested_alarms_from_parser() {
+    let event = Event::new()
+        .alarm(Alarm::display("first", -Duration::minutes(5)))
+        .alarm(
+            Alarm::email(
+                "second",
+                -Duration::hours(1),
+                "mail",
+                ["mailto:a@example.com"],
+            )
+            .unwrap(),
+        )
+        .done();
+    let parsed = event
+        .to_string()
+        .parse::<crate::CalendarComponent>()
+        .unwrap();
+    let alarms = parsed.as_event().unwrap().alarms();
+    assert_eq!(alarms.len(), 2);
+    assert_eq!(alarms.first().unwrap().get_action(), Some(Action::Display));
+    let email = alarms.get(1).unwrap();
+    assert_eq!(
+        email.get_trigger().unwrap().as_duration(),
+        Some(&IcalDuration::hours(-1))
+    );
+    assert_eq!(email.get_attendees(), ["mailto:a@example.com"]);
+}
+
+pub mod properties {
 
     use super::*;
 
     /// [rfc5545#section-3.8.6.1](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.6.1)
-    #[derive(Debug, PartialEq, Eq)]
-    pub(crate) enum Action {
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum Action {
         /// [rfc5545#section-3.8.6.1](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.6.1)
         Audio,
         /// [rfc5545#section-3.8.6.1](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.6.1)
@@ -299,7 +615,7 @@ pub mod properties {
         // IanaToken(String),
         // /// [rfc5545#section-3.8.6.1](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.6.1)
         // XName{vendor: String, name: String},
-        /// what ever else
+        /// Experimental or IANA registered action
         Other(String),
     }
 
@@ -333,6 +649,7 @@ pub mod properties {
                 key: String::from("ACTION"),
                 val: action.to_string(),
                 params: HashMap::new(),
+                list: None,
             }
         }
     }
@@ -393,6 +710,54 @@ pub mod properties {
         }
     }
 
+    /// Proximity trigger of an alarm [RFC 9074, Section 8.1](https://datatracker.ietf.org/doc/html/rfc9074#section-8.1)
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Proximity {
+        /// triggers when arriving at one of the alarm's locations
+        Arrive,
+        /// triggers when departing from one of the alarm's locations
+        Depart,
+        /// triggers when connecting to a vehicle's bluetooth or similar
+        Connect,
+        /// triggers when disconnecting from a vehicle's bluetooth or similar
+        Disconnect,
+        /// Experimental or IANA registered action
+        Other(String),
+    }
+
+    impl FromStr for Proximity {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(match s {
+                "ARRIVE" => Proximity::Arrive,
+                "DEPART" => Proximity::Depart,
+                "CONNECT" => Proximity::Connect,
+                "DISCONNECT" => Proximity::Disconnect,
+                "" => return Err(()),
+                other => Proximity::Other(other.into()),
+            })
+        }
+    }
+
+    impl fmt::Display for Proximity {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Proximity::Arrive => write!(f, "ARRIVE"),
+                Proximity::Depart => write!(f, "DEPART"),
+                Proximity::Connect => write!(f, "CONNECT"),
+                Proximity::Disconnect => write!(f, "DISCONNECT"),
+                Proximity::Other(other) => write!(f, "{}", other),
+            }
+        }
+    }
+
+    impl From<Proximity> for Property {
+        fn from(proximity: Proximity) -> Self {
+            Property::new("PROXIMITY", proximity.to_string())
+        }
+    }
+
     #[test]
     fn test_repeat_default() {
         assert_eq!(
@@ -409,7 +774,7 @@ pub mod properties {
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub enum Trigger {
         /// Duration in relation to either Start or End of the event
-        Duration(Duration, Option<Related>),
+        Duration(IcalDuration, Option<Related>),
         /// Absolute `DateTime` of the Trigger
         DateTime(CalendarDateTime),
     }
@@ -420,12 +785,12 @@ pub mod properties {
         /// # use chrono::*;
         /// assert_eq!(
         ///     Trigger::after_start(Duration::hours(1)),
-        ///     Trigger::Duration(Duration::hours(1), Some(Related::Start))
+        ///     Trigger::Duration(IcalDuration::hours(1), Some(Related::Start))
         /// )
         /// ```
         /// please don't supply negative durations, you'll just confuse everybody
-        pub fn after_start(duration: Duration) -> Trigger {
-            Trigger::Duration(duration, Some(Related::Start))
+        pub fn after_start(duration: impl Into<IcalDuration>) -> Trigger {
+            Trigger::Duration(duration.into(), Some(Related::Start))
         }
 
         /// ```
@@ -433,12 +798,12 @@ pub mod properties {
         /// # use chrono::*;
         /// assert_eq!(
         ///     Trigger::after_end(Duration::hours(1)),
-        ///     Trigger::Duration(Duration::hours(1), Some(Related::End))
+        ///     Trigger::Duration(IcalDuration::hours(1), Some(Related::End))
         /// )
         /// ```
         /// please don't supply negative durations, you'll just confuse everybody
-        pub fn after_end(duration: Duration) -> Trigger {
-            Trigger::Duration(duration, Some(Related::End))
+        pub fn after_end(duration: impl Into<IcalDuration>) -> Trigger {
+            Trigger::Duration(duration.into(), Some(Related::End))
         }
 
         /// ```
@@ -446,12 +811,12 @@ pub mod properties {
         /// # use chrono::*;
         /// assert_eq!(
         ///     Trigger::before_start(Duration::hours(1)),
-        ///     Trigger::Duration(-Duration::hours(1), Some(Related::Start))
+        ///     Trigger::Duration(-IcalDuration::hours(1), Some(Related::Start))
         /// )
         /// ```
         /// please don't supply negative durations, you'll just confuse everybody
-        pub fn before_start(duration: Duration) -> Trigger {
-            Trigger::Duration(-duration, Some(Related::Start))
+        pub fn before_start(duration: impl Into<IcalDuration>) -> Trigger {
+            Trigger::Duration(-duration.into(), Some(Related::Start))
         }
 
         /// ```
@@ -459,12 +824,12 @@ pub mod properties {
         /// # use chrono::*;
         /// assert_eq!(
         ///     Trigger::before_end(Duration::hours(1)),
-        ///     Trigger::Duration(-Duration::hours(1), Some(Related::End))
+        ///     Trigger::Duration(-IcalDuration::hours(1), Some(Related::End))
         /// )
         /// ```
         /// please don't supply negative durations, you'll just confuse everybody
-        pub fn before_end(duration: Duration) -> Trigger {
-            Trigger::Duration(-duration, Some(Related::End))
+        pub fn before_end(duration: impl Into<IcalDuration>) -> Trigger {
+            Trigger::Duration(-duration.into(), Some(Related::End))
         }
 
         /// Returns the containing [`Related`] if the [`Trigger`] contains one
@@ -475,8 +840,8 @@ pub mod properties {
             }
         }
 
-        /// Returns the containing [`Duration`] if the [`Trigger`] contains one
-        pub fn as_duration(&self) -> Option<&Duration> {
+        /// Returns the containing [`IcalDuration`] if the [`Trigger`] contains one
+        pub fn as_duration(&self) -> Option<&IcalDuration> {
             match self {
                 Trigger::Duration(duration, _) => Some(duration),
                 Trigger::DateTime(_) => None,
@@ -492,9 +857,15 @@ pub mod properties {
         }
     }
 
+    impl From<IcalDuration> for Trigger {
+        fn from(duration: IcalDuration) -> Self {
+            Trigger::Duration(duration, None)
+        }
+    }
+
     impl From<Duration> for Trigger {
         fn from(duration: Duration) -> Self {
-            Trigger::Duration(duration, None)
+            Trigger::Duration(duration.into(), None)
         }
     }
 
@@ -507,9 +878,15 @@ pub mod properties {
         }
     }
 
+    impl From<(IcalDuration, Related)> for Trigger {
+        fn from((duration, related): (IcalDuration, Related)) -> Self {
+            Trigger::Duration(duration, Some(related))
+        }
+    }
+
     impl From<(Duration, Related)> for Trigger {
         fn from((duration, related): (Duration, Related)) -> Self {
-            Trigger::Duration(duration, Some(related))
+            Trigger::Duration(duration.into(), Some(related))
         }
     }
 
@@ -526,8 +903,7 @@ pub mod properties {
                     let param_related = prop.get_param_as("RELATED", |s| Related::from_str(s).ok());
 
                     // TODO: improve error handling here
-                    // TODO: yes I found icalendar-duration, let's find a way to integrate this if possible
-                    let parsed_duration = prop.get_value_as(parse_duration);
+                    let parsed_duration = prop.get_value_as(|s| IcalDuration::from_str(s).ok());
 
                     if let Some(duration) = parsed_duration {
                         Ok(Trigger::Duration(duration, param_related))
@@ -617,7 +993,7 @@ pub mod properties {
 
         pretty_assertions::assert_eq!(
             alarm_with_rel_trigger.get_trigger(),
-            Some(Trigger::Duration(dur, None))
+            Some(Trigger::Duration(dur.into(), None))
         );
     }
 
@@ -631,7 +1007,7 @@ pub mod properties {
         alarm_with_rel_start_trigger.print().unwrap();
         pretty_assertions::assert_eq!(
             alarm_with_rel_start_trigger.get_trigger(),
-            Some(Trigger::Duration(dur, Some(Related::Start)))
+            Some(Trigger::Duration(dur.into(), Some(Related::Start)))
         );
     }
 }
@@ -0,0 +1,214 @@
+//! Canonical serialization and content hashes of components
+
+use std::{fmt, str::FromStr};
+
+use super::{date_time::CalendarDateTime, Component};
+use crate::{properties::VOLATILE_PROPERTIES, value_types::ValueType, Property, WriteOptions};
+
+/// Writes a component with volatile properties left out and the others normalized and sorted,
+/// child components are sorted by their own canonical form.
+pub(crate) fn canonical_form<C: Component + ?Sized>(component: &C) -> Result<String, fmt::Error> {
+    let options = WriteOptions::canonical();
+    let mut properties = component
+        .properties()
+        .values()
+        .chain(component.multi_properties().values().flatten())
+        .filter(|property| !VOLATILE_PROPERTIES.contains(&property.key()))
+        .map(canonical_property)
+        .collect::<Vec<_>>();
+    options.sort_component_properties(&mut properties);
+    let mut components = component
+        .components()
+        .iter()
+        .map(canonical_form)
+        .collect::<Result<Vec<_>, _>>()?;
+    components.sort_unstable();
+
+    let mut out = String::new();
+    options.write_line(&mut out, &format!("BEGIN:{}", component.component_kind()))?;
+    for property in properties {
+        property.fmt_write_with(&mut out, &options)?;
+    }
+    for child in components {
+        out.push_str(&child);
+    }
+    options.write_line(&mut out, &format!("END:{}", component.component_kind()))?;
+    Ok(out)
+}
+
+/// Drops a `VALUE` parameter that repeats the default type
+/// and converts date-times with a `TZID` into UTC where the time zone is known.
+fn canonical_property(property: &Property) -> Property {
+    let mut canonical = property.clone();
+    let default_type = ValueType::by_name(property.key());
+    let redundant_type = property.params().get("VALUE").map_or(false, |value| {
+        ValueType::from_str(value.value()).ok() == default_type
+    });
+    if redundant_type {
+        canonical.params.remove("VALUE");
+    }
+
+    if canonical.params.contains_key("TZID") {
+        let utc = canonical
+            .values()
+            .into_iter()
+            .map(|value| {
+                CalendarDateTime::from_property(&Property {
+                    val: value,
+                    ..canonical.clone()
+                })?
+                .try_into_utc()
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(utc) = utc {
+            canonical.val = utc
+                .into_iter()
+                .map(|date_time| CalendarDateTime::Utc(date_time).to_property("").val)
+                .collect::<Vec<_>>()
+                .join(",");
+            canonical.params.remove("TZID");
+        }
+    }
+    canonical
+}
+
+/// 64 bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/index.html#FNV-1a),
+/// unlike [`std::hash::Hasher`]s its results are stable across platforms and releases.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Alarm, Event, EventLike, Trigger};
+
+    #[test]
+    fn fnv1a_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn volatile_and_ordering() {
+        let event = |stamp: &str| {
+            Event::new()
+                .uid("lunch")
+                .add_property("DTSTAMP", stamp)
+                .append_multi_property(("ATTENDEE", "mailto:b@example.com"))
+                .append_multi_property(("ATTENDEE", "mailto:a@example.com"))
+                .alarm(Alarm::display(
+                    "later",
+                    Trigger::before_start(chrono::Duration::minutes(5)),
+                ))
+                .alarm(Alarm::display(
+                    "soon",
+                    Trigger::before_start(chrono::Duration::minutes(1)),
+                ))
+                .summary("Lunch, at noon")
+                .done()
+        };
+        let mut reordered = Event::new()
+            .summary("Lunch, at noon")
+            .append_multi_property(("ATTENDEE", "mailto:a@example.com"))
+            .append_multi_property(("ATTENDEE", "mailto:b@example.com"))
+            .alarm(Alarm::display(
+                "soon",
+                Trigger::before_start(chrono::Duration::minutes(1)),
+            ))
+            .alarm(Alarm::display(
+                "later",
+                Trigger::before_start(chrono::Duration::minutes(5)),
+            ))
+            .uid("lunch")
+            .done();
+        reordered.add_property("LAST-MODIFIED", "20240301T000000Z");
+
+        let first = event("20240101T000000Z");
+        assert_eq!(
+            first.canonical_form(),
+            event("20240202T000000Z").canonical_form()
+        );
+        assert_eq!(first.canonical_form(), reordered.canonical_form());
+        assert_eq!(first.content_hash(), reordered.content_hash());
+        assert!(!first.canonical_form().contains("DTSTAMP"));
+
+        let changed = Event::new().summary("Lunch").done();
+        assert_ne!(first.content_hash(), changed.content_hash());
+    }
+
+    #[test]
+    fn redundant_value_type() {
+        let explicit = Event::new()
+            .append_property(
+                Property::new("DTSTART", "20240108T120000Z")
+                    .add_parameter("VALUE", "DATE-TIME")
+                    .done(),
+            )
+            .done();
+        let implicit = Event::new()
+            .add_property("DTSTART", "20240108T120000Z")
+            .done();
+        assert_eq!(explicit.content_hash(), implicit.content_hash());
+
+        let date = Event::new()
+            .all_day(chrono::NaiveDate::from_ymd_opt(2024, 1, 8).unwrap())
+            .done();
+        assert!(date.canonical_form().contains("VALUE=DATE"));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn date_times_in_utc() {
+        let berlin = Event::new()
+            .append_property(
+                Property::new("DTSTART", "20240108T130000")
+                    .add_parameter("TZID", "Europe/Berlin")
+                    .done(),
+            )
+            .done();
+        let utc = Event::new()
+            .add_property("DTSTART", "20240108T120000Z")
+            .done();
+        assert_eq!(berlin.canonical_form(), utc.canonical_form());
+
+        let unknown = Event::new()
+            .append_property(
+                Property::new("DTSTART", "20240108T130000")
+                    .add_parameter("TZID", "Custom/Zone")
+                    .done(),
+            )
+            .done();
+        assert!(unknown.canonical_form().contains("TZID=Custom/Zone"));
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn identical_exports() {
+        let google = "BEGIN:VEVENT\r\n\
+                      DTSTAMP:20240105T101010Z\r\n\
+                      UID:abc@example.com\r\n\
+                      SUMMARY:Lunch\\, at noon\r\n\
+                      ATTENDEE;CN=Alice;ROLE=CHAIR:mailto:alice@example.com\r\n\
+                      DTSTART:20240108T120000Z\r\n\
+                      END:VEVENT\r\n";
+        let outlook = "begin:vevent\r\n\
+                       dtstart;value=DATE-TIME:20240108T120000Z\r\n\
+                       attendee;role=CHAIR;cn=\"Alice\":mailto:alice@example.com\r\n\
+                       summary:Lunch\\, at noon\r\n\
+                       uid:abc@example.com\r\n\
+                       dtstamp:20240301T080000Z\r\n\
+                       end:vevent\r\n";
+        let google = google.parse::<crate::CalendarComponent>().unwrap();
+        let outlook = outlook.parse::<crate::CalendarComponent>().unwrap();
+        assert_eq!(google.canonical_form(), outlook.canonical_form());
+        assert_eq!(google.content_hash(), outlook.content_hash());
+    }
+}
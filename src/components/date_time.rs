@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone as _, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone as _, Utc};
 
 use crate::{Property, ValueType};
 
@@ -21,10 +21,26 @@ pub(crate) fn format_utc_date_time(utc_dt: DateTime<Utc>) -> String {
     utc_dt.format(UTC_DATE_TIME_FORMAT).to_string()
 }
 
-pub(crate) fn parse_duration(s: &str) -> Option<Duration> {
-    iso8601::duration(s)
-        .ok()
-        .and_then(|iso| Duration::from_std(iso.into()).ok())
+/// Resolves a wall-clock time in a time zone as RFC 5545 does: repeated times mean the first one,
+/// skipped ones use the offset from before the gap and so move forward.
+pub(crate) fn resolve_local<Tz: chrono::TimeZone>(
+    tz: &Tz,
+    local: &NaiveDateTime,
+) -> Option<DateTime<Tz>> {
+    use chrono::{offset::LocalResult, Duration, Offset as _};
+    match tz.from_local_datetime(local) {
+        LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => Some(at),
+        LocalResult::None => {
+            let before = tz
+                .from_local_datetime(&local.checked_sub_signed(Duration::hours(3))?)
+                .earliest()?
+                .offset()
+                .fix();
+            let utc =
+                local.checked_sub_signed(Duration::seconds(before.local_minus_utc().into()))?;
+            Some(tz.from_utc_datetime(&utc))
+        }
+    }
 }
 
 pub(crate) fn naive_date_to_property(date: NaiveDate, key: &str) -> Property {
@@ -117,16 +133,20 @@ impl CalendarDateTime {
     }
 
     /// attempts to convert the into UTC
-    #[cfg(feature = "chrono-tz")]
+    ///
+    /// Date-times with a `TZID` can only be converted with the `chrono-tz` feature enabled.
     pub fn try_into_utc(&self) -> Option<DateTime<Utc>> {
         match self {
             CalendarDateTime::Floating(_) => None, // we shouldn't guess here
             CalendarDateTime::Utc(inner) => Some(*inner),
+            #[cfg(feature = "chrono-tz")]
             CalendarDateTime::WithTimezone { date_time, tzid } => tzid
                 .parse::<chrono_tz::Tz>()
                 .ok()
                 .and_then(|tz| tz.from_local_datetime(date_time).single())
                 .map(|tz| tz.with_timezone(&Utc)),
+            #[cfg(not(feature = "chrono-tz"))]
+            CalendarDateTime::WithTimezone { .. } => None,
         }
     }
 
@@ -382,7 +402,7 @@ impl TryFrom<&crate::parser::Property<'_>> for DatePerhapsTime {
 
         if let Ok(naive_dt) = NaiveDateTime::parse_from_str(val, "%Y%m%dT%H%M%S") {
             if let Some(tz_param) = value.params.iter().find(|p| p.key == "TZID") {
-                if let Some(tzid) = &tz_param.val {
+                if let Some(tzid) = tz_param.value() {
                     return Ok(Self::DateTime(CalendarDateTime::WithTimezone {
                         date_time: naive_dt,
                         tzid: tzid.as_ref().to_string(),
@@ -410,7 +430,7 @@ mod try_from_tests {
             val: "20220716T141500Z".into(),
             params: vec![crate::parser::Parameter {
                 key: "VALUE".into(),
-                val: Some("DATE-TIME".into()),
+                values: vec!["DATE-TIME".into()],
             }],
         };
 
@@ -430,7 +450,7 @@ mod try_from_tests {
             val: "19970714".into(),
             params: vec![crate::parser::Parameter {
                 key: "VALUE".into(),
-                val: Some("DATE-TIME".into()),
+                values: vec!["DATE-TIME".into()],
             }],
         };
 
@@ -448,11 +468,11 @@ mod try_from_tests {
             params: vec![
                 crate::parser::Parameter {
                     key: "VALUE".into(),
-                    val: Some("DATE-TIME".into()),
+                    values: vec!["DATE-TIME".into()],
                 },
                 crate::parser::Parameter {
                     key: "TZID".into(),
-                    val: Some("MY-TZ-ID".into()),
+                    values: vec!["MY-TZ-ID".into()],
                 },
             ],
         };
@@ -477,11 +497,11 @@ mod try_from_tests {
             params: vec![
                 crate::parser::Parameter {
                     key: "VALUE".into(),
-                    val: Some("DATE-TIME".into()),
+                    values: vec!["DATE-TIME".into()],
                 },
                 crate::parser::Parameter {
                     key: "TZID".into(),
-                    val: None,
+                    values: vec![],
                 },
             ],
         };
@@ -498,7 +518,7 @@ mod try_from_tests {
             val: "20220716T141500".into(),
             params: vec![crate::parser::Parameter {
                 key: "VALUE".into(),
-                val: Some("DATE-TIME".into()),
+                values: vec!["DATE-TIME".into()],
             }],
         };
 
@@ -2,7 +2,7 @@ use super::*;
 /// VEVENT [(RFC 5545, Section 3.6.1 )](https://tools.ietf.org/html/rfc5545#section-3.6.1)
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Event {
-    pub(super) inner: InnerComponent,
+    pub(crate) inner: InnerComponent,
 }
 
 impl Event {
@@ -28,10 +28,6 @@ impl Event {
     pub fn get_status(&self) -> Option<EventStatus> {
         EventStatus::from_str(self.property_value("STATUS")?)
     }
-
-    //pub fn repeats<R:Repeater+?Sized>(&mut self, repeat: R) -> &mut Self {
-    //    unimplemented!()
-    //}
 }
 
 #[cfg(test)]
@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+use crate::period::Period;
+
+use super::*;
+
+/// Free/busy time type [RFC 5545, Section 3.2.9](https://datatracker.ietf.org/doc/html/rfc5545#section-3.2.9)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FreeBusyType {
+    /// the time interval is free for scheduling
+    Free,
+    /// the time interval is busy because one or more events have been scheduled for that interval
+    Busy,
+    /// the time interval is busy and that the interval can not be scheduled
+    BusyUnavailable,
+    /// the time interval is busy because one or more events have been tentatively scheduled for that interval
+    BusyTentative,
+}
+
+impl FreeBusyType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FreeBusyType::Free => "FREE",
+            FreeBusyType::Busy => "BUSY",
+            FreeBusyType::BusyUnavailable => "BUSY-UNAVAILABLE",
+            FreeBusyType::BusyTentative => "BUSY-TENTATIVE",
+        }
+    }
+}
+
+impl FromStr for FreeBusyType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "FREE" => Ok(FreeBusyType::Free),
+            "BUSY" => Ok(FreeBusyType::Busy),
+            "BUSY-UNAVAILABLE" => Ok(FreeBusyType::BusyUnavailable),
+            "BUSY-TENTATIVE" => Ok(FreeBusyType::BusyTentative),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<FreeBusyType> for Parameter {
+    fn from(fbtype: FreeBusyType) -> Self {
+        Parameter::new("FBTYPE", fbtype.as_str())
+    }
+}
+
+/// VFREEBUSY [(RFC 5545, Section 3.6.4 )](https://tools.ietf.org/html/rfc5545#section-3.6.4)
+///
+/// Publishes the busy time of a calendar user.
+/// ```
+/// # use icalendar::*;
+/// # use chrono::*;
+/// let start = Utc.with_ymd_and_hms(1997, 10, 15, 5, 0, 0).unwrap();
+/// let end = Utc.with_ymd_and_hms(1997, 10, 16, 5, 0, 0).unwrap();
+///
+/// let free_busy = FreeBusy::new()
+///     .starts(start)
+///     .ends(end)
+///     .busy(&[Period::with_duration(start + Duration::hours(3), Duration::hours(8)).unwrap()])
+///     .done();
+///
+/// assert_eq!(free_busy.get_free_busy().len(), 1);
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FreeBusy {
+    pub(crate) inner: InnerComponent,
+}
+
+impl FreeBusy {
+    /// Creates a new `FreeBusy`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// End of builder pattern.
+    /// copies over everything
+    pub fn done(&mut self) -> Self {
+        FreeBusy {
+            inner: self.inner.done(),
+        }
+    }
+
+    /// Set the [`DTSTART`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.4) [`Property`]
+    ///
+    /// In a `VFREEBUSY` this must be a UTC date-time value.
+    pub fn starts(&mut self, dt: DateTime<Utc>) -> &mut Self {
+        self.add_property("DTSTART", format_utc_date_time(dt))
+    }
+
+    /// Set the [`DTEND`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.2) [`Property`]
+    ///
+    /// In a `VFREEBUSY` this must be a UTC date-time value.
+    pub fn ends(&mut self, dt: DateTime<Utc>) -> &mut Self {
+        self.add_property("DTEND", format_utc_date_time(dt))
+    }
+
+    /// Adds a [`FREEBUSY`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.6) property
+    /// with busy periods.
+    ///
+    /// See [`FreeBusy::free_busy()`] for periods that are not in UTC.
+    pub fn busy<'p>(&mut self, periods: impl IntoIterator<Item = &'p Period>) -> &mut Self {
+        self.free_busy(FreeBusyType::Busy, periods)
+    }
+
+    /// Adds a [`FREEBUSY`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.6) property
+    /// with periods of the given type.
+    ///
+    /// `FREEBUSY` periods must be in UTC, others are converted like in [`Period::try_into_utc()`]
+    /// and left out if that fails.
+    pub fn free_busy<'p>(
+        &mut self,
+        fbtype: FreeBusyType,
+        periods: impl IntoIterator<Item = &'p Period>,
+    ) -> &mut Self {
+        let periods = periods
+            .into_iter()
+            .filter_map(Period::to_utc)
+            .collect::<Vec<_>>();
+        for mut property in Period::to_properties("FREEBUSY", &periods) {
+            self.append_multi_property(property.append_parameter(fbtype).done());
+        }
+        self
+    }
+
+    /// Gets all periods of all [`FREEBUSY`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.6) properties.
+    ///
+    /// A missing `FBTYPE` parameter defaults to [`FreeBusyType::Busy`].
+    pub fn get_free_busy(&self) -> Vec<(FreeBusyType, Period)> {
+        properties_by_key(self, "FREEBUSY")
+            .flat_map(|property| {
+                let fbtype = property
+                    .get_param_as("FBTYPE", |s| FreeBusyType::from_str(s).ok())
+                    .unwrap_or(FreeBusyType::Busy);
+                Period::from_property(property)
+                    .into_iter()
+                    .map(move |period| (fbtype, period))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    #[test]
+    fn get_free_busy() {
+        let start = Utc.with_ymd_and_hms(1997, 10, 15, 5, 0, 0).unwrap();
+        let busy = [
+            Period::with_duration(start, Duration::hours(8)).unwrap(),
+            Period::new(start + Duration::hours(10), start + Duration::hours(12)).unwrap(),
+        ];
+        let tentative =
+            Period::with_duration(start + Duration::hours(14), Duration::hours(1)).unwrap();
+
+        let free_busy = FreeBusy::new()
+            .busy(&busy)
+            .free_busy(FreeBusyType::BusyTentative, [&tentative])
+            .done();
+
+        assert_eq!(
+            free_busy.get_free_busy(),
+            vec![
+                (FreeBusyType::Busy, busy[0].clone()),
+                (FreeBusyType::Busy, busy[1].clone()),
+                (FreeBusyType::BusyTentative, tentative),
+            ]
+        );
+        assert_eq!(
+            free_busy
+                .multi_properties()
+                .get("FREEBUSY")
+                .and_then(|properties| properties.first())
+                .map(Property::value),
+            Some("19971015T050000Z/PT8H,19971015T150000Z/19971015T170000Z")
+        );
+    }
+
+    #[test]
+    fn periods_are_utc() {
+        let start = NaiveDate::from_ymd_opt(1997, 10, 15)
+            .unwrap()
+            .and_hms_opt(5, 0, 0)
+            .unwrap();
+        let floating = Period::with_duration(start, Duration::hours(1)).unwrap();
+        let free_busy = FreeBusy::new().busy([&floating]).done();
+        assert!(free_busy.get_free_busy().is_empty());
+
+        #[cfg(feature = "chrono-tz")]
+        {
+            let berlin = CalendarDateTime::WithTimezone {
+                date_time: start,
+                tzid: "Europe/Berlin".into(),
+            };
+            let period = Period::with_duration(berlin, Duration::hours(1)).unwrap();
+            let free_busy = FreeBusy::new().busy([&period]).done();
+            let utc = Utc.with_ymd_and_hms(1997, 10, 15, 3, 0, 0).unwrap();
+            assert_eq!(
+                free_busy.get_free_busy(),
+                vec![(
+                    FreeBusyType::Busy,
+                    Period::new(utc, utc + Duration::hours(1)).unwrap()
+                )]
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn parse_free_busy() {
+        let parsed = "BEGIN:VFREEBUSY\n\
+            FREEBUSY:19970308T160000Z/PT3H,19970308T200000Z/PT1H\n\
+            FREEBUSY;FBTYPE=FREE:19970308T230000Z/19970309T000000Z\n\
+            END:VFREEBUSY\n"
+            .parse::<crate::CalendarComponent>()
+            .unwrap();
+        let free_busy = parsed.as_free_busy().unwrap();
+
+        let fbtypes = free_busy
+            .get_free_busy()
+            .into_iter()
+            .map(|(fbtype, _)| fbtype)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fbtypes,
+            vec![FreeBusyType::Busy, FreeBusyType::Busy, FreeBusyType::Free]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use super::*;
+
+/// VLOCATION [(RFC 9073, Section 7.2)](https://datatracker.ietf.org/doc/html/rfc9073#section-7.2)
+///
+/// A structured location, added to [`Event`]s, [`Todo`]s and [`Participant`]s.
+/// ```
+/// # use icalendar::*;
+/// let location = Location::new()
+///     .name("Conference Center")
+///     .location_type(["conference-center"])
+///     .done();
+///
+/// let event = Event::new().vlocation(location.clone()).done();
+/// assert_eq!(event.get_vlocations(), vec![location]);
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Location {
+    pub(super) inner: InnerComponent,
+}
+
+impl Location {
+    /// Creates a new Location.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// End of builder pattern.
+    /// copies over everything
+    pub fn done(&mut self) -> Self {
+        Location {
+            inner: self.inner.done(),
+        }
+    }
+
+    /// Set the [`NAME`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.1) `Property`
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        self.add_property("NAME", name)
+    }
+
+    /// Gets the value of the `NAME` `Property`.
+    pub fn get_name(&self) -> Option<&str> {
+        self.property_value("NAME")
+    }
+
+    /// Set the [`LOCATION-TYPE`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.1) `Property`
+    ///
+    /// Values are taken from the [location types registry](https://www.iana.org/assignments/location-type-registry),
+    /// e.g. `"parking"` or `"restaurant"`.
+    pub fn location_type<I, S>(&mut self, location_types: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.append_property(Property::new_list("LOCATION-TYPE", location_types))
+    }
+
+    /// Gets all values of the `LOCATION-TYPE` `Property`.
+    pub fn get_location_types(&self) -> Vec<String> {
+        list_values(self, "LOCATION-TYPE")
+    }
+
+    /// Adds a [`STRUCTURED-DATA`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.6) `Property`
+    pub fn structured_data(&mut self, data: StructuredData) -> &mut Self {
+        self.append_multi_property(data)
+    }
+
+    /// Gets all `STRUCTURED-DATA` properties.
+    pub fn get_structured_data(&self) -> Vec<StructuredData> {
+        structured_data(self)
+    }
+}
+
+/// Escapes a vCard `ADR` component, see [RFC 6350, Section 3.4](https://datatracker.ietf.org/doc/html/rfc6350#section-3.4)
+fn escape_adr_component(component: Option<&str>) -> String {
+    component
+        .unwrap_or_default()
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+impl From<Venue> for Location {
+    /// Carries the address of a [`Venue`] over as `DESCRIPTION`
+    /// and as a vCard `ADR` in `STRUCTURED-DATA`.
+    fn from(venue: Venue) -> Self {
+        let mut location = Location::new();
+        if let Some(uid) = venue.property_value("UID") {
+            location.uid(uid);
+        }
+
+        let locality = [venue.get_postal_code(), venue.get_locality()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let description = [
+            venue.get_street_address(),
+            venue.get_extended_address(),
+            Some(locality.as_str()).filter(|locality| !locality.is_empty()),
+            venue.get_region(),
+            venue.get_country(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n");
+        if description.is_empty() {
+            return location.done();
+        }
+        location.description(&description);
+
+        let adr = [
+            None,
+            venue.get_extended_address(),
+            venue.get_street_address(),
+            venue.get_locality(),
+            venue.get_region(),
+            venue.get_postal_code(),
+            venue.get_country(),
+        ]
+        .map(escape_adr_component)
+        .join(";");
+        location
+            .structured_data(StructuredData::Text {
+                fmttype: "text/vcard".into(),
+                schema: "https://www.rfc-editor.org/rfc/rfc6350".into(),
+                text: format!("BEGIN:VCARD\nVERSION:4.0\nADR:{adr}\nEND:VCARD"),
+            })
+            .done()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn from_venue() {
+        let venue = Venue::new()
+            .street_address("Karl-Liebknecht-Str. 1")
+            .locality("Leipzig")
+            .postal_code("04107")
+            .country("Germany")
+            .done();
+        let location = Location::from(venue);
+
+        assert_eq!(
+            location.get_description(),
+            Some("Karl-Liebknecht-Str. 1\n04107 Leipzig\nGermany")
+        );
+        assert_eq!(
+            location.get_structured_data(),
+            vec![StructuredData::Text {
+                fmttype: "text/vcard".into(),
+                schema: "https://www.rfc-editor.org/rfc/rfc6350".into(),
+                text: "BEGIN:VCARD\nVERSION:4.0\nADR:;;Karl-Liebknecht-Str. 1;Leipzig;;04107;Germany\nEND:VCARD".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn from_empty_venue() {
+        let location = Location::from(Venue::new());
+        assert_eq!(location.get_description(), None);
+        assert_eq!(location.get_structured_data(), vec![]);
+    }
+}
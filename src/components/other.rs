@@ -3,7 +3,7 @@ use super::*;
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Other {
     name: String,
-    pub(super) inner: InnerComponent,
+    pub(crate) inner: InnerComponent,
 }
 
 impl Component for Other {
@@ -29,6 +29,21 @@ impl Component for Other {
         &self.inner.multi_properties
     }
 
+    /// Mutable access to `properties`
+    fn properties_mut(&mut self) -> &mut BTreeMap<String, Property> {
+        &mut self.inner.properties
+    }
+
+    /// Mutable access to `multi_properties`
+    fn multi_properties_mut(&mut self) -> &mut BTreeMap<String, Vec<Property>> {
+        &mut self.inner.multi_properties
+    }
+
+    /// Mutable access to child `components`
+    fn components_mut(&mut self) -> &mut Vec<Other> {
+        &mut self.inner.components
+    }
+
     /// Adds a `Property`
     fn append_property(&mut self, property: impl Into<Property>) -> &mut Self {
         let property = property.into();
@@ -51,7 +66,8 @@ impl Component for Other {
 }
 
 impl From<(String, InnerComponent)> for Other {
-    fn from((name, inner): (String, InnerComponent)) -> Self {
+    fn from((mut name, inner): (String, InnerComponent)) -> Self {
+        name.make_ascii_uppercase();
         Self { name, inner }
     }
 }
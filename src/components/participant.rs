@@ -0,0 +1,122 @@
+use super::*;
+
+/// PARTICIPANT [(RFC 9073, Section 7.1)](https://datatracker.ietf.org/doc/html/rfc9073#section-7.1)
+///
+/// Someone taking part in an [`Event`] or [`Todo`] without necessarily being an attendee,
+/// like a speaker or a sponsor.
+/// ```
+/// # use icalendar::*;
+/// let speaker = Participant::new(ParticipantType::Speaker)
+///     .calendar_address("mailto:speaker@example.com")
+///     .styled_description(StyledDescription::Text {
+///         fmttype: "text/html".into(),
+///         text: "<p>Keynote speaker</p>".into(),
+///     })
+///     .done();
+///
+/// let event = Event::new().participant(speaker.clone()).done();
+/// assert_eq!(event.get_participants(), vec![speaker]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Participant {
+    pub(super) inner: InnerComponent,
+}
+
+impl Participant {
+    /// Creates a new Participant, the [`PARTICIPANT-TYPE`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.2) is required.
+    pub fn new(participant_type: ParticipantType) -> Self {
+        Participant {
+            inner: Default::default(),
+        }
+        .append_property(participant_type)
+        .done()
+    }
+
+    /// End of builder pattern.
+    /// copies over everything
+    pub fn done(&mut self) -> Self {
+        Participant {
+            inner: self.inner.done(),
+        }
+    }
+
+    /// Gets the value of the `PARTICIPANT-TYPE` `Property`.
+    pub fn get_participant_type(&self) -> Option<ParticipantType> {
+        self.property_value("PARTICIPANT-TYPE")?.parse().ok()
+    }
+
+    /// Set the [`CALENDAR-ADDRESS`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.4) `Property`
+    pub fn calendar_address(&mut self, address: &str) -> &mut Self {
+        self.add_property("CALENDAR-ADDRESS", address)
+    }
+
+    /// Gets the value of the `CALENDAR-ADDRESS` `Property`.
+    pub fn get_calendar_address(&self) -> Option<&str> {
+        self.property_value("CALENDAR-ADDRESS")
+    }
+
+    /// Adds a [`STRUCTURED-DATA`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.6) `Property`
+    pub fn structured_data(&mut self, data: StructuredData) -> &mut Self {
+        self.append_multi_property(data)
+    }
+
+    /// Gets all `STRUCTURED-DATA` properties.
+    pub fn get_structured_data(&self) -> Vec<StructuredData> {
+        structured_data(self)
+    }
+
+    /// Adds a [`STYLED-DESCRIPTION`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.5) `Property`
+    pub fn styled_description(&mut self, description: StyledDescription) -> &mut Self {
+        self.append_multi_property(description)
+    }
+
+    /// Gets all `STYLED-DESCRIPTION` properties.
+    pub fn get_styled_descriptions(&self) -> Vec<StyledDescription> {
+        styled_descriptions(self)
+    }
+
+    /// Adds a [`Location`] where this participant can be found
+    pub fn vlocation(&mut self, location: Location) -> &mut Self {
+        self.append_component(location)
+    }
+
+    /// Gets all [`Location`]s of this participant.
+    pub fn get_vlocations(&self) -> Vec<Location> {
+        sub_components(self, "VLOCATION")
+    }
+
+    /// Adds a [`Resource`] used by this participant
+    pub fn vresource(&mut self, resource: Resource) -> &mut Self {
+        self.append_component(resource)
+    }
+
+    /// Gets all [`Resource`]s of this participant.
+    pub fn get_vresources(&self) -> Vec<Resource> {
+        sub_components(self, "VRESOURCE")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_properties_set() {
+        let participant = Participant::new(ParticipantType::Sponsor)
+            .summary("ACME")
+            .vlocation(Location::new().name("Booth 7").done())
+            .done();
+        assert_eq!(
+            participant.get_participant_type(),
+            Some(ParticipantType::Sponsor)
+        );
+        assert_eq!(participant.get_summary(), Some("ACME"));
+        assert_eq!(
+            participant
+                .get_vlocations()
+                .first()
+                .and_then(Location::get_name),
+            Some("Booth 7")
+        );
+    }
+}
@@ -0,0 +1,560 @@
+//! Expansion of recurring components and their alarms
+//!
+//! Floating date-times and dates (at midnight) have no time zone of their own, they are
+//! read in a [`Floating`] time zone, which is UTC unless the caller knows better.
+//! Date-times with a `TZID` that can not be resolved (e.g. without the `chrono-tz` feature)
+//! have no instant, such occurrences and alarms are skipped.
+
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+
+use super::*;
+use crate::repeats::naive_date_time;
+
+/// The time zone floating date-times and dates are read in, implemented for every [`TimeZone`].
+pub(crate) trait Floating {
+    /// Resolves a wall-clock time like a date-time with a `TZID`.
+    fn instant(&self, wall_clock: &NaiveDateTime) -> Option<DateTime<Utc>>;
+}
+
+impl<Tz: TimeZone> Floating for Tz {
+    fn instant(&self, wall_clock: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        date_time::resolve_local(self, wall_clock).map(|at| at.with_timezone(&Utc))
+    }
+}
+
+/// Resolves a date or date-time to an instant, reading floating ones as UTC.
+pub(crate) fn instant(date_perhaps_time: &DatePerhapsTime) -> Option<DateTime<Utc>> {
+    instant_in(date_perhaps_time, &Utc)
+}
+
+/// Resolves a date or date-time to an instant, reading floating ones in `floating`.
+pub(crate) fn instant_in(
+    date_perhaps_time: &DatePerhapsTime,
+    floating: &dyn Floating,
+) -> Option<DateTime<Utc>> {
+    match date_perhaps_time {
+        DatePerhapsTime::Date(date) => floating.instant(&date.and_hms_opt(0, 0, 0)?),
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(naive)) => floating.instant(naive),
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(utc)) => Some(*utc),
+        #[cfg(feature = "chrono-tz")]
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => {
+            let tz = tzid.parse::<chrono_tz::Tz>().ok()?;
+            date_time::resolve_local(&tz, date_time).map(|at| at.with_timezone(&Utc))
+        }
+        #[cfg(not(feature = "chrono-tz"))]
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { .. }) => None,
+    }
+}
+
+fn wall_clock(date_perhaps_time: &DatePerhapsTime) -> Option<NaiveDateTime> {
+    match date_perhaps_time {
+        DatePerhapsTime::Date(date) => date.and_hms_opt(0, 0, 0),
+        DatePerhapsTime::DateTime(date_time) => Some(naive_date_time(date_time)),
+    }
+}
+
+/// Builds an occurrence with the same value type and timezone as `start`.
+fn like_start(start: &DatePerhapsTime, wall_clock: NaiveDateTime) -> DatePerhapsTime {
+    match start {
+        DatePerhapsTime::Date(_) => DatePerhapsTime::Date(wall_clock.date()),
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(_)) => {
+            CalendarDateTime::Floating(wall_clock).into()
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(_)) => {
+            CalendarDateTime::Utc(Utc.from_utc_datetime(&wall_clock)).into()
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { tzid, .. }) => {
+            CalendarDateTime::WithTimezone {
+                date_time: wall_clock,
+                tzid: tzid.clone(),
+            }
+            .into()
+        }
+    }
+}
+
+/// Converts `UNTIL` to the wall-clock of `start`, which `UNTIL` in UTC usually is not.
+fn until_wall_clock(start: &DatePerhapsTime, until: &DatePerhapsTime) -> Option<NaiveDateTime> {
+    match (start, until) {
+        (_, DatePerhapsTime::Date(date)) => date.and_hms_opt(23, 59, 59),
+        #[cfg(feature = "chrono-tz")]
+        (
+            DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { tzid, .. }),
+            DatePerhapsTime::DateTime(CalendarDateTime::Utc(until)),
+        ) => Some(match tzid.parse::<chrono_tz::Tz>() {
+            Ok(tz) => until.with_timezone(&tz).naive_local(),
+            Err(_) => until.naive_utc(),
+        }),
+        (_, DatePerhapsTime::DateTime(until)) => Some(naive_date_time(until)),
+    }
+}
+
+/// `DTEND` of an event or `DUE` of a todo.
+fn end_of<C: Component + ?Sized>(component: &C) -> Option<DatePerhapsTime> {
+    component
+        .get_end()
+        .or_else(|| DatePerhapsTime::from_property(component.properties().get("DUE")?))
+}
+
+/// The duration of a component, used to find the end of each occurrence.
+pub(crate) fn length<C: Component + ?Sized>(
+    component: &C,
+    start: &DatePerhapsTime,
+    floating: &dyn Floating,
+) -> Option<IcalDuration> {
+    if let Some(duration) = component.property_value("DURATION") {
+        return duration.parse().ok();
+    }
+    let end = end_of(component);
+    let all_day = matches!(start, DatePerhapsTime::Date(_));
+    match end {
+        Some(end) => {
+            let length = instant_in(&end, floating)? - instant_in(start, floating)?;
+            // `EventLike::all_day` writes the same date into DTSTART and DTEND
+            if all_day && length <= Duration::zero() {
+                Some(IcalDuration::days(1))
+            } else {
+                Some(length.into())
+            }
+        }
+        None if all_day => Some(IcalDuration::days(1)),
+        None => None,
+    }
+}
+
+/// Starts of all occurrences of a component in ascending order, `RRULE` is expanded lazily.
+///
+/// Combines `DTSTART`, `RRULE` and `RDATE` and removes `EXDATE`.
+pub(crate) fn occurrence_starts<'f, C: EventLike + ?Sized>(
+    component: &C,
+    floating: &'f dyn Floating,
+) -> impl Iterator<Item = (DateTime<Utc>, DatePerhapsTime)> + 'f {
+    let start = component.get_start();
+    let rule = component.get_recurrence_rule();
+
+    let from_rule: Box<dyn Iterator<Item = DatePerhapsTime>> =
+        match (start.clone(), rule, start.as_ref().and_then(wall_clock)) {
+            (Some(start), Some(rule), Some(dtstart)) => {
+                let until = rule
+                    .get_until()
+                    .and_then(|until| until_wall_clock(&start, until));
+                Box::new(
+                    rule.occurrences_until(dtstart, until)
+                        .map(move |wall_clock| like_start(&start, wall_clock)),
+                )
+            }
+            (start, _, _) => Box::new(start.into_iter()),
+        };
+
+    let mut extra = component
+        .rdates()
+        .into_iter()
+        .chain(
+            component
+                .rdate_periods()
+                .into_iter()
+                .map(|period| DatePerhapsTime::DateTime(period.start().clone())),
+        )
+        .filter_map(|occurrence| Some((instant_in(&occurrence, floating)?, occurrence)))
+        .collect::<Vec<_>>();
+    extra.sort_by_key(|(at, _)| *at);
+
+    let exdates = component
+        .exdates()
+        .iter()
+        .filter_map(|exdate| instant_in(exdate, floating))
+        .collect::<Vec<_>>();
+
+    let mut from_rule = from_rule
+        .filter_map(move |occurrence| Some((instant_in(&occurrence, floating)?, occurrence)))
+        .peekable();
+    let mut extra = extra.into_iter().peekable();
+    let mut last = None;
+    std::iter::from_fn(move || loop {
+        let next = match (from_rule.peek(), extra.peek()) {
+            (Some((a, _)), Some((b, _))) if b < a => extra.next(),
+            (Some(_), _) => from_rule.next(),
+            (None, _) => extra.next(),
+        }?;
+        if last != Some(next.0) && !exdates.contains(&next.0) {
+            last = Some(next.0);
+            return Some(next);
+        }
+    })
+}
+
+/// All occurrences of a component starting within `window_start..window_end`, sorted.
+pub(crate) fn occurrences<C: EventLike + ?Sized>(
+    component: &C,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    floating: &dyn Floating,
+) -> Vec<DatePerhapsTime> {
+    occurrence_starts(component, floating)
+        .skip_while(|(at, _)| *at < window_start)
+        .take_while(|(at, _)| *at < window_end)
+        .map(|(_, occurrence)| occurrence)
+        .collect()
+}
+
+/// Whether any occurrence overlaps `start..end`, open bounds are unlimited.
+///
+/// Occurrences without a duration overlap if they start within the range,
+/// as described in [RFC 4791, Section 9.9](https://datatracker.ietf.org/doc/html/rfc4791#section-9.9).
+#[cfg(feature = "caldav")]
+pub(crate) fn overlaps<C: EventLike + ?Sized>(
+    component: &C,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> bool {
+    let length = component
+        .get_start()
+        .and_then(|dtstart| length(component, &dtstart, &Utc))
+        .and_then(|length| length.try_to_chrono())
+        .unwrap_or_else(Duration::zero);
+    occurrence_starts(component, &Utc)
+        .take_while(|(at, _)| end.map_or(true, |end| *at < end))
+        .any(|(at, _)| {
+            start.map_or(true, |start| {
+                if length <= Duration::zero() {
+                    start <= at
+                } else {
+                    at.checked_add_signed(length)
+                        .map_or(true, |end| start < end)
+                }
+            })
+        })
+}
+
+/// The instants an alarm fires at for one occurrence, including repetitions.
+fn fire_times(
+    alarm: &Alarm,
+    anchor: Option<&DatePerhapsTime>,
+    window_end: DateTime<Utc>,
+    floating: &dyn Floating,
+) -> Vec<DateTime<Utc>> {
+    let first = match alarm.get_trigger() {
+        Some(Trigger::DateTime(date_time)) => match instant_in(&date_time.into(), floating) {
+            Some(first) => first,
+            None => return Vec::new(),
+        },
+        Some(Trigger::Duration(offset, _)) => {
+            match anchor
+                .and_then(|anchor| offset.checked_add_to_date_perhaps_time(anchor))
+                .as_ref()
+                .and_then(|first| instant_in(first, floating))
+            {
+                Some(first) => first,
+                None => return Vec::new(),
+            }
+        }
+        None => return Vec::new(),
+    };
+    let interval = alarm
+        .get_duration()
+        .and_then(|duration| duration.try_to_chrono())
+        .unwrap_or_else(Duration::zero);
+    let repeat = if interval.is_zero() {
+        0
+    } else {
+        alarm.get_repeat()
+    };
+    (0..=repeat)
+        .map_while(|n| first.checked_add_signed(interval.checked_mul(i32::try_from(n).ok()?)?))
+        .take_while(|at| *at < window_end)
+        .collect()
+}
+
+/// Instants a single alarm of a component fires at within `start..end`, open bounds are unlimited.
+///
+/// Occurrences are expanded lazily, so asking for the first instant of an endless series is cheap.
+pub(crate) fn alarm_instants<'a, C: EventLike + ?Sized>(
+    component: &'a C,
+    alarm: &'a Alarm,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    floating: &'a dyn Floating,
+) -> Box<dyn Iterator<Item = DateTime<Utc>> + 'a> {
+    let window_end = end.unwrap_or(DateTime::<Utc>::MAX_UTC);
+    let acknowledged = alarm.get_acknowledged();
+    let keep = move |at: &DateTime<Utc>| {
+        start.map_or(true, |start| start <= *at)
+            && *at < window_end
+            && acknowledged.map_or(true, |acknowledged| *at > acknowledged)
+    };
+
+    match alarm.get_trigger() {
+        Some(Trigger::DateTime(_)) => Box::new(
+            fire_times(alarm, None, window_end, floating)
+                .into_iter()
+                .filter(keep),
+        ),
+        // an offset beyond `chrono::Duration` would never fire
+        Some(Trigger::Duration(offset, _)) if offset.try_to_chrono().is_none() => {
+            Box::new(std::iter::empty())
+        }
+        Some(Trigger::Duration(offset, related)) => {
+            let dtstart = match component.get_start() {
+                Some(dtstart) => dtstart,
+                // without `DTSTART` only alarms related to `DUE` or `DTEND` fire, and only once
+                None => {
+                    let fires = match (related, end_of(component)) {
+                        (Some(Related::End), Some(end)) => {
+                            fire_times(alarm, Some(&end), window_end, floating)
+                        }
+                        _ => Vec::new(),
+                    };
+                    return Box::new(fires.into_iter().filter(keep));
+                }
+            };
+            let length = length(component, &dtstart, floating);
+            // occurrences starting after the window may still have alarms firing inside it,
+            // parts that are too long for a `chrono::Duration` are `Some(None)`
+            let repeats = alarm.get_duration().map(|duration| {
+                duration
+                    .try_to_chrono()?
+                    .checked_mul(i32::try_from(alarm.get_repeat()).ok()?)
+            });
+            let margin = [
+                Some(offset.try_to_chrono()),
+                repeats,
+                length.map(|length| length.try_to_chrono()),
+            ]
+            .into_iter()
+            .flatten()
+            .try_fold(Duration::days(1), |margin, part| {
+                margin.checked_add(&part?.abs())
+            })
+            .unwrap_or(Duration::MAX);
+
+            Box::new(
+                occurrence_starts(component, floating)
+                    .take_while(move |(at, _)| {
+                        end.map_or(true, |end| {
+                            at.checked_sub_signed(margin).map_or(true, |at| at < end)
+                        })
+                    })
+                    .flat_map(move |(_, occurrence)| {
+                        let anchor = match (related, length) {
+                            (Some(Related::End), Some(length)) => {
+                                length.checked_add_to_date_perhaps_time(&occurrence)
+                            }
+                            _ => Some(occurrence),
+                        };
+                        fire_times(alarm, anchor.as_ref(), window_end, floating)
+                    })
+                    .filter(keep),
+            )
+        }
+        None => Box::new(std::iter::empty()),
+    }
+}
+
+/// All instants alarms of a component fire at within `window_start..window_end`, sorted.
+pub(crate) fn alarm_times<C: EventLike + ?Sized>(
+    component: &C,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    floating: &dyn Floating,
+) -> Vec<(DateTime<Utc>, Alarm)> {
+    let mut times = Vec::new();
+    for alarm in component.alarms() {
+        times.extend(
+            alarm_instants(
+                component,
+                &alarm,
+                Some(window_start),
+                Some(window_end),
+                floating,
+            )
+            .map(|at| (at, alarm.clone())),
+        );
+    }
+    times.sort_by_key(|(at, _)| *at);
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Frequency, RecurrenceRule};
+
+    fn at(d: u32, h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, d, h, m, 0).unwrap()
+    }
+
+    fn times(
+        component: &impl EventLike,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        component
+            .alarm_times(from, to)
+            .into_iter()
+            .map(|(at, _)| at)
+            .collect()
+    }
+
+    #[test]
+    fn occurrences_with_rdate_and_exdate() {
+        let event = Event::new()
+            .starts(at(1, 9, 0))
+            .repeats(RecurrenceRule::new(Frequency::Daily).count(4))
+            .exdate(at(2, 9, 0))
+            .rdate(at(10, 12, 0))
+            .done();
+        assert_eq!(
+            event.occurrences(at(1, 0, 0), at(31, 0, 0)),
+            [at(1, 9, 0), at(3, 9, 0), at(4, 9, 0), at(10, 12, 0)]
+                .map(DatePerhapsTime::from)
+                .to_vec()
+        );
+        assert_eq!(
+            event.occurrences(at(3, 10, 0), at(10, 12, 0)),
+            vec![at(4, 9, 0).into()]
+        );
+    }
+
+    #[test]
+    fn occurrences_of_all_day_events() {
+        let event = Event::new()
+            .all_day(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            .repeats(RecurrenceRule::new(Frequency::Weekly).count(3))
+            .done();
+        assert_eq!(
+            event.occurrences(at(1, 0, 0), at(31, 0, 0)),
+            [1, 8, 15]
+                .map(|d| DatePerhapsTime::Date(NaiveDate::from_ymd_opt(2024, 1, d).unwrap()))
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn alarms_of_recurring_event() {
+        let event = Event::new()
+            .starts(at(1, 9, 0))
+            .repeats(RecurrenceRule::new(Frequency::Daily))
+            .alarm(
+                Alarm::display("standup", -Duration::minutes(15))
+                    .duration_and_repeat(Duration::minutes(5), 2),
+            )
+            .done();
+        assert_eq!(
+            times(&event, at(2, 0, 0), at(3, 8, 50)),
+            [at(2, 8, 45), at(2, 8, 50), at(2, 8, 55), at(3, 8, 45),]
+        );
+        // the occurrence on the 4th starts outside the window but its alarm fires inside
+        assert_eq!(times(&event, at(3, 23, 0), at(4, 8, 46)), [at(4, 8, 45)]);
+    }
+
+    #[test]
+    fn alarms_related_to_end() {
+        let event = Event::new()
+            .starts(at(1, 9, 0))
+            .ends(at(1, 10, 0))
+            .repeats(RecurrenceRule::new(Frequency::Daily).count(2))
+            .alarm(Alarm::display(
+                "wrap up",
+                (-Duration::minutes(5), Related::End),
+            ))
+            .done();
+        assert_eq!(
+            times(&event, at(1, 0, 0), at(31, 0, 0)),
+            [at(1, 9, 55), at(2, 9, 55)]
+        );
+
+        let todo = Todo::new()
+            .starts(at(1, 9, 0))
+            .due(at(1, 17, 0))
+            .alarm(Alarm::display(
+                "due soon",
+                (-Duration::hours(1), Related::End),
+            ))
+            .done();
+        assert_eq!(times(&todo, at(1, 0, 0), at(31, 0, 0)), [at(1, 16, 0)]);
+    }
+
+    #[test]
+    fn absolute_alarms_fire_once() {
+        let event = Event::new()
+            .starts(at(1, 9, 0))
+            .repeats(RecurrenceRule::new(Frequency::Daily).count(5))
+            .alarm(Alarm::display("once", at(3, 7, 0)))
+            .done();
+        assert_eq!(times(&event, at(1, 0, 0), at(31, 0, 0)), [at(3, 7, 0)]);
+    }
+
+    #[test]
+    fn acknowledged_alarms_are_skipped() {
+        let event = Event::new()
+            .starts(at(1, 9, 0))
+            .repeats(RecurrenceRule::new(Frequency::Daily).count(3))
+            .alarm(Alarm::display("standup", -Duration::minutes(15)).acknowledged(at(2, 8, 50)))
+            .done();
+        assert_eq!(times(&event, at(1, 0, 0), at(31, 0, 0)), [at(3, 8, 45)]);
+    }
+
+    #[test]
+    fn alarms_related_to_due_without_start() {
+        let todo = Todo::new()
+            .due(at(1, 10, 0))
+            .alarm(Alarm::display(
+                "due soon",
+                (-Duration::minutes(15), Related::End),
+            ))
+            .alarm(Alarm::display("never", -Duration::minutes(15)))
+            .done();
+        assert_eq!(times(&todo, at(1, 0, 0), at(31, 0, 0)), [at(1, 9, 45)]);
+    }
+
+    #[test]
+    fn alarms_with_huge_offsets_are_skipped() {
+        let event = Event::new()
+            .starts(at(1, 9, 0))
+            .repeats(RecurrenceRule::new(Frequency::Daily))
+            .alarm(
+                Alarm::display("huge", -Duration::minutes(15))
+                    .append_property(("TRIGGER", "-PT9223372036854775807S"))
+                    .done(),
+            )
+            .done();
+        assert!(times(&event, at(1, 0, 0), at(31, 0, 0)).is_empty());
+    }
+
+    #[test]
+    fn unresolvable_time_zones_have_no_instant() {
+        let start = CalendarDateTime::WithTimezone {
+            date_time: NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+            tzid: "Nowhere/Special".into(),
+        };
+        let event = Event::new()
+            .starts(start)
+            .repeats(RecurrenceRule::new(Frequency::Daily).count(3))
+            .done();
+        assert!(event.occurrences(at(1, 0, 0), at(31, 0, 0)).is_empty());
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn instants_around_transitions() {
+        let berlin = |d: u32, h: u32, m: u32| {
+            DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone {
+                date_time: NaiveDate::from_ymd_opt(2024, 3, d)
+                    .unwrap()
+                    .and_hms_opt(h, m, 0)
+                    .unwrap(),
+                tzid: "Europe/Berlin".into(),
+            })
+        };
+        let utc = |d: u32, h: u32, m: u32| Some(Utc.with_ymd_and_hms(2024, 3, d, h, m, 0).unwrap());
+        assert_eq!(instant(&berlin(30, 12, 0)), utc(30, 11, 0));
+        // 02:30 does not exist on the 31st and is read with the offset from before
+        assert_eq!(instant(&berlin(31, 2, 30)), utc(31, 1, 30));
+    }
+}
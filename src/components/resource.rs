@@ -0,0 +1,70 @@
+use super::*;
+
+/// VRESOURCE [(RFC 9073, Section 7.3)](https://datatracker.ietf.org/doc/html/rfc9073#section-7.3)
+///
+/// A resource like a room or a projector, added to [`Event`]s, [`Todo`]s and [`Participant`]s.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Resource {
+    pub(super) inner: InnerComponent,
+}
+
+impl Resource {
+    /// Creates a new Resource.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// End of builder pattern.
+    /// copies over everything
+    pub fn done(&mut self) -> Self {
+        Resource {
+            inner: self.inner.done(),
+        }
+    }
+
+    /// Set the [`NAME`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.1) `Property`
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        self.add_property("NAME", name)
+    }
+
+    /// Gets the value of the `NAME` `Property`.
+    pub fn get_name(&self) -> Option<&str> {
+        self.property_value("NAME")
+    }
+
+    /// Set the [`RESOURCE-TYPE`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.3) `Property`
+    pub fn resource_type(&mut self, resource_type: ResourceType) -> &mut Self {
+        self.append_property(resource_type)
+    }
+
+    /// Gets the value of the `RESOURCE-TYPE` `Property`.
+    pub fn get_resource_type(&self) -> Option<ResourceType> {
+        self.property_value("RESOURCE-TYPE")?.parse().ok()
+    }
+
+    /// Adds a [`STRUCTURED-DATA`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.6) `Property`
+    pub fn structured_data(&mut self, data: StructuredData) -> &mut Self {
+        self.append_multi_property(data)
+    }
+
+    /// Gets all `STRUCTURED-DATA` properties.
+    pub fn get_structured_data(&self) -> Vec<StructuredData> {
+        structured_data(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_properties_set() {
+        let resource = Resource::new()
+            .name("Room 101")
+            .resource_type(ResourceType::Room)
+            .done();
+        assert_eq!(resource.get_name(), Some("Room 101"));
+        assert_eq!(resource.get_resource_type(), Some(ResourceType::Room));
+        assert_eq!(resource.get_description(), None);
+    }
+}
@@ -5,7 +5,7 @@ use super::*;
 /// VTODO  [(RFC 5545, Section 3.6.2 )](https://tools.ietf.org/html/rfc5545#section-3.6.2)
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Todo {
-    pub(super) inner: InnerComponent,
+    pub(crate) inner: InnerComponent,
 }
 
 impl Todo {
@@ -26,14 +26,14 @@ impl Todo {
     ///
     /// Ranges between 0 - 100
     pub fn percent_complete(&mut self, percent: u8) -> &mut Self {
-        self.add_property("PERCENT-COMPLETE", percent.to_string())
+        self.set::<PercentComplete>(percent)
     }
 
     /// Gets the [`PERCENT-COMPLETE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.8) property.
     ///
     /// Ranges between 0 - 100.
     pub fn get_percent_complete(&self) -> Option<u8> {
-        self.property_value("PERCENT-COMPLETE")?.parse().ok()
+        self.get::<PercentComplete>()
     }
 
     /// Set the [`DUE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.3) property
@@ -75,10 +75,6 @@ impl Todo {
     pub fn get_status(&self) -> Option<TodoStatus> {
         TodoStatus::from_str(self.property_value("STATUS")?)
     }
-
-    //pub fn repeats<R:Repeater+?Sized>(&mut self, repeat: R) -> &mut Self {
-    //    unimplemented!()
-    //}
 }
 
 #[cfg(test)]
@@ -0,0 +1,473 @@
+//! `DURATION` values as described in [RFC 5545, Section 3.3.6](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.6)
+
+use std::{fmt, ops::Neg, str::FromStr};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::{CalendarDateTime, DatePerhapsTime};
+
+/// An iCalendar `DURATION`, keeping nominal and exact parts apart.
+///
+/// Weeks and days are *nominal*: adding a day to a local date-time keeps the wall-clock time,
+/// even across a daylight saving time transition.
+/// Hours, minutes and seconds are *exact* and always add up to a fixed number of seconds.
+/// [`chrono::Duration`] is always exact, so it converts into a duration with only a time part.
+///
+/// ```
+/// # use icalendar::*;
+/// let duration: IcalDuration = "-P1DT2H30M".parse().unwrap();
+/// assert!(duration.is_negative());
+/// assert_eq!(duration.nominal_days(), -1);
+/// assert_eq!(duration.exact_seconds(), -(2 * 3600 + 30 * 60));
+/// assert_eq!(duration.to_string(), "-P1DT2H30M");
+///
+/// assert_eq!(IcalDuration::from(chrono::Duration::minutes(90)).to_string(), "PT1H30M");
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IcalDuration {
+    negative: bool,
+    weeks: u32,
+    days: u32,
+    seconds: u64,
+}
+
+impl IcalDuration {
+    fn with_sign(negative: bool, weeks: u32, days: u32, seconds: u64) -> Self {
+        let is_zero = weeks == 0 && days == 0 && seconds == 0;
+        // weeks only stay apart as long as they can be written as `dur-week`
+        let (weeks, days) = if days == 0 && seconds == 0 {
+            (weeks, days)
+        } else {
+            (0, days.saturating_add(weeks.saturating_mul(7)))
+        };
+        IcalDuration {
+            negative: negative && !is_zero,
+            weeks,
+            days,
+            seconds,
+        }
+    }
+
+    fn clamp(value: i64) -> u32 {
+        u32::try_from(value.unsigned_abs()).unwrap_or(u32::MAX)
+    }
+
+    /// A nominal duration of weeks, e.g. `P2W`.
+    pub fn weeks(weeks: i64) -> Self {
+        Self::with_sign(weeks < 0, Self::clamp(weeks), 0, 0)
+    }
+
+    /// A nominal duration of days, e.g. `P1D`.
+    pub fn days(days: i64) -> Self {
+        Self::with_sign(days < 0, 0, Self::clamp(days), 0)
+    }
+
+    /// An exact duration of hours, e.g. `PT1H`.
+    pub fn hours(hours: i64) -> Self {
+        Self::seconds(hours.saturating_mul(3600))
+    }
+
+    /// An exact duration of minutes, e.g. `PT15M`.
+    pub fn minutes(minutes: i64) -> Self {
+        Self::seconds(minutes.saturating_mul(60))
+    }
+
+    /// An exact duration of seconds, e.g. `PT30S`.
+    pub fn seconds(seconds: i64) -> Self {
+        Self::with_sign(seconds < 0, 0, 0, seconds.unsigned_abs())
+    }
+
+    /// Returns `true` if this duration points into the past.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The signed nominal part in days, weeks count as seven days.
+    pub fn nominal_days(&self) -> i64 {
+        let days = i64::from(self.weeks) * 7 + i64::from(self.days);
+        if self.negative {
+            -days
+        } else {
+            days
+        }
+    }
+
+    /// The signed exact part in seconds.
+    pub fn exact_seconds(&self) -> i64 {
+        let seconds = i64::try_from(self.seconds).unwrap_or(i64::MAX);
+        if self.negative {
+            -seconds
+        } else {
+            seconds
+        }
+    }
+
+    /// Approximates this duration as exact, assuming every day lasts 24 hours.
+    ///
+    /// Returns [`None`] if it is too long for a [`chrono::Duration`].
+    pub fn try_to_chrono(&self) -> Option<Duration> {
+        Duration::try_days(self.nominal_days())?
+            .checked_add(&Duration::try_seconds(self.exact_seconds())?)
+    }
+
+    fn add_to_naive(&self, date_time: NaiveDateTime) -> Option<NaiveDateTime> {
+        date_time
+            .checked_add_signed(Duration::try_days(self.nominal_days())?)?
+            .checked_add_signed(Duration::try_seconds(self.exact_seconds())?)
+    }
+
+    /// Adds this duration to a date-time.
+    ///
+    /// The nominal part is added to the local date, so `P1D` keeps the wall-clock time across
+    /// daylight saving time transitions. With the `chrono-tz` feature enabled the exact part
+    /// of date-times with a `TZID` is added in UTC, otherwise it is added to the local time.
+    pub fn checked_add_to(&self, date_time: &CalendarDateTime) -> Option<CalendarDateTime> {
+        let nominal = Duration::try_days(self.nominal_days())?;
+        let exact = Duration::try_seconds(self.exact_seconds())?;
+        Some(match date_time {
+            CalendarDateTime::Floating(date_time) => {
+                CalendarDateTime::Floating(self.add_to_naive(*date_time)?)
+            }
+            CalendarDateTime::Utc(date_time) => CalendarDateTime::Utc(
+                date_time
+                    .checked_add_signed(nominal)?
+                    .checked_add_signed(exact)?,
+            ),
+            CalendarDateTime::WithTimezone { date_time, tzid } => {
+                let local = date_time.checked_add_signed(nominal)?;
+                CalendarDateTime::WithTimezone {
+                    date_time: Self::add_exact_in_timezone(local, tzid, exact)?,
+                    tzid: tzid.clone(),
+                }
+            }
+        })
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    fn add_exact_in_timezone(
+        local: NaiveDateTime,
+        tzid: &str,
+        exact: Duration,
+    ) -> Option<NaiveDateTime> {
+        match tzid.parse::<chrono_tz::Tz>() {
+            // a nominal part landing in a gap still moves it forward
+            Ok(tz) if !exact.is_zero() => crate::components::date_time::resolve_local(&tz, &local)?
+                .checked_add_signed(exact)
+                .map(|date_time| date_time.naive_local()),
+            _ => local.checked_add_signed(exact),
+        }
+    }
+
+    #[cfg(not(feature = "chrono-tz"))]
+    fn add_exact_in_timezone(
+        local: NaiveDateTime,
+        _tzid: &str,
+        exact: Duration,
+    ) -> Option<NaiveDateTime> {
+        local.checked_add_signed(exact)
+    }
+
+    /// Adds this duration to a date or date-time.
+    ///
+    /// Dates stay dates as long as the duration is purely nominal,
+    /// otherwise they are treated as floating date-times at midnight.
+    pub fn checked_add_to_date_perhaps_time(
+        &self,
+        date_perhaps_time: &DatePerhapsTime,
+    ) -> Option<DatePerhapsTime> {
+        match date_perhaps_time {
+            DatePerhapsTime::Date(date) if self.seconds == 0 => date
+                .checked_add_signed(Duration::try_days(self.nominal_days())?)
+                .map(DatePerhapsTime::Date),
+            DatePerhapsTime::Date(date) => self
+                .add_to_naive(NaiveDate::and_hms_opt(date, 0, 0, 0)?)
+                .map(|date_time| DatePerhapsTime::DateTime(CalendarDateTime::Floating(date_time))),
+            DatePerhapsTime::DateTime(date_time) => self
+                .checked_add_to(date_time)
+                .map(DatePerhapsTime::DateTime),
+        }
+    }
+}
+
+impl Neg for IcalDuration {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::with_sign(!self.negative, self.weeks, self.days, self.seconds)
+    }
+}
+
+impl From<Duration> for IcalDuration {
+    fn from(duration: Duration) -> Self {
+        Self::seconds(duration.num_seconds())
+    }
+}
+
+impl From<&Duration> for IcalDuration {
+    fn from(duration: &Duration) -> Self {
+        Self::from(*duration)
+    }
+}
+
+impl fmt::Display for IcalDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "P")?;
+
+        // `dur-week` can not be combined with other parts
+        if self.days == 0 && self.seconds == 0 {
+            return write!(f, "{}W", self.weeks);
+        }
+
+        let days = u64::from(self.weeks) * 7 + u64::from(self.days);
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if self.seconds > 0 {
+            let (hours, minutes, seconds) = (
+                self.seconds / 3600,
+                self.seconds / 60 % 60,
+                self.seconds % 60,
+            );
+            write!(f, "T")?;
+            if hours > 0 {
+                write!(f, "{}H", hours)?;
+            }
+            if minutes > 0 || (hours > 0 && seconds > 0) {
+                write!(f, "{}M", minutes)?;
+            }
+            if seconds > 0 {
+                write!(f, "{}S", seconds)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for IcalDuration {
+    type Err = ();
+
+    /// Parses a `dur-value`.
+    ///
+    /// This is a bit more lenient than the RFC and accepts weeks combined with other parts.
+    /// Every unit may only occur once and in the order `W`, `D`, `H`, `M`, `S`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const UNITS: [(bool, char); 5] = [
+            (false, 'W'),
+            (false, 'D'),
+            (true, 'H'),
+            (true, 'M'),
+            (true, 'S'),
+        ];
+
+        let s = s.trim();
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let s = s.strip_prefix('P').ok_or(())?;
+        if s.is_empty() {
+            return Err(());
+        }
+
+        let (mut weeks, mut days, mut seconds) = (0u32, 0u32, 0u64);
+        let mut in_time = false;
+        let mut number = String::new();
+        let mut has_time_part = false;
+        let mut previous_unit = None;
+        for c in s.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                'T' if !in_time && number.is_empty() => in_time = true,
+                unit => {
+                    let position = UNITS.iter().position(|known| *known == (in_time, unit));
+                    if position.is_none() || position <= previous_unit {
+                        return Err(());
+                    }
+                    previous_unit = position;
+
+                    let value: u64 = number.parse().map_err(|_| ())?;
+                    number.clear();
+                    let unit_seconds = match unit {
+                        'W' => {
+                            weeks = u32::try_from(value).map_err(|_| ())?;
+                            continue;
+                        }
+                        'D' => {
+                            days = u32::try_from(value).map_err(|_| ())?;
+                            continue;
+                        }
+                        'H' => 3600,
+                        'M' => 60,
+                        _ => 1,
+                    };
+                    seconds = value
+                        .checked_mul(unit_seconds)
+                        .and_then(|value| seconds.checked_add(value))
+                        .ok_or(())?;
+                    has_time_part = true;
+                }
+            }
+        }
+        if !number.is_empty() || (in_time && !has_time_part) {
+            return Err(());
+        }
+        Ok(Self::with_sign(negative, weeks, days, seconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn parse_and_format() {
+        for (sample, expected) in [
+            ("P15DT5H0M20S", "P15DT5H0M20S"),
+            ("P7W", "P7W"),
+            ("-PT15M", "-PT15M"),
+            ("+PT1H", "PT1H"),
+            ("PT3600S", "PT1H"),
+            ("PT90M", "PT1H30M"),
+            ("P1DT1H0M1S", "P1DT1H0M1S"),
+            ("P1W2D", "P9D"),
+            ("PT0S", "P0W"),
+        ] {
+            let duration = IcalDuration::from_str(sample).unwrap();
+            assert_eq!(duration.to_string(), expected, "{sample}");
+            assert_eq!(IcalDuration::from_str(expected), Ok(duration));
+        }
+
+        for invalid in [
+            "",
+            "P",
+            "PT",
+            "P1H",
+            "PT1D",
+            "1D",
+            "P1",
+            "P-1D",
+            "PT1S1S",
+            "PT1M1H",
+            "P1D1W",
+            "P1DT",
+            "PT18446744073709551615S1S",
+            "PT18446744073709551615H",
+        ] {
+            assert_eq!(IcalDuration::from_str(invalid), Err(()), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn nominal_and_exact_parts() {
+        let duration = IcalDuration::from_str("-P1W2DT3H").unwrap();
+        assert_eq!(duration.nominal_days(), -9);
+        assert_eq!(duration.exact_seconds(), -3 * 3600);
+        assert_eq!(
+            duration.try_to_chrono(),
+            Some(-(Duration::days(9) + Duration::hours(3)))
+        );
+        assert_eq!(-duration, IcalDuration::from_str("P1W2DT3H").unwrap());
+    }
+
+    #[test]
+    fn too_long_for_chrono() {
+        let duration = IcalDuration::from_str("PT9223372036854775807S").unwrap();
+        assert_eq!(duration.try_to_chrono(), None);
+        assert_eq!((-duration).try_to_chrono(), None);
+    }
+
+    #[test]
+    fn from_chrono_is_exact() {
+        assert_eq!(
+            IcalDuration::from(Duration::days(1)),
+            IcalDuration::hours(24)
+        );
+        assert_eq!(
+            IcalDuration::from(-Duration::minutes(15)).to_string(),
+            "-PT15M"
+        );
+    }
+
+    #[test]
+    fn add_to_date_times() {
+        let date_time = NaiveDate::from_ymd_opt(2024, 3, 30)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+        let utc = Utc.from_utc_datetime(&date_time);
+
+        assert_eq!(
+            IcalDuration::days(1).checked_add_to(&CalendarDateTime::Utc(utc)),
+            Some(CalendarDateTime::Utc(utc + Duration::days(1)))
+        );
+        assert_eq!(
+            IcalDuration::from_str("P1DT1H")
+                .unwrap()
+                .checked_add_to(&CalendarDateTime::Floating(date_time)),
+            Some(CalendarDateTime::Floating(
+                date_time + Duration::days(1) + Duration::hours(1)
+            ))
+        );
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 30).unwrap();
+        assert_eq!(
+            IcalDuration::weeks(1).checked_add_to_date_perhaps_time(&date.into()),
+            Some(NaiveDate::from_ymd_opt(2024, 4, 6).unwrap().into())
+        );
+        assert_eq!(
+            IcalDuration::hours(9).checked_add_to_date_perhaps_time(&date.into()),
+            Some(date_time.into())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn add_across_dst() {
+        // DST starts in Berlin at 2024-03-31 02:00
+        let before = CalendarDateTime::WithTimezone {
+            date_time: NaiveDate::from_ymd_opt(2024, 3, 30)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+            tzid: "Europe/Berlin".into(),
+        };
+        let at = |day, hour| CalendarDateTime::WithTimezone {
+            date_time: NaiveDate::from_ymd_opt(2024, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap(),
+            tzid: "Europe/Berlin".into(),
+        };
+
+        // nominal: same wall-clock time
+        assert_eq!(
+            IcalDuration::days(1).checked_add_to(&before),
+            Some(at(31, 9))
+        );
+        // exact: 24 hours later is one hour later on the wall-clock
+        assert_eq!(
+            IcalDuration::hours(24).checked_add_to(&before),
+            Some(at(31, 10))
+        );
+
+        // a nominal part landing in the gap moves forward before the exact part is added
+        let in_gap = |day| at(day, 2);
+        assert_eq!(
+            IcalDuration::days(1).checked_add_to(&in_gap(30)),
+            Some(in_gap(31))
+        );
+        assert_eq!(
+            "P1DT1H"
+                .parse::<IcalDuration>()
+                .unwrap()
+                .checked_add_to(&in_gap(30)),
+            Some(at(31, 4))
+        );
+    }
+}
@@ -22,39 +22,48 @@ macro_rules! print_crlf {
     ($fmt:expr, $($arg:tt)*) => (print!(concat!($fmt, "\r\n"), $($arg)*));
 }
 
-macro_rules! write_crlf {
-    ($dst:expr) => (
-        write!($dst, "\r\n")
-    );
-    ($dst:expr, $fmt:expr) => (
-        write!($dst, concat!($fmt, "\r\n"))
-    );
-    ($dst:expr, $fmt:expr, $($arg:tt)*) => (
-        write!($dst, concat!($fmt, "\r\n"), $($arg)*)
-    );
-}
-
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
 #[cfg(all(test, feature = "parser"))]
 #[macro_use]
 mod assert;
 
-//pub mod period;
+#[cfg(feature = "caldav")]
+pub mod caldav;
 mod calendar;
 mod components;
+mod duration;
 #[cfg(feature = "parser")]
 pub mod parser;
+mod period;
 mod properties;
+mod repeats;
+pub mod typed;
 mod value_types;
+pub mod vendor;
+mod write_options;
 
 pub use crate::{
-    calendar::{Calendar, CalendarComponent},
+    calendar::{
+        Calendar, CalendarComponent, CalendarDiff, CalendarIndex, ComponentChange, ComponentKey,
+        DiffOptions, EntryId, IndexedOccurrence, LatestRevision, MergeConflict, MergeReport,
+        MergeStrategy, ParameterChange, PropertyChange, Winner,
+    },
     components::{
-        alarm::{Alarm, Related, Trigger},
+        alarm::{Action, Alarm, Proximity, Related, Trigger},
         date_time::{CalendarDateTime, DatePerhapsTime},
-        Component, Event, EventLike, Todo, Venue,
+        Component, Event, EventLike, FreeBusy, FreeBusyType, Location, Participant, Resource, Todo,
+        Venue,
+    },
+    duration::IcalDuration,
+    period::{Period, PeriodEnd},
+    properties::{
+        Attachment, Class, Color, Conference, EventStatus, Feature, Image, ImageDisplay, Parameter,
+        ParticipantType, Property, ResourceType, StructuredData, StyledDescription, TodoStatus,
     },
-    properties::{Class, EventStatus, Parameter, Property, TodoStatus},
+    repeats::{Frequency, Occurrences, RecurrenceRule, WeekdayNum},
     value_types::ValueType,
+    write_options::{LineEnding, PropertyOrder, WriteOptions},
 };
 
 #[cfg(feature = "chrono-tz")]
@@ -1,8 +1,11 @@
 use crate::calendar::CalendarComponent;
 
-use super::{components::LikeComponent, read_calendar, unfold, Component, Property};
+use super::{components::LikeComponent, read_calendar_folded, Component, Property};
 use core::{fmt, str::FromStr};
 
+#[cfg(test)]
+use super::read_calendar;
+
 /// Helper-type for reserialization
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -95,7 +98,7 @@ impl FromStr for crate::Calendar {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let from_parsed = crate::Calendar::from(read_calendar(&unfold(s))?);
+        let from_parsed = crate::Calendar::from(read_calendar_folded(s)?);
         Ok(from_parsed)
     }
 }
@@ -3,7 +3,7 @@ use std::{fmt, str::FromStr};
 use chrono::Utc;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, tag_no_case},
     combinator::{all_consuming, complete, cut},
     error::{context, convert_error, ContextError, ParseError, VerboseError},
     multi::{many0, many_till},
@@ -19,7 +19,7 @@ use super::parameters::Parameter;
 use super::{
     parsed_string::ParseString,
     properties::property,
-    unfold,
+    read_components_folded,
     utils::{line, line_separated, valid_key_sequence_cow},
     Property,
 };
@@ -30,6 +30,7 @@ use pretty_assertions::assert_eq;
 use crate::{
     calendar::CalendarComponent,
     components::{date_time::format_utc_date_time, InnerComponent, Other},
+    WriteOptions,
 };
 
 /// The parsing equivalent of [`crate::components::Component`]
@@ -53,7 +54,7 @@ impl<'a> Component<'a> {
 }
 
 impl Component<'_> {
-    pub fn find_prop<S: AsRef<str>>(&self, name: S) -> Option<&Property> {
+    pub fn find_prop<S: AsRef<str>>(&self, name: S) -> Option<&Property<'_>> {
         self.properties
             .iter()
             .find(|prop| prop.name == name.as_ref())
@@ -67,7 +68,8 @@ pub(crate) trait LikeComponent<'a> {
 
     /// Writes `Component` into a `Writer` using `std::fmt`.
     fn fmt_write<W: fmt::Write>(&'a self, out: &mut W) -> Result<(), fmt::Error> {
-        write_crlf!(out, "BEGIN:{}", self.name())?;
+        let options = WriteOptions::default();
+        options.write_line(out, &format!("BEGIN:{}", self.name()))?;
 
         if self.name().to_lowercase() == "calendar" {
             if !self
@@ -76,7 +78,7 @@ pub(crate) trait LikeComponent<'a> {
                 .any(|property| property.name == "DTSTAMP")
             {
                 let now = Utc::now();
-                write_crlf!(out, "DTSTAMP:{}", format_utc_date_time(now))?;
+                options.write_line(out, &format!("DTSTAMP:{}", format_utc_date_time(now)))?;
             }
 
             if !self
@@ -84,7 +86,7 @@ pub(crate) trait LikeComponent<'a> {
                 .iter()
                 .any(|property| property.name == "UID")
             {
-                write_crlf!(out, "UID:{}", Uuid::new_v4())?;
+                options.write_line(out, &format!("UID:{}", Uuid::new_v4()))?;
             }
         }
         for property in self.properties() {
@@ -95,8 +97,7 @@ pub(crate) trait LikeComponent<'a> {
             component.fmt_write(out)?;
         }
 
-        write_crlf!(out, "END:{}", self.name())?;
-        Ok(())
+        options.write_line(out, &format!("END:{}", self.name()))
     }
 }
 
@@ -158,11 +159,12 @@ impl From<Component<'_>> for InnerComponent {
 
 impl<'a> From<Component<'a>> for CalendarComponent {
     fn from(component: Component<'a>) -> CalendarComponent {
-        use crate::{Event, Todo, Venue};
+        use crate::{Event, FreeBusy, Todo, Venue};
         match component.name.as_ref() {
             "VEVENT" => Event::from(InnerComponent::from(component)).into(),
             "VTODO" => Todo::from(InnerComponent::from(component)).into(),
             "VVENUE" => Venue::from(InnerComponent::from(component)).into(),
+            "VFREEBUSY" => FreeBusy::from(InnerComponent::from(component)).into(),
             _ => Other::from((component.name.to_string(), InnerComponent::from(component))).into(),
         }
     }
@@ -171,12 +173,28 @@ impl<'a> From<Component<'a>> for CalendarComponent {
 impl FromStr for CalendarComponent {
     type Err = String;
 
+    /// Parses exactly one component, use [`crate::Calendar`] for several of them.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let from_parsed = CalendarComponent::from(read_component(&unfold(s))?);
-        Ok(from_parsed)
+        let mut components = read_components_folded(s)?;
+        match components.len() {
+            0 => Err(String::from("error: no component found")),
+            1 => Ok(CalendarComponent::from(components.remove(0))),
+            n => Err(format!("error: expected one component, found {}", n)),
+        }
     }
 }
 
+#[test]
+fn component_from_str_needs_single_root() {
+    let event = "BEGIN:VEVENT\r\nSUMMARY:a\r\nEND:VEVENT\r\n";
+    assert!(event.parse::<CalendarComponent>().is_ok());
+    assert!("".parse::<CalendarComponent>().is_err());
+    assert_eq!(
+        event.repeat(2).parse::<CalendarComponent>(),
+        Err(String::from("error: expected one component, found 2"))
+    );
+}
+
 #[test]
 #[rustfmt::skip]
 fn parse_empty_component1() {
@@ -216,10 +234,10 @@ END:VEVENT
 
     let expectation = Component{name: "VEVENT".into(), properties: vec![
             Property{name: "KEY".into(), val: "VALUE".into(), params: vec![
-                Parameter::new_ref("foo", Some("bar")),
+                Parameter::new_ref("FOO", Some("bar")),
             ]},
             Property{name: "KEY".into(), val: "VALUE".into(), params: vec![
-                Parameter::new_ref("foo", Some("bar")),
+                Parameter::new_ref("FOO", Some("bar")),
                 Parameter::new_ref("DATE", Some("20170218")),
             ]},
             ], components: vec![]};
@@ -237,6 +255,7 @@ enum ComponentChild<'a> {
     Component(Component<'a>),
 }
 
+#[cfg(test)]
 pub fn read_component(input: &str) -> Result<Component<'_>, String> {
     component(input)
         .finish()
@@ -248,6 +267,7 @@ pub fn component<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Component<'a>, E> {
     let (input, name) = line("BEGIN:", valid_key_sequence_cow)(input)?;
+    let name = name.into_uppercase();
 
     let (input, (properties, components)) = many_till(
         cut(context(
@@ -257,7 +277,10 @@ pub fn component<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
                 line_separated(property).map(ComponentChild::Property),
             )),
         )),
-        line("END:", cut(context("mismatching end", tag(name.as_str())))),
+        line(
+            "END:",
+            cut(context("mismatching end", tag_no_case(name.as_str()))),
+        ),
     )
     .map(|(body_elements, _)| {
         let mut properties = Vec::new();
@@ -458,11 +481,11 @@ fn test_multi_properties() {
                 params: vec![
                     Parameter {
                         key: "EMAIL".into(),
-                        val: Some("email@example.com".into()),
+                        values: vec!["email@example.com".into()],
                     },
                     Parameter {
                         key: "CUTYPE".into(),
-                        val: Some("INDIVIDUAL".into()),
+                        values: vec!["INDIVIDUAL".into()],
                     },
                 ],
             }
@@ -473,11 +496,11 @@ fn test_multi_properties() {
                 params: vec![
                     Parameter {
                         key: "EMAIL".into(),
-                        val: Some("dmail@example.com".into()),
+                        values: vec!["dmail@example.com".into()],
                     },
                     Parameter {
                         key: "CUTYPE".into(),
-                        val: Some("INDIVIDUAL".into()),
+                        values: vec!["INDIVIDUAL".into()],
                     },
                 ],
             }
@@ -512,6 +535,36 @@ fn test_faulty_component() {
     );
 }
 
+#[test]
+fn lowercase_names() {
+    use crate::Component as _;
+
+    let sample = "begin:vevent\nsummary;language=en:Lunch\nattendee;cn=Alice:mailto:alice@example.com\ndtStart:20240108T120000Z\nend:VEVENT\n";
+    let parsed = read_component(sample).unwrap();
+    assert_eq!(parsed.name, "VEVENT");
+    assert!(parsed.find_prop("SUMMARY").is_some());
+
+    let component = CalendarComponent::from(parsed);
+    let event = component.as_event().expect("lowercase vevent is an event");
+    assert_eq!(event.get_summary(), Some("Lunch"));
+    assert_eq!(event.property_value("summary"), Some("Lunch"));
+    assert!(event.get_start().is_some());
+    assert_eq!(
+        event.multi_properties()["ATTENDEE"]
+            .first()
+            .map(|attendee| attendee.params()["CN"].value()),
+        Some("Alice")
+    );
+    assert_eq!(
+        event
+            .to_string()
+            .lines()
+            .filter(|line| line.starts_with("SUMMARY;LANGUAGE=en:Lunch"))
+            .count(),
+        1
+    );
+}
+
 pub fn components<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Vec<Component<'a>>, E> {
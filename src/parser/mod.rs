@@ -1,12 +1,16 @@
 //! # Parsing iCalendar document parser
 //!
-//! I would have loved to provide a zero-copy parser here however the *Internet Calendaring and Scheduling Core Object Specification (iCalendar)*
-//! allows, nay demands special line folding:
+//! The *Internet Calendaring and Scheduling Core Object Specification (iCalendar)* allows, nay demands special line folding:
 //! >    Lines of text SHOULD NOT be longer than 75 octets, excluding the line break.  Long content lines SHOULD be split into a multiple line representations using a line "folding" technique.
 //! > -- [rfc5545 3.1]
 //!
-//! For this reason parsing iCal is a bit indirect.
-//! In this module you find the following functions to parser iCalendar document.
+//! In this module you find the following functions to parse iCalendar documents.
+//! [`read_calendar_folded()`] and [`read_components_folded()`] walk the folded input line by line with [`ContentLines`],
+//! they borrow from the input and only allocate for content lines that are folded or values that are escaped.
+//! Errors reference the line numbers of the input.
+//! With the `rayon` feature `read_calendar_parallel()` parses the top level components on all cores.
+//!
+//! The older functions expect input that was unfolded up front:
 //! [`unfold()`] will unfold the iCal content and turn it into the nice machine-readable format it ought to be.
 //! [`read_calendar_simple()`] returns a Vector of [`Component`]s
 //! [`read_calendar()`] does the same thing but produces nicer parsing errors with line numbers (referencing the normalized content).
@@ -27,18 +31,24 @@ use nom::{error::convert_error, error::VerboseError, Finish};
 
 mod calendar;
 pub(crate) mod components;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod parameters;
 mod parsed_string;
 mod properties;
 #[cfg(test)]
 mod tests;
+mod tokenizer;
 mod utils;
 
 pub use calendar::Calendar;
 pub use components::Component;
+#[cfg(feature = "rayon")]
+pub use parallel::read_calendar_parallel;
 pub use parameters::Parameter;
 pub use parsed_string::ParseString;
 pub use properties::Property;
+pub use tokenizer::{ContentLine, ContentLines};
 
 use components::*;
 
@@ -59,28 +69,54 @@ pub fn read_calendar_simple(input: &str) -> Result<Vec<Component<'_>>, nom::erro
 pub fn read_calendar(input: &str) -> Result<Calendar<'_>, String> {
     components(input)
         .finish()
-        .map(|(_, mut components)| {
-            let root_is_calendar = components
-                .first()
-                .map(|first_root| first_root.name == "VCALENDAR")
-                .unwrap_or(false);
-
-            if root_is_calendar {
-                let root = components.swap_remove(0);
-                Calendar {
-                    properties: root.properties,
-                    components: root.components,
-                }
-            } else {
-                Calendar {
-                    components,
-                    properties: Vec::new(),
-                }
-            }
-        })
+        .map(|(_, components)| calendar_from_roots(components))
         .map_err(|e: VerboseError<&str>| format!("error: {}", convert_error(input, e.clone())))
 }
 
+/// Parse folded iCalendar file content into a [`Calendar`]
+///
+/// Unlike [`read_calendar()`] this does not need the input to be [`unfold()`]ed first,
+/// line numbers in errors refer to the input as it is.
+///
+/// ```
+/// # use icalendar::parser::read_calendar_folded;
+/// let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:a fol\r\n ded summary\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+/// let calendar = read_calendar_folded(input).unwrap();
+/// let event = calendar.components.first().unwrap();
+/// assert_eq!(event.find_prop("SUMMARY").unwrap().val, "a folded summary");
+/// ```
+pub fn read_calendar_folded(input: &str) -> Result<Calendar<'_>, String> {
+    tokenizer::components(input).map(calendar_from_roots)
+}
+
+/// Parse folded iCalendar file content into an array of [`Component`]s
+///
+/// Like [`read_components()`] but without the need to [`unfold()`] the input first.
+pub fn read_components_folded(input: &str) -> Result<Vec<Component<'_>>, String> {
+    tokenizer::components(input)
+}
+
+/// Unwraps a single `VCALENDAR` root.
+fn calendar_from_roots(mut components: Vec<Component<'_>>) -> Calendar<'_> {
+    let root_is_calendar = components
+        .first()
+        .map(|first_root| first_root.name == "VCALENDAR")
+        .unwrap_or(false);
+
+    if root_is_calendar {
+        let root = components.swap_remove(0);
+        Calendar {
+            properties: root.properties,
+            components: root.components,
+        }
+    } else {
+        Calendar {
+            components,
+            properties: Vec::new(),
+        }
+    }
+}
+
 #[test]
 fn begin_crash() {
     assert!(read_calendar("BEGIN:").is_ok());
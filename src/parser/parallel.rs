@@ -0,0 +1,206 @@
+use rayon::prelude::*;
+
+use super::{
+    calendar_from_roots,
+    tokenizer::{build, Boundary, ContentLine, ContentLines},
+    Calendar,
+};
+
+/// A document split into the content lines of the calendar itself
+/// and the text of each component directly below it.
+struct Outline<'a> {
+    calendar: Vec<ContentLine<'a>>,
+    /// Number of lines preceding the component and its text
+    components: Vec<(usize, &'a str)>,
+}
+
+/// Splits at the `BEGIN:` lines of the children of a leading `VCALENDAR`,
+/// or of the root components if there is none.
+///
+/// Roots following a leading `VCALENDAR` stay in the calendar lines as a whole,
+/// so they end up where [`calendar_from_roots`] puts them.
+fn outline(input: &str) -> Outline<'_> {
+    let mut outline = Outline {
+        calendar: Vec::new(),
+        components: Vec::new(),
+    };
+    let mut lines = ContentLines::new(input);
+    let mut depth = 0_usize;
+    let mut roots = 0_usize;
+    let mut split_depth = None;
+    let mut component_start = None;
+
+    loop {
+        let (offset, line_number) = (input.len() - lines.rest().len(), lines.line_number());
+        let line = match lines.next() {
+            Some(line) => line,
+            None => break,
+        };
+
+        match Boundary::of(&line.text) {
+            Some(Boundary::Begin(name)) => {
+                let split_at =
+                    *split_depth.get_or_insert(usize::from(name.eq_ignore_ascii_case("VCALENDAR")));
+                if depth == 0 {
+                    roots += 1;
+                }
+                if depth == split_at && (split_at == 0 || roots == 1) {
+                    component_start = Some((offset, line_number));
+                }
+                depth += 1;
+            }
+            Some(Boundary::End(_)) if depth > 0 => {
+                depth -= 1;
+                if let Some((start, first_line)) =
+                    component_start.filter(|_| Some(depth) == split_depth)
+                {
+                    let end = input.len() - lines.rest().len();
+                    if let Some(text) = input.get(start..end) {
+                        outline.components.push((first_line, text));
+                    }
+                    component_start = None;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+
+        if component_start.is_none() {
+            outline.calendar.push(line);
+        }
+    }
+
+    // an unterminated component is parsed on its own to report the missing END
+    if let Some((start, first_line)) = component_start {
+        if let Some(text) = input.get(start..) {
+            outline.components.push((first_line, text));
+        }
+    }
+    outline
+}
+
+/// Parse folded iCalendar file content into a [`Calendar`], parsing its components in parallel
+///
+/// This produces the same [`Calendar`] as [`read_calendar_folded()`](super::read_calendar_folded).
+/// The document is split at the `BEGIN:` lines of its top level components,
+/// those are parsed on the [`rayon`] thread pool.
+/// Errors of the calendar itself are reported before those of its components,
+/// so with several errors in a document the reported one may differ.
+///
+/// ```
+/// # use icalendar::parser::{read_calendar_folded, read_calendar_parallel};
+/// let input = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+///              BEGIN:VEVENT\r\nSUMMARY:first\r\nEND:VEVENT\r\n\
+///              BEGIN:VTODO\r\nSUMMARY:second\r\nEND:VTODO\r\n\
+///              END:VCALENDAR\r\n";
+/// let calendar = read_calendar_parallel(input).unwrap();
+/// assert_eq!(calendar.components.len(), 2);
+/// assert_eq!(calendar.properties, read_calendar_folded(input).unwrap().properties);
+/// ```
+pub fn read_calendar_parallel(input: &str) -> Result<Calendar<'_>, String> {
+    let Outline {
+        calendar,
+        components,
+    } = outline(input);
+
+    let calendar = build(calendar).map(calendar_from_roots);
+    let components = components
+        .into_par_iter()
+        // most components are a handful of lines, parse them in batches
+        .with_min_len(32)
+        .map(|(first_line, text)| build(ContentLines::starting_at(text, first_line)))
+        .collect::<Vec<_>>();
+
+    let mut calendar = calendar?;
+    for parsed in components {
+        calendar.components.extend(parsed?);
+    }
+    Ok(calendar)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        parser::{read_calendar, read_calendar_folded, unfold},
+        Component, Event, Todo,
+    };
+
+    fn assert_same(input: &str) {
+        let parallel = read_calendar_parallel(input).unwrap();
+        let sequential = read_calendar_folded(input).unwrap();
+        assert_eq!(parallel.properties, sequential.properties);
+        assert_eq!(parallel.components, sequential.components);
+    }
+
+    #[test]
+    fn same_as_read_calendar() {
+        let calendar = (0..200)
+            .map(|i| {
+                if i % 3 == 0 {
+                    Todo::new().summary(&format!("todo {}", i)).done().into()
+                } else {
+                    Event::new()
+                        .summary(&format!("event {}", i))
+                        .description("a description that is long enough to be folded at least once, maybe twice")
+                        .done()
+                        .into()
+                }
+            })
+            .collect::<Vec<crate::CalendarComponent>>()
+            .into_iter()
+            .collect::<crate::Calendar>()
+            .to_string();
+
+        let parallel = read_calendar_parallel(&calendar).unwrap();
+        let unfolded = unfold(&calendar);
+        let reference = read_calendar(&unfolded).unwrap();
+        assert_eq!(parallel.components.len(), 200);
+        assert_eq!(parallel.properties, reference.properties);
+        assert_eq!(parallel.components, reference.components);
+    }
+
+    #[test]
+    fn nested_and_without_calendar() {
+        assert_same(
+            "BEGIN:VEVENT\nUID:1\nBEGIN:VALARM\nACTION:DISPLAY\nEND:VALARM\nEND:VEVENT\n\
+             BEGIN:VTODO\nUID:2\nEND:VTODO\n",
+        );
+        assert_same(
+            "BEGIN:VCALENDAR\nBEGIN:VTIMEZONE\nTZID:Europe/Berlin\nBEGIN:STANDARD\n\
+             DTSTART:19701025T030000\nEND:STANDARD\nEND:VTIMEZONE\nX-WR-CALNAME:cal\n\
+             BEGIN:VEVENT\nUID:1\nEND:VEVENT\nEND:VCALENDAR\n",
+        );
+        assert_same("");
+    }
+
+    #[test]
+    fn concatenated_calendars() {
+        assert_same(
+            "BEGIN:VCALENDAR\nX-WR-CALNAME:first\nBEGIN:VEVENT\nUID:1\nEND:VEVENT\nEND:VCALENDAR\n\
+             BEGIN:VCALENDAR\nX-WR-CALNAME:second\nBEGIN:VEVENT\nUID:2\nEND:VEVENT\nEND:VCALENDAR\n",
+        );
+        assert_same(
+            "BEGIN:VEVENT\nUID:1\nEND:VEVENT\n\
+             BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:2\nEND:VEVENT\nEND:VCALENDAR\n",
+        );
+    }
+
+    #[test]
+    fn errors_refer_to_input_lines() {
+        let input = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:1\nEND:VEVENT\n\
+                     BEGIN:VEVENT\nDESCRIPTION:fol\n ded\nEND;X=Y:oops\nEND:VEVENT\n\
+                     BEGIN:VTODO\nEND:VEVENT\nEND:VCALENDAR\n";
+        let error = read_calendar_parallel(input).unwrap_err();
+        assert!(error.starts_with("error in line 8:"), "{}", error);
+        assert_eq!(error, read_calendar_folded(input).unwrap_err());
+
+        assert!(
+            read_calendar_parallel("BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:1\n")
+                .unwrap_err()
+                .starts_with("error: missing END:")
+        );
+    }
+}
@@ -1,11 +1,11 @@
 use nom::{
     branch::alt,
-    bytes::complete::{is_not, tag, take_till1},
+    bytes::complete::{tag, take_till1, take_while},
     character::complete::space0,
     combinator::{eof, opt},
     error::{convert_error, ContextError, ParseError, VerboseError},
     multi::many0,
-    sequence::{delimited, preceded, separated_pair, tuple},
+    sequence::{delimited, pair, preceded, separated_pair, tuple},
     Finish, IResult, Parser,
 };
 
@@ -15,18 +15,37 @@ use nom::error::ErrorKind;
 use super::{parsed_string::ParseString, utils::valid_key_sequence_cow};
 
 /// Zero-copy version of [`crate::properties::Parameter`]
+///
+/// Values are unquoted and [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868) decoded,
+/// a list like `MEMBER="a","b"` has one entry per element.
 #[derive(PartialEq, Eq, Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameter<'a> {
     pub key: ParseString<'a>,
-    pub val: Option<ParseString<'a>>,
+    pub values: Vec<ParseString<'a>>,
 }
 
 impl<'a> Parameter<'a> {
     pub fn new_ref(key: &'a str, val: Option<&'a str>) -> Parameter<'a> {
         Parameter {
             key: key.into(),
-            val: val.map(Into::into),
+            values: val.map(Into::into).into_iter().collect(),
+        }
+    }
+
+    /// Returns the first value, if any.
+    pub fn value(&self) -> Option<&ParseString<'a>> {
+        self.values.first()
+    }
+
+    pub fn into_owned(self) -> Parameter<'static> {
+        Parameter {
+            key: self.key.into_owned(),
+            values: self
+                .values
+                .into_iter()
+                .map(ParseString::into_owned)
+                .collect(),
         }
     }
 }
@@ -44,9 +63,12 @@ impl<'a> TryFrom<&'a str> for Parameter<'a> {
 
 impl From<Parameter<'_>> for crate::properties::Parameter {
     fn from(parameter: Parameter<'_>) -> crate::properties::Parameter {
-        crate::properties::Parameter::new(
+        if parameter.values.is_empty() {
+            return crate::properties::Parameter::new(parameter.key.as_ref(), "");
+        }
+        crate::properties::Parameter::new_list(
             parameter.key.as_ref(),
-            parameter.val.as_ref().map(AsRef::as_ref).unwrap_or(""),
+            parameter.values.iter().map(ParseString::as_str),
         )
     }
 }
@@ -84,14 +106,14 @@ fn test_parameter() {
         ";KEY",
         Parameter {
             key: "KEY".into(),
-            val: None,
+            values: vec![],
         }
     );
 
     assert_parser!(
         parameter,
         ";email=rust@hoodie.de",
-        Parameter::new_ref("email", Some("rust@hoodie.de"))
+        Parameter::new_ref("EMAIL", Some("rust@hoodie.de"))
     );
 }
 
@@ -109,30 +131,50 @@ fn test_quirky_parameter() {
     assert_parser!(parameter, ";KEY=", Parameter::new_ref("KEY", None));
 }
 
-fn remove_empty_string(input: Option<&str>) -> Option<&str> {
-    if let Some(input) = input {
-        return if input.is_empty() { None } else { Some(input) };
-    }
-    None
-}
-
-fn remove_empty_string_parsed(input: Option<ParseString<'_>>) -> Option<ParseString<'_>> {
-    if let Some(input) = input {
-        return if input.as_ref().is_empty() {
-            None
-        } else {
-            Some(input)
-        };
-    }
-    None
-}
-
 fn parameter<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Parameter<'a>, E> {
     alt((pair_parameter, base_parameter))(input)
 }
 
+/// A quoted value followed by more values separated by commas or a single unquoted value.
+///
+/// Commas in unquoted values are kept, because many producers forget to quote them,
+/// after a quoted value unquoted ones like in `MEMBER="a",b` are elements of the list.
+fn parameter_values<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Vec<ParseString<'a>>, E> {
+    fn quoted<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+        delimited(tag("\""), take_while(|x| x != '"'), tag("\""))(input)
+    }
+
+    alt((
+        eof.map(|_| vec![]),
+        pair(
+            quoted,
+            many0(preceded(
+                tag(","),
+                alt((quoted, take_while(|x| !matches!(x, ',' | ';' | ':' | '"')))),
+            )),
+        )
+        .map(|(first, rest)| {
+            std::iter::once(first)
+                .chain(rest)
+                .map(ParseString::from)
+                .collect()
+        }),
+        take_till1(|x| x == ';' || x == ':').map(|value: &str| vec![value.into()]),
+    ))
+    .map(|values: Vec<ParseString<'a>>| {
+        values
+            .into_iter()
+            .filter(|value| !value.as_str().is_empty())
+            .map(ParseString::decode_parameter_value)
+            .collect()
+    })
+    .parse(input)
+}
+
 fn pair_parameter<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Parameter<'a>, E> {
@@ -141,17 +183,12 @@ fn pair_parameter<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
         separated_pair(
             valid_key_sequence_cow, //key
             tag("="),
-            opt(alt((
-                eof,
-                delimited(tag("\""), is_not("\""), tag("\"")),
-                take_till1(|x| x == ';' || x == ':'),
-            )))
-            .map(remove_empty_string),
+            opt(parameter_values).map(Option::unwrap_or_default),
         ),
     )
-    .map(|(key, val)| Parameter {
-        key,
-        val: val.map(ParseString::from),
+    .map(|(key, values)| Parameter {
+        key: key.into_uppercase(),
+        values,
     })
     .parse(input)
 }
@@ -164,21 +201,70 @@ fn base_parameter<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
             tuple((tag(";"), space0)),
             valid_key_sequence_cow, //key
         ),
-        opt(preceded(
-            tag("="),
-            alt((
-                eof,
-                delimited(tag("\""), is_not("\""), tag("\"")),
-                take_till1(|x| x == ';' || x == ':'),
-            ))
-            .map(ParseString::from),
-        ))
-        .map(remove_empty_string_parsed),
+        opt(preceded(tag("="), parameter_values)).map(Option::unwrap_or_default),
     ))
-    .map(|(key, val)| Parameter { key, val })
+    .map(|(key, values)| Parameter {
+        key: key.into_uppercase(),
+        values,
+    })
     .parse(input)
 }
 
+#[test]
+fn test_parameter_list_values() {
+    assert_parser!(
+        parameter,
+        ";MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\"",
+        Parameter {
+            key: "MEMBER".into(),
+            values: vec!["mailto:a@example.com".into(), "mailto:b@example.com".into()],
+        }
+    );
+    assert_parser!(
+        parameter,
+        ";X-LIST=\"a:b\",c d,\"e\"",
+        Parameter {
+            key: "X-LIST".into(),
+            values: vec!["a:b".into(), "c d".into(), "e".into()],
+        }
+    );
+    assert_parser!(
+        parameter,
+        ";TYPE=fax,voice",
+        Parameter::new_ref("TYPE", Some("fax,voice"))
+    );
+}
+
+#[test]
+fn test_parameter_caret_encoding() {
+    assert_parser!(
+        parameter,
+        ";X-ADDRESS=\"Pittsburgh Pirates^n115 Federal St^nPittsburgh, PA 15212\"",
+        Parameter {
+            key: "X-ADDRESS".into(),
+            values: vec![
+                String::from("Pittsburgh Pirates\n115 Federal St\nPittsburgh, PA 15212").into()
+            ],
+        }
+    );
+    assert_parser!(
+        parameter,
+        ";CN=George Herman ^'Babe^' Ruth",
+        Parameter {
+            key: "CN".into(),
+            values: vec![String::from("George Herman \"Babe\" Ruth").into()],
+        }
+    );
+    assert_parser!(
+        parameter,
+        ";X-CARET=1^^2^x",
+        Parameter {
+            key: "X-CARET".into(),
+            values: vec![String::from("1^2^x").into()],
+        }
+    );
+}
+
 // parameter list
 #[test]
 pub fn parse_parameter_list() {
@@ -35,22 +35,28 @@ impl<'a> ParseString<'a> {
         }
     }
 
+    /// Converts a name to upper case, borrowing it if it already is.
+    pub fn into_uppercase(self) -> ParseString<'a> {
+        if self.0.bytes().any(|b| b.is_ascii_lowercase()) {
+            self.0.to_ascii_uppercase().into()
+        } else {
+            self
+        }
+    }
+
+    /// Decodes the [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868) escapes of a parameter value.
+    pub fn decode_parameter_value(self) -> ParseString<'a> {
+        if self.0.contains('^') {
+            decode_parameter_value(&self.0).into()
+        } else {
+            self
+        }
+    }
+
+    /// Resolves `\\`, `\,`, `\;`, `\:` and `\n` in a single pass, borrowing if there are none.
     pub fn unescape_text(self) -> ParseString<'a> {
-        if self.0.contains(r#"\\"#)
-            || self.0.contains(r#"\,"#)
-            || self.0.contains(r#"\;"#)
-            || self.0.contains(r#"\:"#)
-            || self.0.contains(r#"\N"#)
-            || self.0.contains(r#"\n"#)
-        {
-            self.0
-                .replace(r#"\\"#, r#"\"#)
-                .replace(r#"\,"#, ",")
-                .replace(r#"\;"#, ";")
-                .replace(r#"\:"#, ":")
-                .replace(r#"\N"#, "\n")
-                .replace(r#"\n"#, "\n")
-                .into()
+        if self.0.contains('\\') {
+            unescape_text(&self.0).into()
         } else {
             self
         }
@@ -98,3 +104,52 @@ impl<'a> From<&'a str> for ParseString<'a> {
         ParseString(Cow::Borrowed(s))
     }
 }
+
+/// Unescapes text as described in
+/// [rfc5545 3.3.11](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.11),
+/// any other backslash is kept as it is.
+fn unescape_text(input: &str) -> String {
+    let mut unescaped = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some(&escaped @ ('\\' | ',' | ';' | ':')) => unescaped.push(escaped),
+            Some('n' | 'N') => unescaped.push('\n'),
+            _ => {
+                unescaped.push('\\');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    unescaped
+}
+
+/// Decodes `^^`, `^n` and `^'` as described in
+/// [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868#section-3.2),
+/// any other `^` is kept as it is.
+fn decode_parameter_value(input: &str) -> String {
+    let mut decoded = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '^' {
+            decoded.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('^') => decoded.push('^'),
+            Some('n' | 'N') => decoded.push('\n'),
+            Some('\'') => decoded.push('"'),
+            _ => {
+                decoded.push('^');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    decoded
+}
@@ -3,7 +3,12 @@ use std::{
     str::FromStr,
 };
 
-use crate::{parser::utils::valid_key_sequence_cow, properties::fold_line, value_types::ValueType};
+use crate::{
+    parser::utils::valid_key_sequence_cow,
+    properties::{write_parameter, LIST_PROPERTIES, MULTI_PROPERTIES},
+    value_types::ValueType,
+    WriteOptions,
+};
 
 use super::{
     parameters::{parameters, Parameter},
@@ -23,25 +28,6 @@ use nom::{
 #[cfg(test)]
 use nom::error::ErrorKind;
 
-/// [RFC-5545](https://datatracker.ietf.org/doc/html/rfc5545) states that the following
-/// "MAY occur more than once" in a VEVENT, VTODO, VJOURNAL, and VFREEBUSY.
-/// Note: A VJOURNAL can also contain multiple DECRIPTION but this is not covered here.
-const MULTIS: [&str; 13] = [
-    "ATTACH",
-    "ATTENDEE",
-    "CATEGORIES",
-    "COMMENT",
-    "CONTACT",
-    "EXDATE",
-    "FREEBUSY",
-    "IANA-PROP",
-    "RDATE",
-    "RELATED",
-    "RESOURCES",
-    "RSTATUS",
-    "X-PROP",
-];
-
 /// Zero-copy version of [`crate::properties::Property`]
 #[derive(PartialEq, Eq, Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -62,25 +48,43 @@ impl<'a> Property<'a> {
 }
 
 impl Property<'_> {
+    pub fn into_owned(self) -> Property<'static> {
+        Property {
+            name: self.name.into_owned(),
+            val: self.val.into_owned(),
+            params: self.params.into_iter().map(Parameter::into_owned).collect(),
+        }
+    }
+
     pub(crate) fn fmt_write<W: Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
         // A nice starting capacity for the majority of content lines
         let mut line = String::with_capacity(150);
 
         write!(line, "{}", self.name.as_str())?;
-        for Parameter { key, val } in &self.params {
-            if let Some(val) = val {
-                write!(line, ";{}={}", key.as_str(), val.as_str())?;
-            } else {
+        for Parameter { key, values } in &self.params {
+            if values.is_empty() {
                 write!(line, ";{}", key.as_str())?;
+            } else {
+                write_parameter(
+                    &mut line,
+                    key.as_str(),
+                    values.iter().map(ParseString::as_str),
+                )?;
+            }
+        }
+        // list values were kept escaped while parsing
+        let is_list = LIST_PROPERTIES.contains(&self.name.as_str());
+        match determin_value_type(&self.name, &self.params) {
+            Some(ValueType::Text) if !is_list => {
+                write!(line, ":{}", crate::Property::escape_text(self.val.as_str()))?
             }
+            _ => write!(line, ":{}", self.val.as_str())?,
         }
-        write!(line, ":{}", self.val.as_str())?;
-        write_crlf!(out, "{}", fold_line(&line))?;
-        Ok(())
+        WriteOptions::default().write_line(out, &line)
     }
 
     pub(crate) fn is_multi_property(&self) -> bool {
-        MULTIS.contains(&self.name.as_str())
+        MULTI_PROPERTIES.contains(&self.name.as_str())
     }
 }
 
@@ -103,15 +107,29 @@ impl<'a> TryFrom<&'a str> for Property<'a> {
 
 impl From<Property<'_>> for crate::Property {
     fn from(parsed: Property<'_>) -> Self {
-        Self {
+        let mut property = Self {
             key: parsed.name.as_ref().to_owned(),
             val: parsed.val.as_ref().to_owned(),
-            params: parsed
-                .params
-                .into_iter()
-                .map(|p| (p.key.as_ref().to_owned(), p.into()))
-                .collect(),
+            params: Default::default(),
+            list: None,
+        };
+        // a repeated parameter like `MEMBER="a";MEMBER="b"` adds to the values of the first
+        for parameter in parsed.params {
+            let parameter = crate::properties::Parameter::from(parameter);
+            match property.params.get_mut(parameter.key()) {
+                Some(existing) => {
+                    for value in parameter.values() {
+                        if !existing.values().contains(value) {
+                            existing.push_value(value.as_str());
+                        }
+                    }
+                }
+                None => {
+                    property.append_parameter(parameter);
+                }
+            }
         }
+        property.read_list()
     }
 }
 
@@ -141,7 +159,7 @@ fn properties() {
         Property {
             name: "KEY1".into(),
             val: "VALUE".into(),
-            params: vec![Parameter::new_ref("foo", Some("bar"))]
+            params: vec![Parameter::new_ref("FOO", Some("bar"))]
         }
     );
 
@@ -151,7 +169,7 @@ fn properties() {
         Property {
             name: "KEY2".into(),
             val: "VALUE space separated".into(),
-            params: vec![Parameter::new_ref("foo", Some("bar"))]
+            params: vec![Parameter::new_ref("FOO", Some("bar"))]
         }
     );
 
@@ -161,7 +179,7 @@ fn properties() {
         Property {
             name: "KEY2".into(),
             val: "important:VALUE".into(),
-            params: vec![Parameter::new_ref("foo", Some("bar"))]
+            params: vec![Parameter::new_ref("FOO", Some("bar"))]
         }
     );
 }
@@ -187,6 +205,35 @@ fn unescape_text() {
     );
 }
 
+#[test]
+fn reserialize_escaped_text() {
+    let line = "DESCRIPTION:a\\, b\\; c\\\\\r\n";
+    assert_eq!(Property::try_from(line).unwrap().to_string(), line);
+    let line = "CATEGORIES:A,B\\, C\r\n";
+    assert_eq!(Property::try_from(line).unwrap().to_string(), line);
+}
+
+#[test]
+fn list_values_stay_escaped() {
+    assert_parser!(
+        property,
+        "CATEGORIES:APPOINTMENT,EDUCATION\\, SCIENCE\n",
+        Property {
+            name: "CATEGORIES".into(),
+            val: "APPOINTMENT,EDUCATION\\, SCIENCE".into(),
+            params: Default::default()
+        }
+    );
+
+    assert_eq!(
+        "CATEGORIES:APPOINTMENT,EDUCATION\\, SCIENCE\n"
+            .parse::<crate::Property>()
+            .unwrap()
+            .values(),
+        vec!["APPOINTMENT", "EDUCATION, SCIENCE"]
+    );
+}
+
 #[test]
 fn property_escape_url_as_url() {
     assert_eq!(
@@ -216,7 +263,7 @@ fn property_escape_description_as_text() {
         Property {
             name: "DESCRIPTION".into(),
             val: "VALUE\n newline separated".into(),
-            params: vec![Parameter::new_ref("foo", Some("bar"))]
+            params: vec![Parameter::new_ref("FOO", Some("bar"))]
         }
     );
 }
@@ -272,9 +319,9 @@ fn parse_properties_from_rfc() {
         property,
         "home.tel;type=fax,voice,msg:+49 3581 123456\n",
         Property {
-            name: "home.tel".into(),
+            name: "HOME.TEL".into(),
             val: "+49 3581 123456".into(),
-            params: vec![Parameter::new_ref("type", Some("fax,voice,msg"),)]
+            params: vec![Parameter::new_ref("TYPE", Some("fax,voice,msg"),)]
         }
     );
 
@@ -282,9 +329,9 @@ fn parse_properties_from_rfc() {
         property,
         "email;internet:mb@goerlitz.de\n",
         Property {
-            name: "email".into(),
+            name: "EMAIL".into(),
             val: "mb@goerlitz.de".into(),
-            params: vec![Parameter::new_ref("internet", None,)]
+            params: vec![Parameter::new_ref("INTERNET", None,)]
         }
     );
 }
@@ -337,7 +384,7 @@ fn parse_property_with_colon() {
         val: "c605e4e8-8ea3-4315-b139-19394ab3ced6".into(),
         params: vec![Parameter {
             key: "RELTYPE".into(),
-            val: None,
+            values: vec![],
         }],
     };
 
@@ -361,7 +408,7 @@ fn determin_value_type(name: &ParseString, params: &[Parameter]) -> Option<Value
     params
         .iter()
         .find(|param| param.key == "VALUE")
-        .and_then(|Parameter { val, .. }| val.as_ref())
+        .and_then(Parameter::value)
         .and_then(|typ| ValueType::from_str(typ.as_str()).ok())
         .or_else(|| ValueType::by_name(name.as_str()))
 }
@@ -380,7 +427,8 @@ pub fn property<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
                             // this must be interpreted as component by `component()`
                             // if you get here at all then the parser is in a wrong state
                             "property cannot be END or BEGIN",
-                            preceded(multispace0, property_key).map(ParseString::from),
+                            preceded(multispace0, property_key)
+                                .map(|key| ParseString::from(key).into_uppercase()),
                         )), // key
                         parameters, // params
                     )),
@@ -398,16 +446,18 @@ pub fn property<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
                 ),
                 context(
                     "no-value property",
-                    valid_key_sequence_cow.map(|key| ((key, vec![]), ParseString::from(""))), // key and nothing else
+                    valid_key_sequence_cow
+                        .map(|key| ((key.into_uppercase(), vec![]), ParseString::from(""))), // key and nothing else
                 ),
             )),
             opt(line_ending),
         ))
         .map(|(((name, params), val), _)| {
-            let val = if let Some(value_type) = determin_value_type(&name, &params) {
-                val.unescape_by_value_type(value_type)
-            } else {
-                val
+            // list values are unescaped element by element, see `crate::Property::values()`
+            let is_list = LIST_PROPERTIES.contains(&name.as_str());
+            let val = match determin_value_type(&name, &params) {
+                Some(value_type) if !is_list => val.unescape_by_value_type(value_type),
+                _ => val,
             };
 
             Property { name, val, params }
@@ -0,0 +1,305 @@
+use std::borrow::Cow;
+
+use nom::{
+    combinator::all_consuming,
+    error::{convert_error, VerboseError},
+    Finish,
+};
+
+use super::{parsed_string::ParseString, properties::property, Component, Property};
+
+/// A logical content line, see [rfc5545 3.1](https://datatracker.ietf.org/doc/html/rfc5545#section-3.1)
+///
+/// The text borrows from the input unless the line was folded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ContentLine<'a> {
+    /// Number of the physical line this content line starts on, counting from 1
+    pub line_number: usize,
+    /// The unfolded content line without its line ending
+    pub text: Cow<'a, str>,
+}
+
+/// Iterates over the [`ContentLine`]s of folded input, skipping blank lines.
+///
+/// ```
+/// # use icalendar::parser::ContentLines;
+/// # use std::borrow::Cow;
+/// let mut lines = ContentLines::new("SUMMARY:short\r\nDESCRIPTION:fol\r\n ded\r\n");
+/// assert!(matches!(lines.next().unwrap().text, Cow::Borrowed("SUMMARY:short")));
+/// let folded = lines.next().unwrap();
+/// assert_eq!(folded.text, "DESCRIPTION:folded");
+/// assert_eq!(folded.line_number, 2);
+/// assert!(lines.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContentLines<'a> {
+    rest: &'a str,
+    line_number: usize,
+}
+
+impl<'a> ContentLines<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::starting_at(input, 0)
+    }
+
+    /// Continues counting after `line_number` lines, for input that was split off a larger document.
+    pub(crate) fn starting_at(input: &'a str, line_number: usize) -> Self {
+        ContentLines {
+            rest: input,
+            line_number,
+        }
+    }
+
+    /// The input that has not been consumed yet.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    /// Number of physical lines consumed so far.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<'a> Iterator for ContentLines<'a> {
+    type Item = ContentLine<'a>;
+
+    fn next(&mut self) -> Option<ContentLine<'a>> {
+        while !self.rest.is_empty() {
+            let (first, mut rest) = split_line(self.rest);
+            self.line_number += 1;
+            let line_number = self.line_number;
+
+            let mut text = Cow::Borrowed(first);
+            while let Some(continuation) = rest.strip_prefix(|c| c == ' ' || c == '\t') {
+                let (next, after) = split_line(continuation);
+                text.to_mut().push_str(next);
+                self.line_number += 1;
+                rest = after;
+            }
+            self.rest = rest;
+
+            if !text.trim().is_empty() {
+                return Some(ContentLine { line_number, text });
+            }
+        }
+        None
+    }
+}
+
+/// Splits off the first physical line, accepting both CRLF and LF.
+fn split_line(input: &str) -> (&str, &str) {
+    match input.split_once('\n') {
+        Some((line, rest)) => (line.strip_suffix('\r').unwrap_or(line), rest),
+        None => (input, ""),
+    }
+}
+
+enum Token<'a> {
+    Begin(ParseString<'a>),
+    End(ParseString<'a>),
+    Property(Property<'a>),
+}
+
+impl Token<'_> {
+    fn into_owned(self) -> Token<'static> {
+        match self {
+            Token::Begin(name) => Token::Begin(name.into_owned()),
+            Token::End(name) => Token::End(name.into_owned()),
+            Token::Property(property) => Token::Property(property.into_owned()),
+        }
+    }
+}
+
+fn strip_prefix_no_case<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .and_then(|_| line.get(prefix.len()..))
+}
+
+/// Recognizes `BEGIN:` and `END:` lines without parsing anything else.
+pub(crate) enum Boundary<'a> {
+    Begin(&'a str),
+    End(&'a str),
+}
+
+impl<'a> Boundary<'a> {
+    pub(crate) fn of(line: &'a str) -> Option<Self> {
+        let trimmed = line.trim();
+        strip_prefix_no_case(trimmed, "BEGIN:")
+            .map(Boundary::Begin)
+            .or_else(|| strip_prefix_no_case(trimmed, "END:").map(Boundary::End))
+    }
+}
+
+fn token(line: &str) -> Result<Token<'_>, String> {
+    match Boundary::of(line) {
+        Some(Boundary::Begin(name)) => {
+            return Ok(Token::Begin(ParseString::from(name).into_uppercase()))
+        }
+        Some(Boundary::End(name)) => {
+            return Ok(Token::End(ParseString::from(name).into_uppercase()))
+        }
+        None => {}
+    }
+    all_consuming(property)(line)
+        .finish()
+        .map(|(_, property)| Token::Property(property))
+        .map_err(|e: VerboseError<&str>| convert_error(line, e))
+}
+
+/// Builds the component tree from folded input, line numbers in errors refer to the input.
+pub(crate) fn components(input: &str) -> Result<Vec<Component<'_>>, String> {
+    build(ContentLines::new(input))
+}
+
+/// Builds the component tree from content lines.
+pub(crate) fn build<'a>(
+    lines: impl IntoIterator<Item = ContentLine<'a>>,
+) -> Result<Vec<Component<'a>>, String> {
+    let mut roots = Vec::new();
+    let mut open: Vec<Component<'a>> = Vec::new();
+
+    for ContentLine { line_number, text } in lines {
+        let token = match text {
+            Cow::Borrowed(line) => token(line),
+            Cow::Owned(line) => token(&line).map(Token::into_owned),
+        }
+        .map_err(|e| format!("error in line {}:\n{}", line_number, e))?;
+
+        match token {
+            Token::Begin(name) => open.push(Component {
+                name,
+                properties: Vec::new(),
+                components: Vec::new(),
+            }),
+            Token::End(name) => {
+                let component = match open.pop() {
+                    Some(component) if component.name == name => component,
+                    Some(component) => {
+                        return Err(format!(
+                            "error in line {}: mismatching end, expected END:{} but found END:{}",
+                            line_number, component.name, name
+                        ))
+                    }
+                    None => {
+                        return Err(format!(
+                            "error in line {}: END:{} without BEGIN",
+                            line_number, name
+                        ))
+                    }
+                };
+                match open.last_mut() {
+                    Some(parent) => parent.components.push(component),
+                    None => roots.push(component),
+                }
+            }
+            Token::Property(property) => match open.last_mut() {
+                Some(component) => component.properties.push(property),
+                None => {
+                    return Err(format!(
+                        "error in line {}: property {} outside of a component",
+                        line_number, property.name
+                    ))
+                }
+            },
+        }
+    }
+
+    match open.last() {
+        Some(component) => Err(format!("error: missing END:{}", component.name)),
+        None => Ok(roots),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parser::{read_components, unfold};
+
+    #[test]
+    fn borrows_unfolded_lines() {
+        let lines = ContentLines::new("BEGIN:VEVENT\n\nSUMMARY:lunch\r\n\r\nEND:VEVENT")
+            .map(|line| (line.line_number, line.text))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                (1, Cow::Borrowed("BEGIN:VEVENT")),
+                (3, Cow::Borrowed("SUMMARY:lunch")),
+                (5, Cow::Borrowed("END:VEVENT")),
+            ]
+        );
+    }
+
+    #[test]
+    fn unfolds_continuations() {
+        let input = "1 hello world\r\n2 hello \r\n   world\n3 hello \r\n\tworld\r\n4 hello world";
+        let lines = ContentLines::new(input)
+            .map(|line| (line.line_number, line.text.into_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                (1, String::from("1 hello world")),
+                (2, String::from("2 hello   world")),
+                (4, String::from("3 hello world")),
+                (6, String::from("4 hello world")),
+            ]
+        );
+        assert_eq!(
+            lines.into_iter().map(|(_, line)| line).collect::<Vec<_>>(),
+            unfold(input).lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn matches_unfolded_parser() {
+        let input = "BEGIN:VCALENDAR\r\n\
+                     BEGIN:VEVENT\r\n\
+                     UID:abc\r\n\
+                     description;altrep=\"cid:part1\":a long\\, escaped and fo\r\n lded text\r\n\
+                     ATTENDEE;MEMBER=\"mailto:a@example.com\",\r\n \"mailto:b@example.com\":mailto:c@example.com\r\n\
+                     END:VEVENT\r\n\
+                     end:vcalendar\r\n";
+        assert_eq!(components(input), read_components(&unfold(input)));
+    }
+
+    #[test]
+    fn folded_values_are_owned() {
+        let parsed =
+            components("BEGIN:VEVENT\nSUMMARY:lunch\nDESCRIPTION:fol\n ded\nEND:VEVENT").unwrap();
+        let event = parsed.first().unwrap();
+        assert_eq!(event.find_prop("SUMMARY").unwrap().val, "lunch");
+        assert_eq!(event.find_prop("DESCRIPTION").unwrap().val, "folded");
+    }
+
+    #[test]
+    fn structural_errors() {
+        assert_eq!(
+            components("BEGIN:VEVENT\nSUMMARY:lunch\nEND:VTODO\n"),
+            Err(String::from(
+                "error in line 3: mismatching end, expected END:VEVENT but found END:VTODO"
+            ))
+        );
+        assert_eq!(
+            components("BEGIN:VEVENT\nSUMMARY:lunch\n"),
+            Err(String::from("error: missing END:VEVENT"))
+        );
+        assert_eq!(
+            components("SUMMARY:lunch\n"),
+            Err(String::from(
+                "error in line 1: property SUMMARY outside of a component"
+            ))
+        );
+        assert!(
+            components("BEGIN:VEVENT\nDESCRIPTION:a\n b\nEND;X=Y:oops\nEND:VEVENT")
+                .unwrap_err()
+                .starts_with("error in line 4:")
+        );
+    }
+}
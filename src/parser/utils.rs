@@ -16,7 +16,12 @@ use super::parsed_string::ParseString;
 pub fn property_key<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, &'a str, E> {
-    if input.get(0..=2) == Some("END") || input.get(0..=4) == Some("BEGIN") {
+    let starts_with = |prefix: &str| {
+        input
+            .get(..prefix.len())
+            .map_or(false, |start| start.eq_ignore_ascii_case(prefix))
+    };
+    if starts_with("END") || starts_with("BEGIN") {
         IResult::Err(Err::Error(nom::error::make_error(
             input,
             nom::error::ErrorKind::Satisfy,
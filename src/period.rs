@@ -1,25 +1,360 @@
-//use repeats::*;
-
-//pub trait Period {
-//    fn days(&self) -> Repeats;
-//    fn weeks(&self) -> Repeats;
-//    fn months(&self) -> Repeats;
-//    fn years(&self) -> Repeats;
-//}
-//
-//
-//impl Period for usize {
-//    fn days(&self) -> Repeats {
-//        Repeats::Days(*self)
-//    }
-//    fn weeks(&self) -> Repeats {
-//        Repeats::Weeks(*self)
-//    }
-//    fn months(&self) -> Repeats {
-//        Repeats::Months(*self)
-//    }
-//    fn years(&self) -> Repeats {
-//        Repeats::Years(*self)
-//    }
-//}
-//
+//! `PERIOD` values as described in [RFC 5545, Section 3.3.9](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.9)
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+
+use crate::{CalendarDateTime, IcalDuration, Parameter, Property, ValueType};
+
+/// The end of a [`Period`], either explicit or relative to its start.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PeriodEnd {
+    /// `period-explicit`: the period ends at the given date-time.
+    DateTime(CalendarDateTime),
+    /// `period-start`: the period lasts for the given duration.
+    Duration(IcalDuration),
+}
+
+/// A precise period of time, used in `RDATE;VALUE=PERIOD` and `FREEBUSY`.
+///
+/// [RFC 5545, Section 3.3.9](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.9)
+///
+/// ```
+/// # use icalendar::*;
+/// # use chrono::*;
+/// let start = Utc.with_ymd_and_hms(1997, 1, 1, 18, 0, 0).unwrap();
+///
+/// let explicit: Period = "19970101T180000Z/19970102T070000Z".parse().unwrap();
+/// assert_eq!(explicit, Period::new(start, Utc.with_ymd_and_hms(1997, 1, 2, 7, 0, 0).unwrap()).unwrap());
+///
+/// let with_duration: Period = "19970101T180000Z/PT5H30M".parse().unwrap();
+/// assert_eq!(with_duration, Period::with_duration(start, IcalDuration::minutes(330)).unwrap());
+///
+/// // periods must not end before they start
+/// assert!("19970101T180000Z/19970101T170000Z".parse::<Period>().is_err());
+/// assert!(Period::with_duration(start, IcalDuration::minutes(-30)).is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Period {
+    start: CalendarDateTime,
+    end: PeriodEnd,
+}
+
+impl Period {
+    /// Creates a period with an explicit start and end.
+    ///
+    /// Returns [`None`] unless the end is after the start and both are in the same time zone.
+    pub fn new(
+        start: impl Into<CalendarDateTime>,
+        end: impl Into<CalendarDateTime>,
+    ) -> Option<Self> {
+        let (start, end) = (start.into(), end.into());
+        let is_after = match (&start, &end) {
+            (CalendarDateTime::Floating(start), CalendarDateTime::Floating(end)) => end > start,
+            (CalendarDateTime::Utc(start), CalendarDateTime::Utc(end)) => end > start,
+            (
+                CalendarDateTime::WithTimezone {
+                    date_time: start,
+                    tzid: start_tzid,
+                },
+                CalendarDateTime::WithTimezone {
+                    date_time: end,
+                    tzid: end_tzid,
+                },
+            ) => start_tzid == end_tzid && end > start,
+            _ => false,
+        };
+        is_after.then(|| Period {
+            start,
+            end: PeriodEnd::DateTime(end),
+        })
+    }
+
+    /// Creates a period with a start and a duration.
+    ///
+    /// Returns [`None`] unless the duration is positive.
+    pub fn with_duration(
+        start: impl Into<CalendarDateTime>,
+        duration: impl Into<IcalDuration>,
+    ) -> Option<Self> {
+        let duration = duration.into();
+        let is_positive = !duration.is_negative() && duration != IcalDuration::default();
+        is_positive.then(|| Period {
+            start: start.into(),
+            end: PeriodEnd::Duration(duration),
+        })
+    }
+
+    /// Returns the start of this period.
+    pub fn start(&self) -> &CalendarDateTime {
+        &self.start
+    }
+
+    /// Returns the end of this period as it was specified.
+    pub fn end(&self) -> &PeriodEnd {
+        &self.end
+    }
+
+    /// Converts this period into a concrete range of UTC date-times.
+    ///
+    /// Returns [`None`] for floating date-times and for date-times with a `TZID`
+    /// unless the `chrono-tz` feature is enabled.
+    pub fn try_into_utc(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let start = self.start.try_into_utc()?;
+        let end = match &self.end {
+            PeriodEnd::DateTime(end) => end.try_into_utc()?,
+            PeriodEnd::Duration(duration) => {
+                duration.checked_add_to(&self.start)?.try_into_utc()?
+            }
+        };
+        Some((start, end))
+    }
+
+    /// This period with a UTC start, converted to an explicit UTC end unless it already starts in UTC.
+    pub(crate) fn to_utc(&self) -> Option<Self> {
+        match self.start {
+            // an explicit end is in the same time zone as the start
+            CalendarDateTime::Utc(_) => Some(self.clone()),
+            _ => {
+                let (start, end) = self.try_into_utc()?;
+                Period::new(start, end)
+            }
+        }
+    }
+
+    /// Parses a period, the `TZID` applies to both the start and an explicit end.
+    ///
+    /// Periods that end before they start are rejected like in [`Period::new()`].
+    pub(crate) fn from_value(value: &str, tzid: Option<&str>) -> Option<Self> {
+        let with_tzid = |date_time: &str| {
+            let date_time = CalendarDateTime::from_str(date_time).ok()?;
+            Some(match (date_time, tzid) {
+                (CalendarDateTime::Floating(date_time), Some(tzid)) => {
+                    CalendarDateTime::WithTimezone {
+                        date_time,
+                        tzid: tzid.to_owned(),
+                    }
+                }
+                (date_time, _) => date_time,
+            })
+        };
+
+        let (start, end) = value.split_once('/')?;
+        let start = with_tzid(start)?;
+        if end.starts_with(['P', '+', '-']) {
+            Self::with_duration(start, end.parse::<IcalDuration>().ok()?)
+        } else {
+            Self::new(start, with_tzid(end)?)
+        }
+    }
+
+    fn tzid(&self) -> Option<&str> {
+        match &self.start {
+            CalendarDateTime::WithTimezone { tzid, .. } => Some(tzid),
+            _ => None,
+        }
+    }
+
+    /// Reads all periods of a list-valued property like `FREEBUSY` or `RDATE;VALUE=PERIOD`.
+    pub(crate) fn from_property(property: &Property) -> Vec<Self> {
+        if property.value_type() != Some(ValueType::Period) {
+            return Vec::new();
+        }
+        let tzid = property.params().get("TZID").map(Parameter::value);
+        property
+            .values()
+            .iter()
+            .filter_map(|value| Self::from_value(value, tzid))
+            .collect()
+    }
+
+    /// Creates list-valued properties from periods, one for each time zone.
+    ///
+    /// A property can only have one `TZID` parameter, periods in other zones go into properties of their own.
+    pub(crate) fn to_properties<'p>(
+        key: &str,
+        periods: impl IntoIterator<Item = &'p Period>,
+    ) -> Vec<Property> {
+        let mut zones: Vec<(Option<&str>, Vec<&Period>)> = Vec::new();
+        for period in periods {
+            match zones.iter_mut().find(|(tzid, _)| *tzid == period.tzid()) {
+                Some((_, periods)) => periods.push(period),
+                None => zones.push((period.tzid(), vec![period])),
+            }
+        }
+        zones
+            .into_iter()
+            .map(|(tzid, periods)| {
+                let mut property = Property::new_list(key, periods.iter().map(ToString::to_string));
+                if let Some(tzid) = tzid {
+                    property.add_parameter("TZID", tzid);
+                }
+                property
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let start = self.start.to_property("");
+        match &self.end {
+            PeriodEnd::DateTime(end) => {
+                write!(f, "{}/{}", start.value(), end.to_property("").value())
+            }
+            PeriodEnd::Duration(duration) => {
+                write!(f, "{}/{}", start.value(), duration)
+            }
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_value(s, None).ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(1997, 1, 1, 18, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn period_roundtrip() {
+        for sample in [
+            "19970101T180000Z/19970102T070000Z",
+            "19970101T180000Z/PT5H30M",
+        ] {
+            let period = Period::from_str(sample).unwrap();
+            assert_eq!(period.to_string(), sample);
+        }
+    }
+
+    #[test]
+    fn period_into_utc() {
+        let end = Utc.with_ymd_and_hms(1997, 1, 1, 23, 30, 0).unwrap();
+        assert_eq!(
+            Period::new(start(), end).unwrap().try_into_utc(),
+            Some((start(), end))
+        );
+        assert_eq!(
+            Period::with_duration(start(), Duration::minutes(330))
+                .unwrap()
+                .try_into_utc(),
+            Some((start(), end))
+        );
+
+        let floating = NaiveDate::from_ymd_opt(1997, 1, 1)
+            .unwrap()
+            .and_hms_opt(18, 0, 0)
+            .unwrap();
+        assert_eq!(
+            Period::with_duration(floating, Duration::hours(1))
+                .unwrap()
+                .try_into_utc(),
+            None
+        );
+    }
+
+    #[test]
+    fn period_with_tzid() {
+        let property = Period::to_properties(
+            "RDATE",
+            &[Period::from_value("19970101T180000/PT1H", Some("Europe/Berlin")).unwrap()],
+        )
+        .pop()
+        .unwrap()
+        .append_parameter(ValueType::Period)
+        .done();
+
+        assert_eq!(
+            property.params().get("TZID").unwrap().value(),
+            "Europe/Berlin"
+        );
+        assert_eq!(
+            Period::from_property(&property).first().unwrap().start(),
+            &CalendarDateTime::WithTimezone {
+                date_time: NaiveDate::from_ymd_opt(1997, 1, 1)
+                    .unwrap()
+                    .and_hms_opt(18, 0, 0)
+                    .unwrap(),
+                tzid: "Europe/Berlin".into()
+            }
+        );
+    }
+
+    #[test]
+    fn periods_in_different_zones() {
+        let berlin = Period::from_value("19970101T180000/PT1H", Some("Europe/Berlin")).unwrap();
+        let london = Period::from_value("19970101T170000/PT1H", Some("Europe/London")).unwrap();
+        let utc = Period::from_str("19970101T190000Z/PT1H").unwrap();
+        let properties = Period::to_properties("RDATE", [&berlin, &utc, &london, &berlin]);
+
+        let written = properties
+            .iter()
+            .map(|property| {
+                let tzid = property.params().get("TZID").map(Parameter::value);
+                (tzid, property.value())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            written,
+            vec![
+                (
+                    Some("Europe/Berlin"),
+                    "19970101T180000/PT1H,19970101T180000/PT1H"
+                ),
+                (None, "19970101T190000Z/PT1H"),
+                (Some("Europe/London"), "19970101T170000/PT1H"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_empty_and_mixed_periods() {
+        let berlin = |hour| CalendarDateTime::WithTimezone {
+            date_time: NaiveDate::from_ymd_opt(1997, 1, 1)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap(),
+            tzid: "Europe/Berlin".into(),
+        };
+        assert!(Period::new(berlin(18), berlin(19)).is_some());
+        assert_eq!(Period::new(berlin(18), berlin(18)), None);
+        assert_eq!(Period::new(berlin(19), berlin(18)), None);
+        assert_eq!(Period::new(berlin(18), start() + Duration::hours(1)), None);
+        assert_eq!(
+            Period::with_duration(start(), IcalDuration::default()),
+            None
+        );
+
+        for invalid in [
+            "19970101T180000Z/19970101T180000Z",
+            "19970101T180000Z/19970101T170000Z",
+            "19970101T180000Z/19970101T190000",
+            "19970101T180000Z/PT0S",
+            "19970101T180000Z/-PT1H",
+        ] {
+            assert_eq!(Period::from_str(invalid), Err(()), "{}", invalid);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn period_with_tzid_into_utc() {
+        let period = Period::from_value("19970101T190000/PT1H", Some("Europe/Berlin")).unwrap();
+        assert_eq!(
+            period.try_into_utc(),
+            Some((start(), start() + Duration::hours(1)))
+        );
+    }
+}
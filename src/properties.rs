@@ -1,25 +1,60 @@
 use std::{
+    borrow::Cow,
     collections::HashMap,
     fmt::{self, Write},
     mem,
     str::FromStr,
 };
 
-use crate::value_types::ValueType;
+use crate::{value_types::ValueType, IcalDuration, WriteOptions};
+
+mod attachment;
+mod rfc7986;
+mod rfc9073;
+pub use attachment::Attachment;
+pub use rfc7986::{Color, Conference, Feature, Image, ImageDisplay};
+pub use rfc9073::{ParticipantType, ResourceType, StructuredData, StyledDescription};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 /// key-value pairs inside of `Property`s
+///
+/// A parameter may hold a list of values, like the
+/// [`MEMBER`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.2.11) of an `ATTENDEE`.
 pub struct Parameter {
     key: String,
-    val: String,
+    values: Vec<String>,
 }
 
 impl Parameter {
     /// Creates a new `Parameter`
     pub fn new(key: &str, val: &str) -> Self {
         Parameter {
-            key: key.to_owned(),
-            val: val.to_owned(),
+            key: key.to_ascii_uppercase(),
+            values: vec![val.to_owned()],
+        }
+    }
+
+    /// Creates a new `Parameter` with a list of values.
+    ///
+    /// ```
+    /// # use icalendar::{Parameter, Property};
+    /// let attendee = Property::new("ATTENDEE", "mailto:js@x.org")
+    ///     .append_parameter(Parameter::new_list("DELEGATED-TO", ["mailto:a@x.org", "mailto:b@x.org"]))
+    ///     .done();
+    /// let line: String = attendee.try_into().unwrap();
+    /// assert_eq!(
+    ///     line,
+    ///     "ATTENDEE;DELEGATED-TO=\"mailto:a@x.org\",\"mailto:b@x.org\":mailto:js@x.org\r\n"
+    /// );
+    /// ```
+    pub fn new_list<I, S>(key: &str, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Parameter {
+            key: key.to_ascii_uppercase(),
+            values: values.into_iter().map(Into::into).collect(),
         }
     }
 
@@ -29,8 +64,32 @@ impl Parameter {
     }
 
     /// Returns a reference to the value field.
+    ///
+    /// For parameters with a list of values this is the first one, see [`Parameter::values()`].
     pub fn value(&self) -> &str {
-        &self.val
+        self.values.first().map(String::as_str).unwrap_or_default()
+    }
+
+    /// Returns all values of the parameter.
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    /// Returns `true` if the parameter holds more than one value.
+    pub fn is_list(&self) -> bool {
+        self.values.len() > 1
+    }
+
+    /// Appends another value to the list of values.
+    pub fn push_value(&mut self, val: impl Into<String>) -> &mut Self {
+        self.values.push(val.into());
+        self
+    }
+
+    /// Writes `KEY=value`, quoted and
+    /// [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868) encoded where necessary.
+    pub(crate) fn fmt_write<W: Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
+        write_parameter(out, &self.key, self.values.iter().map(String::as_str))
     }
 }
 
@@ -40,6 +99,111 @@ impl From<(&str, &str)> for Parameter {
     }
 }
 
+/// Returns a name of a property, parameter or component in upper case, borrowing it if it already is.
+///
+/// [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545#section-2) treats these names
+/// case-insensitively, they are stored and written in upper case.
+pub(crate) fn normalize_name(name: &str) -> Cow<'_, str> {
+    if name.bytes().any(|b| b.is_ascii_lowercase()) {
+        Cow::Owned(name.to_ascii_uppercase())
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// Parameters whose values the grammar of
+/// [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545#section-3.2) always puts in quotes.
+const QUOTED_PARAMETERS: [&str; 6] = [
+    "ALTREP",
+    "DELEGATED-FROM",
+    "DELEGATED-TO",
+    "DIR",
+    "MEMBER",
+    "SENT-BY",
+];
+
+/// Encodes `^`, newlines and `"` as described in
+/// [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868#section-3.1).
+pub(crate) fn encode_parameter_value(input: &str) -> Cow<'_, str> {
+    if !input.contains(['^', '\n', '"']) {
+        return Cow::Borrowed(input);
+    }
+    let mut encoded = String::with_capacity(input.len() + 2);
+    for c in input.chars() {
+        match c {
+            '^' => encoded.push_str("^^"),
+            '\n' => encoded.push_str("^n"),
+            '"' => encoded.push_str("^'"),
+            c => encoded.push(c),
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Writes `;KEY=value,value` with every value encoded and quoted if it contains `:`, `;` or `,`.
+pub(crate) fn write_parameter<'a, W: Write>(
+    out: &mut W,
+    key: &str,
+    values: impl Iterator<Item = &'a str>,
+) -> Result<(), fmt::Error> {
+    let always_quote = QUOTED_PARAMETERS.contains(&key);
+    write!(out, ";{}=", key)?;
+    for (i, value) in values.enumerate() {
+        if i > 0 {
+            out.write_char(',')?;
+        }
+        let encoded = encode_parameter_value(value);
+        if always_quote || encoded.contains([':', ';', ',']) {
+            write!(out, "\"{}\"", encoded)?;
+        } else {
+            out.write_str(&encoded)?;
+        }
+    }
+    Ok(())
+}
+
+/// Properties whose value is a comma separated list of values.
+///
+/// Commas in these values separate list elements, only commas inside of a single element are escaped.
+pub(crate) const LIST_PROPERTIES: [&str; 6] = [
+    "CATEGORIES",
+    "EXDATE",
+    "FREEBUSY",
+    "LOCATION-TYPE",
+    "RDATE",
+    "RESOURCES",
+];
+
+/// [RFC-5545](https://datatracker.ietf.org/doc/html/rfc5545) states that the following
+/// "MAY occur more than once" in a VEVENT, VTODO, VJOURNAL, and VFREEBUSY.
+/// Note: A VJOURNAL can also contain multiple DECRIPTION but this is not covered here.
+/// `CONFERENCE` and `IMAGE` are from [RFC-7986](https://datatracker.ietf.org/doc/html/rfc7986),
+/// `STRUCTURED-DATA` and `STYLED-DESCRIPTION` from [RFC-9073](https://datatracker.ietf.org/doc/html/rfc9073).
+pub(crate) const MULTI_PROPERTIES: [&str; 17] = [
+    "ATTACH",
+    "ATTENDEE",
+    "CATEGORIES",
+    "COMMENT",
+    "CONFERENCE",
+    "CONTACT",
+    "EXDATE",
+    "FREEBUSY",
+    "IANA-PROP",
+    "IMAGE",
+    "RDATE",
+    "RELATED",
+    "RESOURCES",
+    "RSTATUS",
+    "STRUCTURED-DATA",
+    "STYLED-DESCRIPTION",
+    "X-PROP",
+];
+
+/// Properties that change whenever a component is written,
+/// ignored by [`DiffOptions::ignore_volatile()`](crate::DiffOptions::ignore_volatile)
+/// and [`Component::content_hash()`](crate::Component::content_hash).
+pub(crate) const VOLATILE_PROPERTIES: [&str; 2] = ["DTSTAMP", "LAST-MODIFIED"];
+
 //type EntryParameters = Vec<Parameter>;
 pub type EntryParameters = HashMap<String, Parameter>;
 
@@ -49,6 +213,8 @@ pub struct Property {
     pub(crate) key: String,
     pub(crate) val: String,
     pub(crate) params: EntryParameters,
+    /// The unescaped elements of TEXT list properties like `CATEGORIES`, `val` holds them joined by commas
+    pub(crate) list: Option<Vec<String>>,
 }
 
 impl From<(&str, &str)> for Property {
@@ -59,34 +225,101 @@ impl From<(&str, &str)> for Property {
 
 impl Property {
     /// Guess what this does :D
+    ///
+    /// The key is converted to upper case, because property names are case-insensitive.
+    /// The value is taken as is and escaped when written, except that commas in list-valued
+    /// properties like `CATEGORIES` separate the values, use [`Property::new_list`] for values containing commas.
     pub fn new(key: impl Into<String>, val: impl Into<String>) -> Self {
+        let mut key = key.into();
+        key.make_ascii_uppercase();
         Property {
-            key: key.into(),
+            key,
             val: val.into(),
             params: HashMap::new(),
+            list: None,
         }
     }
 
     #[deprecated]
     /// if you already have `String`s I'll gladly take
-    pub fn new_pre_alloc(key: String, val: String) -> Self {
+    pub fn new_pre_alloc(mut key: String, val: String) -> Self {
+        key.make_ascii_uppercase();
         Property {
             key,
             val,
             params: HashMap::new(),
+            list: None,
         }
     }
 
+    /// Creates a new list-valued `Property` such as `CATEGORIES` or `EXDATE`.
+    ///
+    /// Each value is escaped individually and the values are joined by commas.
+    /// ```
+    /// # use icalendar::Property;
+    /// let categories = Property::new_list("CATEGORIES", ["APPOINTMENT", "EDUCATION, SCIENCE"]);
+    /// assert_eq!(categories.values(), vec!["APPOINTMENT", "EDUCATION, SCIENCE"]);
+    /// ```
+    pub fn new_list<I, S>(key: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut property = Property::new(key, "");
+        let values = values
+            .into_iter()
+            .map(|value| value.as_ref().to_owned())
+            .collect::<Vec<_>>();
+        property.val = values.join(",");
+        if property.is_list() && ValueType::by_name(&property.key) == Some(ValueType::Text) {
+            property.list = Some(values);
+        }
+        property
+    }
+
+    #[cfg(feature = "parser")]
+    /// Splits the value of TEXT list properties into [`Property::list`], it is taken as escaped as in parsed input.
+    pub(crate) fn read_list(mut self) -> Self {
+        if self.is_list() && ValueType::by_name(&self.key) == Some(ValueType::Text) {
+            let elements = split_list(&self.val)
+                .into_iter()
+                .map(unescape_text)
+                .collect::<Vec<_>>();
+            self.val = elements.join(",");
+            self.list = Some(elements);
+        }
+        self
+    }
+
     /// Returns a reference to the key field.
     pub fn key(&self) -> &str {
         &self.key
     }
 
     /// Returns a reference to the value field.
+    ///
+    /// For list-valued properties like `CATEGORIES` these are the unescaped elements joined by commas,
+    /// use [`Property::values()`] to tell them apart.
     pub fn value(&self) -> &str {
         &self.val
     }
 
+    /// Returns `true` if the value of this property is a comma separated list.
+    pub fn is_list(&self) -> bool {
+        LIST_PROPERTIES.contains(&self.key.as_str())
+    }
+
+    /// Returns the individual values of a list-valued property.
+    ///
+    /// Properties that are not list-valued return their value as the only element.
+    pub fn values(&self) -> Vec<String> {
+        match &self.list {
+            Some(list) => list.clone(),
+            None if self.is_list() => self.val.split(',').map(ToOwned::to_owned).collect(),
+            None => vec![self.val.clone()],
+        }
+    }
+
     /// Returns a reference to the parameters.
     pub fn params(&self) -> &EntryParameters {
         &self.params
@@ -101,7 +334,7 @@ impl Property {
     pub fn value_type(&self) -> Option<ValueType> {
         self.params
             .get("VALUE")
-            .and_then(|p| ValueType::from_str(&p.val).ok())
+            .and_then(|p| ValueType::from_str(p.value()).ok())
             .or_else(|| ValueType::by_name(self.key()))
     }
 
@@ -128,10 +361,14 @@ impl Property {
     where
         F: Fn(&str) -> Option<T>,
     {
-        self.params.get(key).and_then(|param| converter(&param.val))
+        self.params
+            .get(&*normalize_name(key))
+            .and_then(|param| converter(param.value()))
     }
 
-    /// Appends a new parameter.
+    /// Appends a new parameter, replacing one with the same key.
+    ///
+    /// Use [`Parameter::new_list`] for parameters with several values.
     pub fn append_parameter<I: Into<Parameter>>(&mut self, into_parameter: I) -> &mut Self {
         let parameter = into_parameter.into();
         self.params.insert(parameter.key.clone(), parameter);
@@ -150,11 +387,12 @@ impl Property {
             key: mem::take(&mut self.key),
             val: mem::take(&mut self.val),
             params: mem::take(&mut self.params),
+            list: self.list.take(),
         }
     }
 
     /// <https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.11>
-    fn escape_text(input: &str) -> String {
+    pub(crate) fn escape_text(input: &str) -> String {
         input
             .replace('\\', r#"\\"#)
             .replace(',', r#"\,"#)
@@ -162,34 +400,44 @@ impl Property {
             .replace('\n', r#"\N"#)
     }
 
-    fn quote_if_contains_colon(input: &str) -> String {
-        if input.contains([':', ';']) {
-            let mut quoted = String::with_capacity(input.len() + 2);
-            quoted.push('"');
-            quoted.push_str(input);
-            quoted.push('"');
-            quoted
-        } else {
-            input.to_string()
-        }
-    }
-
     /// Writes this Property to `out`
     pub(crate) fn fmt_write<W: Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
+        self.fmt_write_with(out, &WriteOptions::default())
+    }
+
+    /// Writes this Property to `out` as configured by `options`
+    pub(crate) fn fmt_write_with<W: Write>(
+        &self,
+        out: &mut W,
+        options: &WriteOptions,
+    ) -> Result<(), fmt::Error> {
         // A nice starting capacity for the majority of content lines
         let mut line = String::with_capacity(150);
 
         write!(line, "{}", self.key)?;
-        for Parameter { key, val } in self.params.values() {
-            write!(line, ";{}={}", key, Self::quote_if_contains_colon(val))?;
+        let mut params = self.params.values().collect::<Vec<_>>();
+        if options.sorts_parameters() {
+            params.sort_by(|a, b| a.key().cmp(b.key()));
+        }
+        for parameter in params {
+            parameter.fmt_write(&mut line)?;
         }
         let value_type = self.value_type();
         match value_type {
+            Some(ValueType::Text) if self.is_list() => match &self.list {
+                Some(list) => {
+                    let escaped = list.iter().map(|element| Self::escape_text(element));
+                    write!(line, ":{}", escaped.collect::<Vec<_>>().join(","))?
+                }
+                None => {
+                    let escaped = self.val.split(',').map(Self::escape_text);
+                    write!(line, ":{}", escaped.collect::<Vec<_>>().join(","))?
+                }
+            },
             Some(ValueType::Text) => write!(line, ":{}", Self::escape_text(&self.val))?,
             _ => write!(line, ":{}", self.val)?,
         }
-        write_crlf!(out, "{}", fold_line(&line))?;
-        Ok(())
+        options.write_line(out, &line)
     }
 }
 
@@ -236,6 +484,7 @@ impl From<Class> for Property {
                 Class::Confidential => "CONFIDENTIAL",
             }),
             params: HashMap::new(),
+            list: None,
         }
     }
 }
@@ -308,6 +557,7 @@ impl From<EventStatus> for Property {
                 EventStatus::Cancelled => "CANCELLED",
             }),
             params: HashMap::new(),
+            list: None,
         }
     }
 }
@@ -317,7 +567,7 @@ impl From<ValueType> for Parameter {
     fn from(val: ValueType) -> Self {
         Parameter {
             key: String::from("VALUE"),
-            val: String::from(match val {
+            values: vec![String::from(match val {
                 ValueType::Binary => "BINARY",
                 ValueType::Boolean => "BOOLEAN",
                 ValueType::CalAddress => "CAL-ADDRESS",
@@ -332,7 +582,7 @@ impl From<ValueType> for Parameter {
                 ValueType::Time => "TIME",
                 ValueType::Uri => "URI",
                 ValueType::UtcOffset => "UTC-OFFSET",
-            }),
+            })],
         }
     }
 }
@@ -352,9 +602,15 @@ impl From<TodoStatus> for Property {
     }
 }
 
+impl From<IcalDuration> for Property {
+    fn from(duration: IcalDuration) -> Self {
+        Property::new("DURATION", duration.to_string())
+    }
+}
+
 impl From<chrono::Duration> for Property {
     fn from(duration: chrono::Duration) -> Self {
-        Property::new("DURATION", duration.to_string())
+        IcalDuration::from(duration).into()
     }
 }
 //pub enum AttendeeRole {
@@ -383,18 +639,58 @@ impl From<chrono::Duration> for Property {
 //impl Into<Property> for Attendee {
 //}
 
-// Fold a content line as described in RFC 5545, Section 3.1
+/// Splits a list value on every comma that is not escaped by a backslash.
+#[cfg(feature = "parser")]
+pub(crate) fn split_list(input: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                values.push(input.get(start..i).unwrap_or_default());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(input.get(start..).unwrap_or_default());
+    values
+}
+
+#[cfg(feature = "parser")]
+/// <https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.11>
+pub(crate) fn unescape_text(input: &str) -> String {
+    let mut unescaped = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('N' | 'n') => unescaped.push('\n'),
+            Some(next) => unescaped.push(next),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Folds a content line into lines of at most `limit` octets, separated by `line_ending`,
+/// as described in [RFC 5545, Section 3.1](https://datatracker.ietf.org/doc/html/rfc5545#section-3.1)
 #[allow(clippy::indexing_slicing)]
-pub(crate) fn fold_line(line: &str) -> String {
-    const LIMIT: usize = 75;
+pub(crate) fn fold_line_with(line: &str, limit: usize, line_ending: &str) -> String {
     let len = line.len();
-    let mut ret = String::with_capacity(len + (len / LIMIT * 3));
+    let mut ret = String::with_capacity(len + (len / limit * 3));
     let mut bytes_remaining = len;
 
     let mut pos = 0;
-    let mut next_pos = LIMIT;
+    let mut next_pos = limit;
 
-    while bytes_remaining > LIMIT {
+    while bytes_remaining > limit {
         let pos_is_whitespace = |line: &str, next_pos| {
             line.chars()
                 .nth(next_pos)
@@ -412,11 +708,12 @@ pub(crate) fn fold_line(line: &str) -> String {
             }
         }
         ret.push_str(&line[pos..next_pos]);
-        ret.push_str("\r\n ");
+        ret.push_str(line_ending);
+        ret.push(' ');
 
         bytes_remaining -= next_pos - pos;
         pos = next_pos;
-        next_pos += LIMIT - 1;
+        next_pos += limit - 1;
     }
 
     ret.push_str(&line[len - bytes_remaining..]);
@@ -429,6 +726,10 @@ mod tests {
 
     use super::*;
 
+    fn fold_line(line: &str) -> String {
+        fold_line_with(line, WriteOptions::RFC_FOLD_WIDTH, "\r\n")
+    }
+
     #[test]
     fn fold_line_short() {
         let line = "This is a short line";
@@ -484,6 +785,138 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "parser")]
+    #[test]
+    fn split_list_on_unescaped_commas() {
+        assert_eq!(split_list("a,b"), vec!["a", "b"]);
+        assert_eq!(split_list(r"a\,b,c"), vec![r"a\,b", "c"]);
+        assert_eq!(split_list(r"a\\,b"), vec![r"a\\", "b"]);
+        assert_eq!(split_list(""), vec![""]);
+    }
+
+    #[test]
+    fn list_property_values() {
+        let prop = Property::new_list("CATEGORIES", ["APPOINTMENT", "EDUCATION, SCIENCE", "a;b"]);
+        assert_eq!(prop.value(), "APPOINTMENT,EDUCATION, SCIENCE,a;b");
+        assert_eq!(
+            prop.values(),
+            vec!["APPOINTMENT", "EDUCATION, SCIENCE", "a;b"]
+        );
+
+        let not_a_list = Property::new("SUMMARY", "a, b");
+        assert_eq!(not_a_list.values(), vec!["a, b"]);
+
+        let raw = Property::new("CATEGORIES", r"C:\x,a");
+        assert_eq!(raw.value(), r"C:\x,a");
+        assert_eq!(raw.values(), vec![r"C:\x", "a"]);
+    }
+
+    #[test]
+    fn serialize_list_property() {
+        let prop = Property::new_list("CATEGORIES", ["APPOINTMENT", "EDUCATION, SCIENCE"]);
+        let mut buf = String::new();
+        prop.fmt_write(&mut buf).unwrap();
+        assert_eq!(buf, "CATEGORIES:APPOINTMENT,EDUCATION\\, SCIENCE\r\n");
+
+        let prop = Property::new("CATEGORIES", "a,b;c");
+        let mut buf = String::new();
+        prop.fmt_write(&mut buf).unwrap();
+        assert_eq!(buf, "CATEGORIES:a,b\\;c\r\n");
+
+        let prop = Property::new("CATEGORIES", r"C:\x, a, b");
+        let mut buf = String::new();
+        prop.fmt_write(&mut buf).unwrap();
+        assert_eq!(buf, "CATEGORIES:C:\\\\x, a, b\r\n");
+    }
+
+    #[test]
+    fn quote_parameter_values() {
+        let line = |parameter: Parameter| -> String {
+            Property::new("ATTENDEE", "mailto:a@example.com")
+                .append_parameter(parameter)
+                .done()
+                .try_into()
+                .unwrap()
+        };
+        assert_eq!(
+            line(Parameter::new("CN", "Doe, John")),
+            "ATTENDEE;CN=\"Doe, John\":mailto:a@example.com\r\n"
+        );
+        assert_eq!(
+            line(Parameter::new("CN", "John Doe")),
+            "ATTENDEE;CN=John Doe:mailto:a@example.com\r\n"
+        );
+        assert_eq!(
+            line(Parameter::new("SENT-BY", "mailto:b@example.com")),
+            "ATTENDEE;SENT-BY=\"mailto:b@example.com\":mailto:a@example.com\r\n"
+        );
+        assert_eq!(
+            line(Parameter::new_list(
+                "MEMBER",
+                ["mailto:x@ex.com", "mailto:y@ex.com"]
+            )),
+            "ATTENDEE;MEMBER=\"mailto:x@ex.com\",\"mailto:y@ex.com\":mailto:a@example.com\r\n"
+        );
+    }
+
+    #[test]
+    fn caret_encode_parameter_values() {
+        let property = Property::new("ATTENDEE", "mailto:a@example.com")
+            .add_parameter("CN", "George Herman \"Babe\" Ruth^\nYankees")
+            .done();
+        let line: String = property.try_into().unwrap();
+        assert_eq!(
+            line,
+            "ATTENDEE;CN=George Herman ^'Babe^' Ruth^^^nYankees:mailto:a@example.com\r\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn reparse_parameters() {
+        let property = Property::new("ATTENDEE", "mailto:a@ex.com")
+            .add_parameter("CN", "D, \"J\"\n")
+            .append_parameter(Parameter::new_list(
+                "DELEGATED-TO",
+                ["x@ex.com", "y@ex.com"],
+            ))
+            .done();
+        let line: String = property.clone().try_into().unwrap();
+        let reparsed: Property = line.parse().unwrap();
+        assert_eq!(reparsed, property);
+        assert_eq!(
+            reparsed.params().get("DELEGATED-TO").map(Parameter::values),
+            Some(&["x@ex.com".to_owned(), "y@ex.com".to_owned()][..])
+        );
+    }
+
+    #[test]
+    fn parameters_are_replaced() {
+        let property = Property::new("ATTENDEE", "mailto:js@ex.com")
+            .add_parameter("PARTSTAT", "NEEDS-ACTION")
+            .add_parameter("PARTSTAT", "ACCEPTED")
+            .done();
+        let line: String = property.try_into().unwrap();
+        assert_eq!(line, "ATTENDEE;PARTSTAT=ACCEPTED:mailto:js@ex.com\r\n");
+    }
+
+    #[test]
+    fn repeated_parameters_are_merged() {
+        let parsed: Property =
+            "ATTENDEE;MEMBER=\"mailto:a@ex.com\";MEMBER=\"mailto:b@ex.com\":mailto:js@ex.com"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            parsed.params().get("MEMBER").map(Parameter::values),
+            Some(&["mailto:a@ex.com".to_owned(), "mailto:b@ex.com".to_owned()][..])
+        );
+        let line: String = parsed.try_into().unwrap();
+        assert_eq!(
+            line,
+            "ATTENDEE;MEMBER=\"mailto:a@ex.com\",\"mailto:b@ex.com\":mailto:js@ex.com\r\n"
+        );
+    }
+
     #[test]
     fn serialize_property() {
         let prop = Property::new("SUMMARY", "This is a summary");
@@ -0,0 +1,142 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use crate::{Parameter, Property, ValueType};
+
+/// [`ATTACH`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.1) property,
+/// either a reference to a document or the inline document itself.
+///
+/// ```
+/// # use icalendar::*;
+/// let sound = Attachment::Binary {
+///     fmttype: Some("audio/basic".into()),
+///     bytes: vec![0x2e, 0x73, 0x6e, 0x64],
+/// };
+/// let property = Property::from(sound.clone());
+/// assert_eq!(property.value(), "LnNuZA==");
+/// assert_eq!(Attachment::try_from(&property), Ok(sound));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Attachment {
+    /// A URI pointing to the attached document
+    Uri(String),
+    /// An inline document, written as `ENCODING=BASE64;VALUE=BINARY`
+    Binary {
+        /// Media type of the document, written as `FMTTYPE`
+        fmttype: Option<String>,
+        /// The raw content of the document
+        bytes: Vec<u8>,
+    },
+}
+
+impl Attachment {
+    /// Returns the URI if this is a referenced attachment.
+    pub fn as_uri(&self) -> Option<&str> {
+        match self {
+            Attachment::Uri(uri) => Some(uri),
+            Attachment::Binary { .. } => None,
+        }
+    }
+
+    /// Returns the decoded content if this is an inline attachment.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Attachment::Uri(_) => None,
+            Attachment::Binary { bytes, .. } => Some(bytes),
+        }
+    }
+}
+
+impl From<Attachment> for Property {
+    fn from(attachment: Attachment) -> Self {
+        match attachment {
+            Attachment::Uri(uri) => Property::new("ATTACH", uri),
+            Attachment::Binary { fmttype, bytes } => {
+                let mut property = Property::new("ATTACH", STANDARD.encode(bytes));
+                property
+                    .add_parameter("ENCODING", "BASE64")
+                    .append_parameter(ValueType::Binary);
+                if let Some(fmttype) = fmttype {
+                    property.add_parameter("FMTTYPE", &fmttype);
+                }
+                property
+            }
+        }
+    }
+}
+
+impl TryFrom<&Property> for Attachment {
+    type Error = ();
+
+    fn try_from(property: &Property) -> Result<Self, Self::Error> {
+        if property.key() != "ATTACH" {
+            return Err(());
+        }
+        let is_base64 = property
+            .params()
+            .get("ENCODING")
+            .map(Parameter::value)
+            .map_or(false, |encoding| encoding.eq_ignore_ascii_case("BASE64"));
+
+        if is_base64 || property.value_type() == Some(ValueType::Binary) {
+            // folding may leave whitespace behind in sloppily written files
+            let encoded = property
+                .value()
+                .chars()
+                .filter(|c| !c.is_ascii_whitespace())
+                .collect::<String>();
+            Ok(Attachment::Binary {
+                fmttype: property
+                    .params()
+                    .get("FMTTYPE")
+                    .map(|fmttype| fmttype.value().to_owned()),
+                bytes: STANDARD.decode(encoded).map_err(|_| ())?,
+            })
+        } else {
+            Ok(Attachment::Uri(property.value().to_owned()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn binary_attachment_is_folded() {
+        let attachment = Attachment::Binary {
+            fmttype: Some("image/png".into()),
+            bytes: (0..=255).collect(),
+        };
+        let mut out = String::new();
+        Property::from(attachment).fmt_write(&mut out).unwrap();
+
+        assert!(out.lines().all(|line| line.len() <= 75));
+        assert!(out.starts_with("ATTACH;"));
+        assert!(out.contains("ENCODING=BASE64"));
+        assert!(out.contains("VALUE=BINARY"));
+        assert!(out.contains("FMTTYPE=image/png"));
+    }
+
+    #[test]
+    fn uri_attachment() {
+        let property = Property::new("ATTACH", "ftp://example.com/pub/sounds/bell-01.aud")
+            .add_parameter("FMTTYPE", "audio/basic")
+            .done();
+        assert_eq!(
+            Attachment::try_from(&property),
+            Ok(Attachment::Uri(
+                "ftp://example.com/pub/sounds/bell-01.aud".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_base64() {
+        let property = Property::new("ATTACH", "not base64!")
+            .add_parameter("ENCODING", "BASE64")
+            .done();
+        assert_eq!(Attachment::try_from(&property), Err(()));
+    }
+}
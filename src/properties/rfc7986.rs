@@ -0,0 +1,435 @@
+//! Typed properties introduced by [RFC 7986](https://datatracker.ietf.org/doc/html/rfc7986)
+
+use std::{fmt, str::FromStr};
+
+use crate::{Parameter, Property};
+
+/// The 147 color names of [CSS3](https://www.w3.org/TR/css-color-3/#svg-color)
+#[rustfmt::skip]
+const CSS3_COLOR_NAMES: [&str; 147] = [
+    "aliceblue", "antiquewhite", "aqua", "aquamarine", "azure", "beige", "bisque", "black",
+    "blanchedalmond", "blue", "blueviolet", "brown", "burlywood", "cadetblue", "chartreuse",
+    "chocolate", "coral", "cornflowerblue", "cornsilk", "crimson", "cyan", "darkblue", "darkcyan",
+    "darkgoldenrod", "darkgray", "darkgreen", "darkgrey", "darkkhaki", "darkmagenta",
+    "darkolivegreen", "darkorange", "darkorchid", "darkred", "darksalmon", "darkseagreen",
+    "darkslateblue", "darkslategray", "darkslategrey", "darkturquoise", "darkviolet", "deeppink",
+    "deepskyblue", "dimgray", "dimgrey", "dodgerblue", "firebrick", "floralwhite", "forestgreen",
+    "fuchsia", "gainsboro", "ghostwhite", "gold", "goldenrod", "gray", "green", "greenyellow",
+    "grey", "honeydew", "hotpink", "indianred", "indigo", "ivory", "khaki", "lavender",
+    "lavenderblush", "lawngreen", "lemonchiffon", "lightblue", "lightcoral", "lightcyan",
+    "lightgoldenrodyellow", "lightgray", "lightgreen", "lightgrey", "lightpink", "lightsalmon",
+    "lightseagreen", "lightskyblue", "lightslategray", "lightslategrey", "lightsteelblue",
+    "lightyellow", "lime", "limegreen", "linen", "magenta", "maroon", "mediumaquamarine",
+    "mediumblue", "mediumorchid", "mediumpurple", "mediumseagreen", "mediumslateblue",
+    "mediumspringgreen", "mediumturquoise", "mediumvioletred", "midnightblue", "mintcream",
+    "mistyrose", "moccasin", "navajowhite", "navy", "oldlace", "olive", "olivedrab", "orange",
+    "orangered", "orchid", "palegoldenrod", "palegreen", "paleturquoise", "palevioletred",
+    "papayawhip", "peachpuff", "peru", "pink", "plum", "powderblue", "purple", "red", "rosybrown",
+    "royalblue", "saddlebrown", "salmon", "sandybrown", "seagreen", "seashell", "sienna", "silver",
+    "skyblue", "slateblue", "slategray", "slategrey", "snow", "springgreen", "steelblue", "tan",
+    "teal", "thistle", "tomato", "turquoise", "violet", "wheat", "white", "whitesmoke", "yellow",
+    "yellowgreen",
+];
+
+/// Splits a multi-valued parameter like `DISPLAY=BADGE,THUMBNAIL`.
+fn param_values<'p>(property: &'p Property, key: &str) -> impl Iterator<Item = &'p str> {
+    property
+        .params()
+        .get(key)
+        .map(Parameter::values)
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn list_param<T: ToString>(key: &str, values: &[T]) -> Parameter {
+    Parameter::new_list(key, values.iter().map(ToString::to_string))
+}
+
+/// [`COLOR`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.9) property,
+/// a CSS3 color name.
+///
+/// ```
+/// # use icalendar::*;
+/// let color: Color = "Turquoise".parse().unwrap();
+/// assert_eq!(color.as_str(), "turquoise");
+/// assert!("not-a-color".parse::<Color>().is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Color(&'static str);
+
+impl Color {
+    /// Returns the lowercase CSS3 name of this color.
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl FromStr for Color {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        CSS3_COLOR_NAMES
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(s))
+            .map(|name| Color(name))
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl From<Color> for Property {
+    fn from(color: Color) -> Self {
+        Property::new("COLOR", color.as_str())
+    }
+}
+
+/// [`DISPLAY`](https://datatracker.ietf.org/doc/html/rfc7986#section-6.1) parameter of an [`Image`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImageDisplay {
+    /// Image may be shown next to the title
+    Badge,
+    /// Image is a full representation of the object
+    Graphic,
+    /// Image is shown in full size when the object is opened
+    Fullsize,
+    /// Image is shown in a listing of objects
+    Thumbnail,
+    /// Experimental or IANA registered value
+    Other(String),
+}
+
+impl FromStr for ImageDisplay {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_uppercase().as_str() {
+            "BADGE" => ImageDisplay::Badge,
+            "GRAPHIC" => ImageDisplay::Graphic,
+            "FULLSIZE" => ImageDisplay::Fullsize,
+            "THUMBNAIL" => ImageDisplay::Thumbnail,
+            "" => return Err(()),
+            _ => ImageDisplay::Other(s.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for ImageDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ImageDisplay::Badge => "BADGE",
+            ImageDisplay::Graphic => "GRAPHIC",
+            ImageDisplay::Fullsize => "FULLSIZE",
+            ImageDisplay::Thumbnail => "THUMBNAIL",
+            ImageDisplay::Other(other) => other,
+        })
+    }
+}
+
+/// [`IMAGE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.10) property,
+/// an image referenced by URI.
+///
+/// Inline images with `VALUE=BINARY` are not supported and can not be read as an `Image`.
+///
+/// ```
+/// # use icalendar::*;
+/// let image = Image::new("https://example.com/logo.png")
+///     .fmttype("image/png")
+///     .display(ImageDisplay::Badge);
+///
+/// let mut calendar = Calendar::new();
+/// calendar.image(image.clone());
+/// assert_eq!(calendar.get_images(), vec![image]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    uri: String,
+    fmttype: Option<String>,
+    display: Vec<ImageDisplay>,
+}
+
+impl Image {
+    /// Creates an image from its URI.
+    pub fn new(uri: impl Into<String>) -> Self {
+        Image {
+            uri: uri.into(),
+            fmttype: None,
+            display: Vec::new(),
+        }
+    }
+
+    /// Sets the media type, written as `FMTTYPE`.
+    pub fn fmttype(mut self, fmttype: impl Into<String>) -> Self {
+        self.fmttype = Some(fmttype.into());
+        self
+    }
+
+    /// Adds a way this image is meant to be displayed.
+    pub fn display(mut self, display: ImageDisplay) -> Self {
+        self.display.push(display);
+        self
+    }
+
+    /// Returns the URI of this image.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Returns the media type of this image.
+    pub fn get_fmttype(&self) -> Option<&str> {
+        self.fmttype.as_deref()
+    }
+
+    /// Returns the ways this image is meant to be displayed, an empty list means `BADGE`.
+    pub fn get_display(&self) -> &[ImageDisplay] {
+        &self.display
+    }
+}
+
+impl From<Image> for Property {
+    fn from(image: Image) -> Self {
+        let mut property = Property::new("IMAGE", image.uri);
+        property.add_parameter("VALUE", "URI");
+        if let Some(fmttype) = image.fmttype {
+            property.add_parameter("FMTTYPE", &fmttype);
+        }
+        if !image.display.is_empty() {
+            property.append_parameter(list_param("DISPLAY", &image.display));
+        }
+        property
+    }
+}
+
+impl TryFrom<&Property> for Image {
+    type Error = ();
+
+    fn try_from(property: &Property) -> Result<Self, Self::Error> {
+        let is_binary = property
+            .params()
+            .get("VALUE")
+            .map_or(false, |value| value.value().eq_ignore_ascii_case("BINARY"));
+        if property.key() != "IMAGE" || is_binary || property.params().contains_key("ENCODING") {
+            return Err(());
+        }
+        Ok(Image {
+            uri: property.value().to_owned(),
+            fmttype: property
+                .params()
+                .get("FMTTYPE")
+                .map(|fmttype| fmttype.value().to_owned()),
+            display: param_values(property, "DISPLAY")
+                .filter_map(|display| display.parse().ok())
+                .collect(),
+        })
+    }
+}
+
+/// [`FEATURE`](https://datatracker.ietf.org/doc/html/rfc7986#section-6.3) parameter of a [`Conference`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Audio capability
+    Audio,
+    /// Chat or instant messaging
+    Chat,
+    /// Blog or atom feed
+    Feed,
+    /// Moderator dial-in code
+    Moderator,
+    /// Phone conference
+    Phone,
+    /// Screen sharing
+    Screen,
+    /// Video capability
+    Video,
+    /// Experimental or IANA registered value
+    Other(String),
+}
+
+impl FromStr for Feature {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_uppercase().as_str() {
+            "AUDIO" => Feature::Audio,
+            "CHAT" => Feature::Chat,
+            "FEED" => Feature::Feed,
+            "MODERATOR" => Feature::Moderator,
+            "PHONE" => Feature::Phone,
+            "SCREEN" => Feature::Screen,
+            "VIDEO" => Feature::Video,
+            "" => return Err(()),
+            _ => Feature::Other(s.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Feature::Audio => "AUDIO",
+            Feature::Chat => "CHAT",
+            Feature::Feed => "FEED",
+            Feature::Moderator => "MODERATOR",
+            Feature::Phone => "PHONE",
+            Feature::Screen => "SCREEN",
+            Feature::Video => "VIDEO",
+            Feature::Other(other) => other,
+        })
+    }
+}
+
+/// [`CONFERENCE`](https://datatracker.ietf.org/doc/html/rfc7986#section-5.11) property,
+/// information for accessing a conferencing system.
+///
+/// ```
+/// # use icalendar::*;
+/// let conference = Conference::new("https://chat.example.com/audio?id=123456")
+///     .feature(Feature::Audio)
+///     .feature(Feature::Video)
+///     .label("Attendee dial-in");
+///
+/// let event = Event::new().conference(conference.clone()).done();
+/// assert_eq!(event.get_conferences(), vec![conference]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conference {
+    uri: String,
+    features: Vec<Feature>,
+    label: Option<String>,
+}
+
+impl Conference {
+    /// Creates a conference from the URI used to join it.
+    pub fn new(uri: impl Into<String>) -> Self {
+        Conference {
+            uri: uri.into(),
+            features: Vec::new(),
+            label: None,
+        }
+    }
+
+    /// Adds a feature of this conference.
+    pub fn feature(mut self, feature: Feature) -> Self {
+        self.features.push(feature);
+        self
+    }
+
+    /// Sets a human readable label, written as `LABEL`.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Returns the URI used to join this conference.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Returns the features of this conference.
+    pub fn get_features(&self) -> &[Feature] {
+        &self.features
+    }
+
+    /// Returns the label of this conference.
+    pub fn get_label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+}
+
+impl From<Conference> for Property {
+    fn from(conference: Conference) -> Self {
+        let mut property = Property::new("CONFERENCE", conference.uri);
+        property.add_parameter("VALUE", "URI");
+        if !conference.features.is_empty() {
+            property.append_parameter(list_param("FEATURE", &conference.features));
+        }
+        if let Some(label) = conference.label {
+            property.add_parameter("LABEL", &label);
+        }
+        property
+    }
+}
+
+impl TryFrom<&Property> for Conference {
+    type Error = ();
+
+    fn try_from(property: &Property) -> Result<Self, Self::Error> {
+        if property.key() != "CONFERENCE" {
+            return Err(());
+        }
+        Ok(Conference {
+            uri: property.value().to_owned(),
+            features: param_values(property, "FEATURE")
+                .filter_map(|feature| feature.parse().ok())
+                .collect(),
+            label: property
+                .params()
+                .get("LABEL")
+                .map(|label| label.value().to_owned()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn css3_colors() {
+        assert_eq!(
+            Color::from_str("DarkSlateGrey").unwrap().as_str(),
+            "darkslategrey"
+        );
+        assert_eq!(Color::from_str("#ff0000"), Err(()));
+        assert!(CSS3_COLOR_NAMES
+            .windows(2)
+            .all(|pair| pair.first() < pair.last()));
+    }
+
+    #[test]
+    fn image_property() {
+        let image = Image::new("https://example.com/images/party.png")
+            .fmttype("image/png")
+            .display(ImageDisplay::Badge)
+            .display(ImageDisplay::Thumbnail);
+        let property = Property::from(image.clone());
+
+        let mut out = String::new();
+        property.fmt_write(&mut out).unwrap();
+        assert!(out.contains("DISPLAY=BADGE,THUMBNAIL"));
+        assert_eq!(Image::try_from(&property), Ok(image));
+
+        let inline = Property::new("IMAGE", "iVBORw0KGgo=")
+            .add_parameter("VALUE", "BINARY")
+            .add_parameter("ENCODING", "BASE64")
+            .done();
+        assert_eq!(Image::try_from(&inline), Err(()));
+    }
+
+    #[test]
+    fn conference_property() {
+        let property = Property::new("CONFERENCE", "tel:+1-412-555-0123,,,654321")
+            .add_parameter("VALUE", "URI")
+            .add_parameter("FEATURE", "PHONE,MODERATOR")
+            .add_parameter("LABEL", "Moderator dial-in")
+            .done();
+
+        assert_eq!(
+            Conference::try_from(&property),
+            Ok(Conference::new("tel:+1-412-555-0123,,,654321")
+                .feature(Feature::Phone)
+                .feature(Feature::Moderator)
+                .label("Moderator dial-in"))
+        );
+    }
+}
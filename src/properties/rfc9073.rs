@@ -0,0 +1,356 @@
+//! Typed properties introduced by [RFC 9073](https://datatracker.ietf.org/doc/html/rfc9073)
+
+use std::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use crate::{Parameter, Property, ValueType};
+
+fn param_string(property: &Property, key: &str) -> Option<String> {
+    property
+        .params()
+        .get(key)
+        .map(|param| param.value().to_owned())
+}
+
+/// [`PARTICIPANT-TYPE`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.2) of a
+/// [`Participant`](crate::Participant).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParticipantType {
+    /// A participant taking an active role
+    Active,
+    /// A participant taking an inactive role
+    Inactive,
+    /// A sponsor of the event
+    Sponsor,
+    /// Contact for the event
+    Contact,
+    /// Contact for bookings
+    BookingContact,
+    /// Contact for emergencies
+    EmergencyContact,
+    /// Contact for publicity
+    PublicityContact,
+    /// Contact for the planners of the event
+    PlannerContact,
+    /// A performer
+    Performer,
+    /// A speaker
+    Speaker,
+    /// Experimental or IANA registered value
+    Other(String),
+}
+
+impl FromStr for ParticipantType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_uppercase().as_str() {
+            "ACTIVE" => ParticipantType::Active,
+            "INACTIVE" => ParticipantType::Inactive,
+            "SPONSOR" => ParticipantType::Sponsor,
+            "CONTACT" => ParticipantType::Contact,
+            "BOOKING-CONTACT" => ParticipantType::BookingContact,
+            "EMERGENCY-CONTACT" => ParticipantType::EmergencyContact,
+            "PUBLICITY-CONTACT" => ParticipantType::PublicityContact,
+            "PLANNER-CONTACT" => ParticipantType::PlannerContact,
+            "PERFORMER" => ParticipantType::Performer,
+            "SPEAKER" => ParticipantType::Speaker,
+            "" => return Err(()),
+            _ => ParticipantType::Other(s.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for ParticipantType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParticipantType::Active => "ACTIVE",
+            ParticipantType::Inactive => "INACTIVE",
+            ParticipantType::Sponsor => "SPONSOR",
+            ParticipantType::Contact => "CONTACT",
+            ParticipantType::BookingContact => "BOOKING-CONTACT",
+            ParticipantType::EmergencyContact => "EMERGENCY-CONTACT",
+            ParticipantType::PublicityContact => "PUBLICITY-CONTACT",
+            ParticipantType::PlannerContact => "PLANNER-CONTACT",
+            ParticipantType::Performer => "PERFORMER",
+            ParticipantType::Speaker => "SPEAKER",
+            ParticipantType::Other(other) => other,
+        })
+    }
+}
+
+impl From<ParticipantType> for Property {
+    fn from(participant_type: ParticipantType) -> Self {
+        Property::new("PARTICIPANT-TYPE", participant_type.to_string())
+    }
+}
+
+/// [`RESOURCE-TYPE`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.3) of a
+/// [`Resource`](crate::Resource).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceType {
+    /// A room for the event
+    Room,
+    /// A projector
+    Projector,
+    /// Audio conferencing equipment
+    RemoteConferenceAudio,
+    /// Video conferencing equipment
+    RemoteConferenceVideo,
+    /// Experimental or IANA registered value
+    Other(String),
+}
+
+impl FromStr for ResourceType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_uppercase().as_str() {
+            "ROOM" => ResourceType::Room,
+            "PROJECTOR" => ResourceType::Projector,
+            "REMOTE-CONFERENCE-AUDIO" => ResourceType::RemoteConferenceAudio,
+            "REMOTE-CONFERENCE-VIDEO" => ResourceType::RemoteConferenceVideo,
+            "" => return Err(()),
+            _ => ResourceType::Other(s.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for ResourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ResourceType::Room => "ROOM",
+            ResourceType::Projector => "PROJECTOR",
+            ResourceType::RemoteConferenceAudio => "REMOTE-CONFERENCE-AUDIO",
+            ResourceType::RemoteConferenceVideo => "REMOTE-CONFERENCE-VIDEO",
+            ResourceType::Other(other) => other,
+        })
+    }
+}
+
+impl From<ResourceType> for Property {
+    fn from(resource_type: ResourceType) -> Self {
+        Property::new("RESOURCE-TYPE", resource_type.to_string())
+    }
+}
+
+/// [`STRUCTURED-DATA`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.6) property,
+/// machine readable data like a vCard or JSON-LD.
+///
+/// ```
+/// # use icalendar::*;
+/// let data = StructuredData::Text {
+///     fmttype: "application/ld+json".into(),
+///     schema: "https://schema.org/SportsEvent".into(),
+///     text: r#"{"@context": "http://schema.org"}"#.into(),
+/// };
+/// let event = Event::new().structured_data(data.clone()).done();
+/// assert_eq!(event.get_structured_data(), vec![data]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StructuredData {
+    /// Inline text data, written as `VALUE=TEXT`
+    Text {
+        /// Media type of the data, written as `FMTTYPE`
+        fmttype: String,
+        /// Schema of the data, written as `SCHEMA`
+        schema: String,
+        /// The data itself
+        text: String,
+    },
+    /// Inline binary data, written as `ENCODING=BASE64;VALUE=BINARY`
+    Binary {
+        /// Media type of the data, written as `FMTTYPE`
+        fmttype: String,
+        /// Schema of the data, written as `SCHEMA`
+        schema: String,
+        /// The raw data
+        bytes: Vec<u8>,
+    },
+    /// Reference to the data, written as `VALUE=URI`
+    Uri {
+        /// Media type of the data, written as `FMTTYPE`
+        fmttype: Option<String>,
+        /// Schema of the data, written as `SCHEMA`
+        schema: Option<String>,
+        /// Location of the data
+        uri: String,
+    },
+}
+
+impl From<StructuredData> for Property {
+    fn from(data: StructuredData) -> Self {
+        match data {
+            StructuredData::Text {
+                fmttype,
+                schema,
+                text,
+            } => Property::new("STRUCTURED-DATA", text)
+                .append_parameter(ValueType::Text)
+                .add_parameter("FMTTYPE", &fmttype)
+                .add_parameter("SCHEMA", &schema)
+                .done(),
+            StructuredData::Binary {
+                fmttype,
+                schema,
+                bytes,
+            } => Property::new("STRUCTURED-DATA", STANDARD.encode(bytes))
+                .add_parameter("ENCODING", "BASE64")
+                .append_parameter(ValueType::Binary)
+                .add_parameter("FMTTYPE", &fmttype)
+                .add_parameter("SCHEMA", &schema)
+                .done(),
+            StructuredData::Uri {
+                fmttype,
+                schema,
+                uri,
+            } => {
+                let mut property = Property::new("STRUCTURED-DATA", uri);
+                property.append_parameter(ValueType::Uri);
+                if let Some(fmttype) = fmttype {
+                    property.add_parameter("FMTTYPE", &fmttype);
+                }
+                if let Some(schema) = schema {
+                    property.add_parameter("SCHEMA", &schema);
+                }
+                property
+            }
+        }
+    }
+}
+
+impl TryFrom<&Property> for StructuredData {
+    type Error = ();
+
+    fn try_from(property: &Property) -> Result<Self, Self::Error> {
+        if property.key() != "STRUCTURED-DATA" {
+            return Err(());
+        }
+        let fmttype = param_string(property, "FMTTYPE");
+        let schema = param_string(property, "SCHEMA");
+        match property.value_type() {
+            Some(ValueType::Text) => Ok(StructuredData::Text {
+                fmttype: fmttype.ok_or(())?,
+                schema: schema.ok_or(())?,
+                text: property.value().to_owned(),
+            }),
+            Some(ValueType::Binary) => Ok(StructuredData::Binary {
+                fmttype: fmttype.ok_or(())?,
+                schema: schema.ok_or(())?,
+                bytes: STANDARD.decode(property.value()).map_err(|_| ())?,
+            }),
+            Some(ValueType::Uri) => Ok(StructuredData::Uri {
+                fmttype,
+                schema,
+                uri: property.value().to_owned(),
+            }),
+            _ => Err(()),
+        }
+    }
+}
+
+/// [`STYLED-DESCRIPTION`](https://datatracker.ietf.org/doc/html/rfc9073#section-6.5) property,
+/// a rich text description such as HTML.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StyledDescription {
+    /// Inline description, written as `VALUE=TEXT`
+    Text {
+        /// Media type of the description, written as `FMTTYPE`, e.g. `text/html`
+        fmttype: String,
+        /// The styled description
+        text: String,
+    },
+    /// Reference to the description, written as `VALUE=URI`
+    Uri(String),
+}
+
+impl From<StyledDescription> for Property {
+    fn from(description: StyledDescription) -> Self {
+        match description {
+            StyledDescription::Text { fmttype, text } => Property::new("STYLED-DESCRIPTION", text)
+                .append_parameter(ValueType::Text)
+                .add_parameter("FMTTYPE", &fmttype)
+                .done(),
+            StyledDescription::Uri(uri) => Property::new("STYLED-DESCRIPTION", uri)
+                .append_parameter(ValueType::Uri)
+                .done(),
+        }
+    }
+}
+
+impl TryFrom<&Property> for StyledDescription {
+    type Error = ();
+
+    fn try_from(property: &Property) -> Result<Self, Self::Error> {
+        if property.key() != "STYLED-DESCRIPTION" {
+            return Err(());
+        }
+        match property.value_type() {
+            Some(ValueType::Uri) => Ok(StyledDescription::Uri(property.value().to_owned())),
+            Some(ValueType::Text) => Ok(StyledDescription::Text {
+                fmttype: property
+                    .params()
+                    .get("FMTTYPE")
+                    .map_or("text/plain", Parameter::value)
+                    .to_owned(),
+                text: property.value().to_owned(),
+            }),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn structured_data_roundtrip() {
+        for data in [
+            StructuredData::Text {
+                fmttype: "text/vcard".into(),
+                schema: "https://www.rfc-editor.org/rfc/rfc6350".into(),
+                text: "BEGIN:VCARD\nVERSION:4.0\nEND:VCARD".into(),
+            },
+            StructuredData::Binary {
+                fmttype: "application/octet-stream".into(),
+                schema: "https://example.com/schema".into(),
+                bytes: vec![0, 1, 2, 3],
+            },
+            StructuredData::Uri {
+                fmttype: None,
+                schema: Some("https://schema.org/Place".into()),
+                uri: "https://example.com/place.json".into(),
+            },
+        ] {
+            assert_eq!(
+                StructuredData::try_from(&Property::from(data.clone())),
+                Ok(data)
+            );
+        }
+    }
+
+    #[test]
+    fn structured_text_requires_schema() {
+        let property = Property::new("STRUCTURED-DATA", "{}")
+            .append_parameter(ValueType::Text)
+            .add_parameter("FMTTYPE", "application/json")
+            .done();
+        assert_eq!(StructuredData::try_from(&property), Err(()));
+    }
+
+    #[test]
+    fn participant_types() {
+        for participant_type in ["SPEAKER", "BOOKING-CONTACT", "X-HOST"] {
+            assert_eq!(
+                ParticipantType::from_str(participant_type)
+                    .unwrap()
+                    .to_string(),
+                participant_type
+            );
+        }
+    }
+}
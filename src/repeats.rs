@@ -2,7 +2,7 @@
 //!
 //! Taken from the rfc:
 //!
-//! ```
+//! ```text
 //!   +----------+--------+--------+-------+-------+------+-------+------+
 //!   |          |SECONDLY|MINUTELY|HOURLY |DAILY  |WEEKLY|MONTHLY|YEARLY|
 //!   +----------+--------+--------+-------+-------+------+-------+------+
@@ -25,82 +25,924 @@
 //!   |BYSETPOS  |Limit   |Limit   |Limit  |Limit  |Limit |Limit  |Limit |
 //!   +----------+--------+--------+-------+-------+------+-------+------+
 //! ```
+//!
+//! Weeks of `BYWEEKNO` start on `WKST`, the first week of a year is the first one with at least four of its days in that year.
+//! Where the table says N/A `BYWEEKNO` and `BYYEARDAY` still limit the occurrences.
 
+use std::{collections::VecDeque, fmt, str::FromStr};
 
-//     RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU
-//     RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU
-
-use chrono::{DateTime, Local};
-
-//#[derive(Debug,Clone)]
-//pub struct Every {
-//    freq: Freq,
-//    limit: Vec<Limit>,
-//    interval: usize,
-//    recurrence: Recurrence
-//}
-//
-//impl Every {
-//    pub fn new() -> Self {
-//        Every {
-//            freq: None,
-//            limit: Vec::new(),
-//            interval: 0,
-//            recurrence: None
-//        }
-//    }
-//    pub fn second(&mut self) -> &mut Self {
-//        self.interval = 2;
-//        self
-//    }
-//}
-
-#[derive(Debug,Clone)]
-pub enum Freq {
-    Daily,
-    Hourly,
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Weekday};
+
+use crate::{CalendarDateTime, DatePerhapsTime};
+
+/// Gives up on rules like `FREQ=MONTHLY;BYMONTHDAY=30;BYMONTH=2` which never match.
+const MAX_EMPTY_PERIODS: u32 = 1000;
+
+/// `FREQ` of a [`RecurrenceRule`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Frequency {
+    /// `SECONDLY`
+    Secondly,
+    /// `MINUTELY`
     Minutely,
-    Monthly,
+    /// `HOURLY`
+    Hourly,
+    /// `DAILY`
+    Daily,
+    /// `WEEKLY`
     Weekly,
+    /// `MONTHLY`
+    Monthly,
+    /// `YEARLY`
     Yearly,
-    Secondly,
-    None
 }
 
-#[derive(Debug,Clone)]
-pub enum Limit {
-    Until(DateTime<Local>),
-    Count(usize),
-    Interval(usize)
+impl Frequency {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Secondly => "SECONDLY",
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "SECONDLY" => Frequency::Secondly,
+            "MINUTELY" => Frequency::Minutely,
+            "HOURLY" => Frequency::Hourly,
+            "DAILY" => Frequency::Daily,
+            "WEEKLY" => Frequency::Weekly,
+            "MONTHLY" => Frequency::Monthly,
+            "YEARLY" => Frequency::Yearly,
+            _ => return Err(()),
+        })
+    }
+}
+
+fn weekday_as_str(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, ()> {
+    Ok(match s {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(()),
+    })
+}
+
+/// A weekday of `BYDAY`, optionally the n-th one of the month or year like `-1FR`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WeekdayNum {
+    /// Counted from the end of the month or year if negative
+    pub ordinal: Option<i8>,
+    /// The weekday
+    pub weekday: Weekday,
+}
+
+impl From<Weekday> for WeekdayNum {
+    fn from(weekday: Weekday) -> Self {
+        WeekdayNum {
+            ordinal: None,
+            weekday,
+        }
+    }
+}
+
+impl From<(i8, Weekday)> for WeekdayNum {
+    fn from((ordinal, weekday): (i8, Weekday)) -> Self {
+        WeekdayNum {
+            ordinal: Some(ordinal),
+            weekday,
+        }
+    }
+}
+
+impl fmt::Display for WeekdayNum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ordinal) = self.ordinal {
+            write!(f, "{}", ordinal)?;
+        }
+        f.write_str(weekday_as_str(self.weekday))
+    }
+}
+
+impl FromStr for WeekdayNum {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s.len().checked_sub(2).ok_or(())?;
+        let (ordinal, weekday) = (s.get(..split).ok_or(())?, s.get(split..).ok_or(())?);
+        let ordinal = match ordinal.strip_prefix('+').unwrap_or(ordinal) {
+            "" => None,
+            ordinal => match ordinal.parse::<i8>().map_err(|_| ())? {
+                0 => return Err(()),
+                ordinal => Some(ordinal),
+            },
+        };
+        Ok(WeekdayNum {
+            ordinal,
+            weekday: parse_weekday(weekday)?,
+        })
+    }
+}
+
+/// A recurrence rule as used in `RRULE`
+///
+/// [RFC 5545, Section 3.3.10](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.10)
+///
+/// ```
+/// # use icalendar::*;
+/// # use chrono::*;
+/// let rule = RecurrenceRule::new(Frequency::Monthly)
+///     .by_day([(-1, Weekday::Fri)])
+///     .count(3);
+/// assert_eq!(rule.to_string(), "FREQ=MONTHLY;COUNT=3;BYDAY=-1FR");
+///
+/// let start = NaiveDate::from_ymd_opt(2024, 1, 26).unwrap().and_hms_opt(17, 0, 0).unwrap();
+/// let fridays = rule.occurrences(start).map(|dt| dt.date().to_string()).collect::<Vec<_>>();
+/// assert_eq!(fridays, ["2024-01-26", "2024-02-23", "2024-03-29"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<DatePerhapsTime>,
+    by_second: Vec<u32>,
+    by_minute: Vec<u32>,
+    by_hour: Vec<u32>,
+    by_day: Vec<WeekdayNum>,
+    by_month_day: Vec<i8>,
+    by_year_day: Vec<i16>,
+    by_week_no: Vec<i8>,
+    by_month: Vec<u32>,
+    by_set_pos: Vec<i32>,
+    week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    /// Creates a rule repeating every period of the given frequency.
+    pub fn new(frequency: Frequency) -> Self {
+        RecurrenceRule {
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_second: Vec::new(),
+            by_minute: Vec::new(),
+            by_hour: Vec::new(),
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_year_day: Vec::new(),
+            by_week_no: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Mon,
+        }
+    }
+
+    /// Sets `INTERVAL`, e.g. `2` to repeat every other period.
+    pub fn interval(mut self, interval: u32) -> Self {
+        self.interval = interval.max(1);
+        self
+    }
+
+    /// Sets `COUNT`, the total number of occurrences.
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Sets `UNTIL`, the last possible occurrence.
+    pub fn until(mut self, until: impl Into<DatePerhapsTime>) -> Self {
+        self.until = Some(until.into());
+        self
+    }
+
+    /// Sets `BYSECOND`.
+    pub fn by_second(mut self, seconds: impl IntoIterator<Item = u32>) -> Self {
+        self.by_second = seconds.into_iter().filter(|s| *s <= 60).collect();
+        self
+    }
+
+    /// Sets `BYMINUTE`.
+    pub fn by_minute(mut self, minutes: impl IntoIterator<Item = u32>) -> Self {
+        self.by_minute = minutes.into_iter().filter(|m| *m < 60).collect();
+        self
+    }
+
+    /// Sets `BYHOUR`.
+    pub fn by_hour(mut self, hours: impl IntoIterator<Item = u32>) -> Self {
+        self.by_hour = hours.into_iter().filter(|h| *h < 24).collect();
+        self
+    }
+
+    /// Sets `BYDAY`, either plain weekdays or tuples like `(-1, Weekday::Fri)`.
+    pub fn by_day<D: Into<WeekdayNum>>(mut self, days: impl IntoIterator<Item = D>) -> Self {
+        self.by_day = days.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets `BYMONTHDAY`, negative days count from the end of the month.
+    pub fn by_month_day(mut self, days: impl IntoIterator<Item = i8>) -> Self {
+        self.by_month_day = days
+            .into_iter()
+            .filter(|d| *d != 0 && (-31..=31).contains(d))
+            .collect();
+        self
+    }
+
+    /// Sets `BYYEARDAY`, negative days count from the end of the year.
+    pub fn by_year_day(mut self, days: impl IntoIterator<Item = i16>) -> Self {
+        self.by_year_day = days
+            .into_iter()
+            .filter(|d| *d != 0 && (-366..=366).contains(d))
+            .collect();
+        self
+    }
+
+    /// Sets `BYWEEKNO`, negative weeks count from the end of the year.
+    pub fn by_week_no(mut self, weeks: impl IntoIterator<Item = i8>) -> Self {
+        self.by_week_no = weeks
+            .into_iter()
+            .filter(|w| *w != 0 && (-53..=53).contains(w))
+            .collect();
+        self
+    }
+
+    /// Sets `BYMONTH`.
+    pub fn by_month(mut self, months: impl IntoIterator<Item = u32>) -> Self {
+        self.by_month = months
+            .into_iter()
+            .filter(|m| (1..=12).contains(m))
+            .collect();
+        self
+    }
+
+    /// Sets `BYSETPOS`, which picks occurrences by position within each period.
+    pub fn by_set_pos(mut self, positions: impl IntoIterator<Item = i32>) -> Self {
+        self.by_set_pos = positions.into_iter().filter(|p| *p != 0).collect();
+        self
+    }
+
+    /// Sets `WKST`, the day a week starts on, which matters for weekly rules with an interval and `BYWEEKNO`.
+    pub fn week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// Returns the `FREQ` of this rule.
+    pub fn get_frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    /// Returns the `COUNT` of this rule.
+    pub fn get_count(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// Returns the `UNTIL` of this rule.
+    pub fn get_until(&self) -> Option<&DatePerhapsTime> {
+        self.until.as_ref()
+    }
+
+    /// Iterates over the occurrences of this rule starting at `dtstart`, which is always the first.
+    ///
+    /// All computation happens on the wall-clock, an `UNTIL` given in UTC is compared as is.
+    pub fn occurrences(&self, dtstart: NaiveDateTime) -> Occurrences {
+        let until = self.until.as_ref().map(|until| match until {
+            DatePerhapsTime::Date(date) => date.and_hms_opt(23, 59, 59).unwrap_or_default(),
+            DatePerhapsTime::DateTime(date_time) => naive_date_time(date_time),
+        });
+        self.occurrences_until(dtstart, until)
+    }
+
+    /// Like [`RecurrenceRule::occurrences`] but with `UNTIL` already converted to the wall-clock of `dtstart`.
+    pub(crate) fn occurrences_until(
+        &self,
+        dtstart: NaiveDateTime,
+        until: Option<NaiveDateTime>,
+    ) -> Occurrences {
+        Occurrences {
+            rule: self.normalized(dtstart),
+            dtstart,
+            until,
+            period: 0,
+            pending: VecDeque::from([dtstart]),
+            emitted: 0,
+            empty_periods: 0,
+            exhausted: false,
+        }
+    }
+
+    /// Fills in the implicit parts of a rule from `dtstart`, e.g. `FREQ=MONTHLY` repeats on its day of the month.
+    fn normalized(&self, dtstart: NaiveDateTime) -> RecurrenceRule {
+        let mut rule = self.clone();
+        if rule.by_day.is_empty() && rule.by_month_day.is_empty() && rule.by_year_day.is_empty() {
+            match rule.frequency {
+                Frequency::Yearly if !rule.by_week_no.is_empty() => {
+                    rule.by_day = vec![dtstart.weekday().into()]
+                }
+                Frequency::Yearly => {
+                    if rule.by_month.is_empty() {
+                        rule.by_month = vec![dtstart.month()];
+                    }
+                    rule.by_month_day = vec![dtstart.day() as i8];
+                }
+                Frequency::Monthly => rule.by_month_day = vec![dtstart.day() as i8],
+                Frequency::Weekly => rule.by_day = vec![dtstart.weekday().into()],
+                _ => {}
+            }
+        }
+        rule
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let (month_length, year_length) = (days_in_month(date), days_in_year(date));
+        let ordinal_in_year = self.frequency == Frequency::Yearly && self.by_month.is_empty();
+
+        (self.by_month.is_empty() || self.by_month.contains(&date.month()))
+            && (self.by_month_day.is_empty()
+                || self.by_month_day.iter().any(|day| {
+                    let day = i64::from(*day);
+                    let from_start = i64::from(date.day());
+                    day == from_start || day == from_start - month_length - 1
+                }))
+            && (self.by_year_day.is_empty()
+                || self.by_year_day.iter().any(|day| {
+                    let day = i64::from(*day);
+                    let from_start = i64::from(date.ordinal());
+                    day == from_start || day == from_start - year_length - 1
+                }))
+            && (self.by_week_no.is_empty()
+                || week_of_year(date, self.week_start).map_or(false, |(year, week)| {
+                    let weeks = weeks_in_year(year, self.week_start).unwrap_or(52);
+                    self.by_week_no.iter().any(|by_week| {
+                        let by_week = i64::from(*by_week);
+                        by_week == week || by_week == week - weeks - 1
+                    })
+                }))
+            && (self.by_day.is_empty()
+                || self.by_day.iter().any(|by_day| {
+                    if by_day.weekday != date.weekday() {
+                        return false;
+                    }
+                    let ordinal = match by_day.ordinal {
+                        Some(ordinal)
+                            if matches!(self.frequency, Frequency::Monthly | Frequency::Yearly) =>
+                        {
+                            i64::from(ordinal)
+                        }
+                        _ => return true,
+                    };
+                    let (position, length) = if ordinal_in_year {
+                        (i64::from(date.ordinal()), year_length)
+                    } else {
+                        (i64::from(date.day()), month_length)
+                    };
+                    if ordinal > 0 {
+                        (position - 1) / 7 + 1 == ordinal
+                    } else {
+                        (length - position) / 7 + 1 == -ordinal
+                    }
+                }))
+    }
+
+    /// Computes all candidates of the `period`-th period, sorted and with `BYSETPOS` applied.
+    fn expand_period(&self, dtstart: NaiveDateTime, period: i64) -> Option<Vec<NaiveDateTime>> {
+        let step = i64::from(self.interval).checked_mul(period)?;
+        let start_date = dtstart.date();
+
+        let (dates, base) = match self.frequency {
+            Frequency::Yearly => {
+                let year = i32::try_from(i64::from(start_date.year()).checked_add(step)?).ok()?;
+                let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let dates = if self.by_week_no.is_empty() {
+                    first.iter_days().take_while(|d| d.year() == year).collect()
+                } else {
+                    // the first and last week may reach into the neighbouring years
+                    first
+                        .checked_sub_signed(Duration::days(6))?
+                        .iter_days()
+                        .take_while(|d| d.year() <= year || d.ordinal() <= 7)
+                        .filter(|d| {
+                            week_of_year(*d, self.week_start).map(|(of, _)| of) == Some(year)
+                        })
+                        .collect()
+                };
+                (dates, dtstart)
+            }
+            Frequency::Monthly => {
+                let months = i64::from(start_date.year()) * 12 + i64::from(start_date.month0());
+                let months = months.checked_add(step)?;
+                let year = i32::try_from(months.div_euclid(12)).ok()?;
+                let month = u32::try_from(months.rem_euclid(12)).ok()? + 1;
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                (
+                    first
+                        .iter_days()
+                        .take_while(|d| d.month() == month)
+                        .collect(),
+                    dtstart,
+                )
+            }
+            Frequency::Weekly => {
+                let offset = (7 + start_date.weekday().num_days_from_monday()
+                    - self.week_start.num_days_from_monday())
+                    % 7;
+                let first = start_date
+                    .checked_sub_signed(Duration::days(i64::from(offset)))?
+                    .checked_add_signed(Duration::try_weeks(step)?)?;
+                (first.iter_days().take(7).collect(), dtstart)
+            }
+            Frequency::Daily => (
+                vec![start_date.checked_add_signed(Duration::try_days(step)?)?],
+                dtstart,
+            ),
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+                let seconds = match self.frequency {
+                    Frequency::Hourly => 3600,
+                    Frequency::Minutely => 60,
+                    _ => 1,
+                };
+                let base = dtstart
+                    .checked_add_signed(Duration::try_seconds(step.checked_mul(seconds)?)?)?;
+                (vec![base.date()], base)
+            }
+        };
+
+        let time_part = |values: &[u32], own: u32, expands: bool| -> Vec<u32> {
+            if values.is_empty() {
+                vec![own]
+            } else if expands {
+                values.to_vec()
+            } else if values.contains(&own) {
+                vec![own]
+            } else {
+                Vec::new()
+            }
+        };
+        let hours = time_part(
+            &self.by_hour,
+            base.hour(),
+            self.frequency > Frequency::Hourly,
+        );
+        let minutes = time_part(
+            &self.by_minute,
+            base.minute(),
+            self.frequency > Frequency::Minutely,
+        );
+        let seconds = time_part(
+            &self.by_second,
+            base.second(),
+            self.frequency > Frequency::Secondly,
+        );
+
+        let mut candidates = Vec::new();
+        for date in dates.into_iter().filter(|date| self.day_matches(*date)) {
+            for hour in &hours {
+                for minute in &minutes {
+                    for second in &seconds {
+                        if let Some(candidate) = date.and_hms_opt(*hour, *minute, *second) {
+                            candidates.push(candidate);
+                        }
+                    }
+                }
+            }
+        }
+        candidates.sort();
+        candidates.dedup();
+
+        if !self.by_set_pos.is_empty() {
+            let len = candidates.len() as i64;
+            let mut selected = self
+                .by_set_pos
+                .iter()
+                .filter_map(|pos| {
+                    let index = if *pos > 0 {
+                        i64::from(*pos) - 1
+                    } else {
+                        len + i64::from(*pos)
+                    };
+                    candidates.get(usize::try_from(index).ok()?).copied()
+                })
+                .collect::<Vec<_>>();
+            selected.sort();
+            selected.dedup();
+            candidates = selected;
+        }
+        Some(candidates)
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> i64 {
+    let (year, month) = (date.year(), date.month());
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    };
+    next.and_then(|next| {
+        NaiveDate::from_ymd_opt(year, month, 1).map(|first| (next - first).num_days())
+    })
+    .unwrap_or(31)
 }
 
-#[derive(Debug,Clone)]
-pub enum Recurrence {
-    //Bysecond( Byseclist )
-    //Byminute( Byminlist )
-    //Byhour( Byhrlist )
-    //Byday( Bywdaylist )
-    //Bymonthday( Bymodaylist )
-    //Byyearday( Byyrdaylist )
-    //Byweekno( Bywknolist )
-    //Bymonth( Bymolist )
-    //Bysetpos( Bysplist )
-    //Wkst( Weekday ),
-    None
+/// The year a week belongs to and its number, the year of a week is the one holding its fourth day.
+fn week_of_year(date: NaiveDate, week_start: Weekday) -> Option<(i32, i64)> {
+    let offset =
+        (7 + date.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+    let fourth_day = date
+        .checked_sub_signed(Duration::days(i64::from(offset)))?
+        .checked_add_signed(Duration::days(3))?;
+    Some((fourth_day.year(), i64::from(fourth_day.ordinal0()) / 7 + 1))
 }
 
+/// The number of weeks in a year, the last one always contains the 28th of December.
+fn weeks_in_year(year: i32, week_start: Weekday) -> Option<i64> {
+    week_of_year(NaiveDate::from_ymd_opt(year, 12, 28)?, week_start).map(|(_, week)| week)
+}
 
-enum Weekday {
-    Su, Mo, Tu, We, Th, Fr, Sa
+fn days_in_year(date: NaiveDate) -> i64 {
+    if NaiveDate::from_ymd_opt(date.year(), 2, 29).is_some() {
+        366
+    } else {
+        365
+    }
 }
 
+/// The wall-clock of a date-time, UTC date-times are taken as they are.
+pub(crate) fn naive_date_time(date_time: &CalendarDateTime) -> NaiveDateTime {
+    match date_time {
+        CalendarDateTime::Floating(naive) => *naive,
+        CalendarDateTime::Utc(utc) => utc.naive_utc(),
+        CalendarDateTime::WithTimezone { date_time, .. } => *date_time,
+    }
+}
 
+/// Iterator over the occurrences of a [`RecurrenceRule`], see [`RecurrenceRule::occurrences`].
+#[derive(Debug)]
+pub struct Occurrences {
+    rule: RecurrenceRule,
+    dtstart: NaiveDateTime,
+    until: Option<NaiveDateTime>,
+    period: i64,
+    pending: VecDeque<NaiveDateTime>,
+    emitted: u32,
+    empty_periods: u32,
+    exhausted: bool,
+}
+
+impl Iterator for Occurrences {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(next) = self.pending.pop_front() {
+                if self.rule.count.map_or(false, |count| self.emitted >= count)
+                    || self.until.map_or(false, |until| next > until)
+                {
+                    self.exhausted = true;
+                    self.pending.clear();
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(next);
+            }
+            if self.exhausted {
+                return None;
+            }
 
+            match self.rule.expand_period(self.dtstart, self.period) {
+                Some(candidates) => {
+                    self.period += 1;
+                    let before = self.pending.len();
+                    // DTSTART was already emitted as the first occurrence
+                    self.pending.extend(
+                        candidates
+                            .into_iter()
+                            .filter(|candidate| *candidate > self.dtstart),
+                    );
+                    if self.pending.len() == before {
+                        self.empty_periods += 1;
+                        self.exhausted = self.empty_periods > MAX_EMPTY_PERIODS;
+                    } else {
+                        self.empty_periods = 0;
+                    }
+                }
+                None => self.exhausted = true,
+            }
+        }
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: ToString>(values: &[T]) -> String {
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        }
 
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if let Some(until) = &self.until {
+            write!(f, ";UNTIL={}", until.to_property("UNTIL").value())?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        for (key, values) in [
+            ("BYSECOND", join(&self.by_second)),
+            ("BYMINUTE", join(&self.by_minute)),
+            ("BYHOUR", join(&self.by_hour)),
+            ("BYDAY", join(&self.by_day)),
+            ("BYMONTHDAY", join(&self.by_month_day)),
+            ("BYYEARDAY", join(&self.by_year_day)),
+            ("BYWEEKNO", join(&self.by_week_no)),
+            ("BYMONTH", join(&self.by_month)),
+            ("BYSETPOS", join(&self.by_set_pos)),
+        ] {
+            if !values.is_empty() {
+                write!(f, ";{}={}", key, values)?;
+            }
+        }
+        if self.week_start != Weekday::Mon {
+            write!(f, ";WKST={}", weekday_as_str(self.week_start))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn list<T: FromStr>(value: &str) -> Result<Vec<T>, ()> {
+            value
+                .split(',')
+                .map(|v| v.trim().parse().map_err(|_| ()))
+                .collect()
+        }
+
+        let mut parts = s.trim().split(';').filter(|part| !part.is_empty());
+        let mut rule = RecurrenceRule::new(Frequency::Daily);
+        let mut frequency = None;
+
+        for part in &mut parts {
+            let (key, value) = part.split_once('=').ok_or(())?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(value.to_ascii_uppercase().parse()?),
+                "INTERVAL" => rule = rule.interval(value.parse().map_err(|_| ())?),
+                "COUNT" => rule = rule.count(value.parse().map_err(|_| ())?),
+                "UNTIL" => {
+                    rule.until = Some(match CalendarDateTime::from_str(value) {
+                        Ok(date_time) => date_time.into(),
+                        Err(_) => NaiveDate::parse_from_str(value, "%Y%m%d")
+                            .map_err(|_| ())?
+                            .into(),
+                    })
+                }
+                "BYSECOND" => rule = rule.by_second(list(value)?),
+                "BYMINUTE" => rule = rule.by_minute(list(value)?),
+                "BYHOUR" => rule = rule.by_hour(list(value)?),
+                "BYDAY" => rule.by_day = list(&value.to_ascii_uppercase())?,
+                "BYMONTHDAY" => rule = rule.by_month_day(list(value)?),
+                "BYYEARDAY" => rule = rule.by_year_day(list(value)?),
+                "BYWEEKNO" => rule = rule.by_week_no(list(value)?),
+                "BYMONTH" => rule = rule.by_month(list(value)?),
+                "BYSETPOS" => rule = rule.by_set_pos(list(value)?),
+                "WKST" => rule.week_start = parse_weekday(&value.to_ascii_uppercase())?,
+                x_name if x_name.starts_with("X-") => {}
+                _ => return Err(()),
+            }
+        }
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err(());
+        }
+        rule.frequency = frequency.ok_or(())?;
+        Ok(rule)
+    }
+}
 
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
 
+    use super::*;
 
+    fn start(y: i32, m: u32, d: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
 
+    fn dates(rule: &str, dtstart: NaiveDateTime, take: usize) -> Vec<String> {
+        RecurrenceRule::from_str(rule)
+            .unwrap()
+            .occurrences(dtstart)
+            .take(take)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .collect()
+    }
 
+    #[test]
+    fn roundtrip() {
+        for rule in [
+            "FREQ=DAILY;COUNT=10",
+            "FREQ=WEEKLY;UNTIL=19971224T000000Z;INTERVAL=2;BYDAY=MO,WE,FR;WKST=SU",
+            "FREQ=MONTHLY;BYDAY=-2MO",
+            "FREQ=YEARLY;BYDAY=1SU;BYMONTH=3",
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            "FREQ=DAILY;UNTIL=20000131;BYMONTH=1",
+            "FREQ=YEARLY;BYDAY=MO;BYWEEKNO=20",
+            "FREQ=YEARLY;BYYEARDAY=1,100,-1",
+        ] {
+            assert_eq!(RecurrenceRule::from_str(rule).unwrap().to_string(), rule);
+        }
+        for invalid in [
+            "COUNT=2",
+            "FREQ=FORTNIGHTLY",
+            "FREQ=YEARLY;BYWEEKNO=last",
+            "FREQ=DAILY;COUNT=2;UNTIL=20000131",
+        ] {
+            assert_eq!(RecurrenceRule::from_str(invalid), Err(()), "{invalid}");
+        }
+    }
 
-pub trait Repeater: Sized {}
+    #[test]
+    fn daily_with_count() {
+        assert_eq!(
+            dates("FREQ=DAILY;COUNT=3", start(1997, 9, 2), 10),
+            ["1997-09-02 09:00", "1997-09-03 09:00", "1997-09-04 09:00"]
+        );
+    }
+
+    #[test]
+    fn weekly_until() {
+        // every other week on Tuesday and Thursday, from the RFC
+        assert_eq!(
+            dates(
+                "FREQ=WEEKLY;INTERVAL=2;UNTIL=19971007T000000Z;WKST=SU;BYDAY=TU,TH",
+                start(1997, 9, 2),
+                20
+            ),
+            [
+                "1997-09-02 09:00",
+                "1997-09-04 09:00",
+                "1997-09-16 09:00",
+                "1997-09-18 09:00",
+                "1997-09-30 09:00",
+                "1997-10-02 09:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_by_day() {
+        // first and last Sunday of the month, from the RFC
+        assert_eq!(
+            dates("FREQ=MONTHLY;COUNT=6;BYDAY=1SU,-1SU", start(1997, 9, 7), 10),
+            [
+                "1997-09-07 09:00",
+                "1997-09-28 09:00",
+                "1997-10-05 09:00",
+                "1997-10-26 09:00",
+                "1997-11-02 09:00",
+                "1997-11-30 09:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_skips_short_months() {
+        assert_eq!(
+            dates("FREQ=MONTHLY;COUNT=3", start(2024, 1, 31), 10),
+            ["2024-01-31 09:00", "2024-03-31 09:00", "2024-05-31 09:00"]
+        );
+    }
+
+    #[test]
+    fn monthly_set_pos() {
+        // last work day of the month, from the RFC
+        assert_eq!(
+            dates(
+                "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+                start(1997, 9, 30),
+                3
+            ),
+            ["1997-09-30 09:00", "1997-10-31 09:00", "1997-11-28 09:00"]
+        );
+    }
+
+    #[test]
+    fn yearly() {
+        assert_eq!(
+            dates("FREQ=YEARLY;COUNT=3", start(2020, 2, 29), 10),
+            ["2020-02-29 09:00", "2024-02-29 09:00", "2028-02-29 09:00"]
+        );
+        // first Sunday in March
+        assert_eq!(
+            dates("FREQ=YEARLY;BYMONTH=3;BYDAY=1SU", start(2024, 3, 3), 3),
+            ["2024-03-03 09:00", "2025-03-02 09:00", "2026-03-01 09:00"]
+        );
+    }
+
+    #[test]
+    fn hourly_and_expanded_times() {
+        assert_eq!(
+            dates("FREQ=HOURLY;INTERVAL=3;COUNT=3", start(1997, 9, 2), 10),
+            ["1997-09-02 09:00", "1997-09-02 12:00", "1997-09-02 15:00"]
+        );
+        assert_eq!(
+            dates("FREQ=DAILY;BYHOUR=9,17;COUNT=4", start(1997, 9, 2), 10),
+            [
+                "1997-09-02 09:00",
+                "1997-09-02 17:00",
+                "1997-09-03 09:00",
+                "1997-09-03 17:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn never_matching_rule_terminates() {
+        assert_eq!(
+            dates(
+                "FREQ=MONTHLY;BYMONTHDAY=30;BYMONTH=2",
+                start(2024, 1, 30),
+                10
+            ),
+            ["2024-01-30 09:00"]
+        );
+    }
+
+    #[test]
+    fn yearly_by_week_no() {
+        // Monday of week 20, from the RFC
+        assert_eq!(
+            dates("FREQ=YEARLY;BYWEEKNO=20;BYDAY=MO", start(1997, 5, 12), 3),
+            ["1997-05-12 09:00", "1998-05-11 09:00", "1999-05-17 09:00"]
+        );
+        // the weekday comes from DTSTART, week 1 of 2025 starts in 2024
+        assert_eq!(
+            dates("FREQ=YEARLY;BYWEEKNO=1,-1", start(2024, 1, 1), 4),
+            [
+                "2024-01-01 09:00",
+                "2024-12-23 09:00",
+                "2024-12-30 09:00",
+                "2025-12-22 09:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn yearly_by_year_day() {
+        // from the RFC
+        assert_eq!(
+            dates(
+                "FREQ=YEARLY;INTERVAL=3;COUNT=4;BYYEARDAY=1,100,200",
+                start(1997, 1, 1),
+                10
+            ),
+            [
+                "1997-01-01 09:00",
+                "1997-04-10 09:00",
+                "1997-07-19 09:00",
+                "2000-01-01 09:00",
+            ]
+        );
+        assert_eq!(
+            dates("FREQ=YEARLY;BYYEARDAY=-1", start(2024, 12, 31), 2),
+            ["2024-12-31 09:00", "2025-12-31 09:00"]
+        );
+    }
+}
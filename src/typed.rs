@@ -0,0 +1,291 @@
+//! Properties with typed values
+//!
+//! A [`TypedProperty`] declares the name, value type, cardinality and allowed components
+//! of a property once, [`Component::set()`](crate::Component::set) and
+//! [`Component::get()`](crate::Component::get) then convert its value from and to a rust type.
+//! The built-in properties in this module are used by the setters and getters like
+//! [`Component::summary()`](crate::Component::summary), custom and `X-` properties work the same:
+//!
+//! ```
+//! # use icalendar::{*, typed::*};
+//! /// `X-APPLE-TRAVEL-DURATION` as a number of minutes
+//! struct TravelMinutes;
+//!
+//! impl TypedProperty for TravelMinutes {
+//!     const NAME: &'static str = "X-APPLE-TRAVEL-DURATION";
+//!     const VALUE_TYPE: ValueType = ValueType::Duration;
+//!     const COMPONENTS: &'static [&'static str] = &["VEVENT"];
+//!     type Value = u32;
+//!
+//!     fn to_property(minutes: u32) -> Property {
+//!         Property::new(Self::NAME, format!("PT{}M", minutes))
+//!             .add_parameter("VALUE", "DURATION")
+//!             .done()
+//!     }
+//!
+//!     fn from_property(property: &Property) -> Option<u32> {
+//!         property.value().strip_prefix("PT")?.strip_suffix('M')?.parse().ok()
+//!     }
+//! }
+//!
+//! let event = Event::new().set::<TravelMinutes>(15).done();
+//! assert_eq!(event.get::<TravelMinutes>(), Some(15));
+//! assert!(event.to_string().contains("X-APPLE-TRAVEL-DURATION;VALUE=DURATION:PT15M"));
+//! ```
+
+use crate::{Class, Property, ValueType};
+
+/// How often a property may occur within one component
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Cardinality {
+    /// At most once, setting it again replaces the value
+    Once,
+    /// Any number of times, setting it again adds another value
+    Many,
+}
+
+/// Declares a property and the rust type of its value.
+pub trait TypedProperty {
+    /// Name of the property, like `SUMMARY` or `X-WR-CALNAME`
+    const NAME: &'static str;
+
+    /// [Value type](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3) of the property.
+    ///
+    /// A `VALUE` parameter is added if it differs from the type the RFCs register for [`Self::NAME`],
+    /// `X-` properties have no registered type and write whatever [`Self::to_property()`] returns.
+    const VALUE_TYPE: ValueType;
+
+    /// How often the property may occur within one component
+    const CARDINALITY: Cardinality = Cardinality::Once;
+
+    /// Kinds of components the property may be set on, like `VEVENT`, empty allows any
+    const COMPONENTS: &'static [&'static str] = &[];
+
+    /// The rust type of the value
+    type Value;
+
+    /// Converts a value into a property named [`Self::NAME`].
+    fn to_property(value: Self::Value) -> Property;
+
+    /// Reads the value of a property, `None` if it is not valid.
+    fn from_property(property: &Property) -> Option<Self::Value>;
+
+    /// Returns `true` if the property may be set on components of the given kind.
+    fn allowed_in(kind: &str) -> bool {
+        Self::COMPONENTS.is_empty() || Self::COMPONENTS.contains(&kind)
+    }
+}
+
+/// Converts a value into a property including the `VALUE` parameter if the name implies another type.
+pub(crate) fn typed_property<P: TypedProperty + ?Sized>(value: P::Value) -> Property {
+    let mut property = P::to_property(value);
+    let registered = ValueType::by_name(P::NAME);
+    if registered.is_some() && property.value_type() != Some(P::VALUE_TYPE) {
+        property.add_parameter("VALUE", &P::VALUE_TYPE.to_string());
+    }
+    property
+}
+
+macro_rules! string_property {
+    ($(#[$meta:meta])* $name:ident, $key:literal, $value_type:ident, $cardinality:ident) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        pub struct $name;
+
+        impl TypedProperty for $name {
+            const NAME: &'static str = $key;
+            const VALUE_TYPE: ValueType = ValueType::$value_type;
+            const CARDINALITY: Cardinality = Cardinality::$cardinality;
+            type Value = String;
+
+            fn to_property(value: String) -> Property {
+                Property::new(Self::NAME, value)
+            }
+
+            fn from_property(property: &Property) -> Option<String> {
+                Some(property.value().to_owned())
+            }
+        }
+    };
+}
+pub(crate) use string_property;
+
+string_property! {
+    /// [`SUMMARY`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.12)
+    Summary, "SUMMARY", Text, Once
+}
+string_property! {
+    /// [`DESCRIPTION`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.5)
+    Description, "DESCRIPTION", Text, Once
+}
+string_property! {
+    /// [`LOCATION`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.7),
+    /// named apart from the structured [`Location`](crate::Location) component
+    LocationText, "LOCATION", Text, Once
+}
+string_property! {
+    /// [`COMMENT`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.4)
+    Comment, "COMMENT", Text, Many
+}
+string_property! {
+    /// [`CONTACT`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.2)
+    Contact, "CONTACT", Text, Many
+}
+string_property! {
+    /// [`UID`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.7)
+    Uid, "UID", Text, Once
+}
+string_property! {
+    /// [`URL`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.6)
+    Url, "URL", Uri, Once
+}
+
+/// [`PRIORITY`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.9),
+/// values above 10 are capped when set and rejected when read
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Priority;
+
+impl TypedProperty for Priority {
+    const NAME: &'static str = "PRIORITY";
+    const VALUE_TYPE: ValueType = ValueType::Integer;
+    const COMPONENTS: &'static [&'static str] = &["VEVENT", "VTODO"];
+    type Value = u32;
+
+    fn to_property(priority: u32) -> Property {
+        Property::new(Self::NAME, priority.min(10).to_string())
+    }
+
+    fn from_property(property: &Property) -> Option<u32> {
+        property
+            .value()
+            .parse()
+            .ok()
+            .filter(|priority| *priority <= 10)
+    }
+}
+
+/// [`SEQUENCE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.7.4)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sequence;
+
+impl TypedProperty for Sequence {
+    const NAME: &'static str = "SEQUENCE";
+    const VALUE_TYPE: ValueType = ValueType::Integer;
+    type Value = u32;
+
+    fn to_property(sequence: u32) -> Property {
+        Property::new(Self::NAME, sequence.to_string())
+    }
+
+    fn from_property(property: &Property) -> Option<u32> {
+        property.value().parse().ok()
+    }
+}
+
+/// [`PERCENT-COMPLETE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.8),
+/// values above 100 are capped when set and rejected when read
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PercentComplete;
+
+impl TypedProperty for PercentComplete {
+    const NAME: &'static str = "PERCENT-COMPLETE";
+    const VALUE_TYPE: ValueType = ValueType::Integer;
+    const COMPONENTS: &'static [&'static str] = &["VTODO"];
+    type Value = u8;
+
+    fn to_property(percent: u8) -> Property {
+        Property::new(Self::NAME, percent.min(100).to_string())
+    }
+
+    fn from_property(property: &Property) -> Option<u8> {
+        property
+            .value()
+            .parse()
+            .ok()
+            .filter(|percent| *percent <= 100)
+    }
+}
+
+impl TypedProperty for Class {
+    const NAME: &'static str = "CLASS";
+    const VALUE_TYPE: ValueType = ValueType::Text;
+    type Value = Class;
+
+    fn to_property(class: Class) -> Property {
+        class.into()
+    }
+
+    fn from_property(property: &Property) -> Option<Class> {
+        Class::from_str(property.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Component, Event, Todo};
+
+    /// A custom text property that may occur multiple times
+    struct Room;
+
+    impl TypedProperty for Room {
+        const NAME: &'static str = "X-ROOM";
+        const VALUE_TYPE: ValueType = ValueType::Text;
+        const CARDINALITY: Cardinality = Cardinality::Many;
+        type Value = String;
+
+        fn to_property(value: String) -> Property {
+            Property::new(Self::NAME, value)
+        }
+
+        fn from_property(property: &Property) -> Option<String> {
+            Some(property.value().to_owned())
+        }
+    }
+
+    #[test]
+    fn builtin_properties() {
+        let todo = Todo::new()
+            .set::<Summary>("Taxes".into())
+            .set::<Priority>(12)
+            .set::<PercentComplete>(40)
+            .set::<Class>(Class::Private)
+            .set::<Comment>("first".into())
+            .set::<Comment>("second".into())
+            .done();
+        assert_eq!(todo.get_summary(), Some("Taxes"));
+        assert_eq!(todo.get_priority(), Some(10));
+        assert_eq!(todo.get::<PercentComplete>(), Some(40));
+        assert_eq!(todo.get_class(), Some(Class::Private));
+        assert_eq!(todo.get::<Comment>().as_deref(), Some("first"));
+        assert_eq!(todo.get_all::<Comment>(), ["first", "second"]);
+
+        let done = Todo::new().set::<PercentComplete>(140).done();
+        assert_eq!(done.property_value("PERCENT-COMPLETE"), Some("100"));
+        assert_eq!(done.get::<PercentComplete>(), Some(100));
+
+        let event = Event::new().summary("Lunch").sequence(3).done();
+        assert_eq!(event.get::<Summary>().as_deref(), Some("Lunch"));
+        assert_eq!(event.get::<Sequence>(), Some(3));
+        assert_eq!(event.get::<Priority>(), None);
+    }
+
+    #[test]
+    fn custom_properties() {
+        let event = Event::new()
+            .set::<Room>("A, 1st floor".into())
+            .set::<Room>("B".into())
+            .done();
+        assert_eq!(event.get_all::<Room>(), ["A, 1st floor", "B"]);
+        assert!(event.to_string().contains("X-ROOM:B\r\n"));
+    }
+
+    #[test]
+    fn allowed_components() {
+        assert!(Priority::allowed_in("VTODO"));
+        assert!(!Priority::allowed_in("VJOURNAL"));
+        assert!(Room::allowed_in("VJOURNAL"));
+    }
+}
@@ -1,6 +1,6 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-use crate::Parameter;
+use crate::{properties::normalize_name, Parameter};
 
 /// see 8.3.4. [Value Data Types Registry](https://tools.ietf.org/html/rfc5545#section-8.3.4)
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -36,13 +36,16 @@ pub enum ValueType {
 }
 
 impl ValueType {
-    pub(crate) fn by_name(name: &str) -> Option<Self> {
-        if name.chars().any(char::is_lowercase) {
-            // eprintln!("property_name must be uppercase");
-            return None;
-        }
+    /// The default value type of a registered property, `None` for unknown names
+    ///
+    /// Property names are case-insensitive.
+    /// ```
+    /// # use icalendar::ValueType;
+    /// assert_eq!(ValueType::by_name("dtstart"), Some(ValueType::DateTime));
+    /// ```
+    pub fn by_name(name: &str) -> Option<Self> {
         use ValueType::*;
-        match name {
+        match &*normalize_name(name) {
             // 3.7.0 calendar properties
             "CALSCALE" => Some(Text), // 3.7.1
             "METHOD" => Some(Text),   // 3.7.2
@@ -95,6 +98,19 @@ impl ValueType {
             // "An IANA-registered property name" => Any parameter can be specified on this property.
             // "X-"/* ... */ => Some(Text),       // any type 3.8.8.2
             "REQUEST-STATUS" => Some(Text), // 3.8.8.3
+
+            // RFC 9073
+            "LOCATION-TYPE" => Some(Text),          // 6.1
+            "PARTICIPANT-TYPE" => Some(Text),       // 6.2
+            "RESOURCE-TYPE" => Some(Text),          // 6.3
+            "CALENDAR-ADDRESS" => Some(CalAddress), // 6.4
+            "STYLED-DESCRIPTION" => Some(Text),     // or URI // 6.5
+            "STRUCTURED-DATA" => Some(Text),        // or BINARY // or URI // 6.6
+
+            // RFC 9074
+            "ACKNOWLEDGED" => Some(DateTime), // 6
+            "PROXIMITY" => Some(Text),        // 8.1
+            "DEFAULT-ALARM" => Some(Boolean), // 9
             _ => None,
         }
     }
@@ -135,3 +151,24 @@ impl FromStr for ValueType {
         }
     }
 }
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Binary => "BINARY",
+            Self::Boolean => "BOOLEAN",
+            Self::CalAddress => "CAL-ADDRESS",
+            Self::Date => "DATE",
+            Self::DateTime => "DATE-TIME",
+            Self::Duration => "DURATION",
+            Self::Float => "FLOAT",
+            Self::Integer => "INTEGER",
+            Self::Period => "PERIOD",
+            Self::Recur => "RECUR",
+            Self::Text => "TEXT",
+            Self::Time => "TIME",
+            Self::Uri => "URI",
+            Self::UtcOffset => "UTC-OFFSET",
+        })
+    }
+}
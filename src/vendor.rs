@@ -0,0 +1,17 @@
+//! Typed `X-` properties of widespread vendor extensions
+//!
+//! These are [`TypedProperty`](crate::typed::TypedProperty)s, set and read them with
+//! [`Component::set()`](crate::Component::set), [`Component::get()`](crate::Component::get)
+//! or [`Calendar::set()`](crate::Calendar::set) for calendar properties.
+//! Where the RFCs define an equivalent, the types convert from and to it,
+//! so files imported from Outlook or iCloud keep their meaning.
+//!
+//! Except for [`wr`], each module requires the feature of the same name.
+
+#[cfg(feature = "apple")]
+pub mod apple;
+#[cfg(feature = "google")]
+pub mod google;
+#[cfg(feature = "microsoft")]
+pub mod microsoft;
+pub mod wr;
@@ -0,0 +1,391 @@
+//! Options controlling how calendars and components are serialized
+
+use std::{
+    borrow::Borrow,
+    fmt::{self, Write},
+};
+
+use crate::{properties::fold_line_with, vendor::wr, Property};
+
+/// Line ending written after every content line
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// `\r\n`, required by [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545#section-3.1)
+    Crlf,
+    /// `\n`, accepted by most parsers and friendlier to line-based tools
+    Lf,
+}
+
+impl LineEnding {
+    /// Returns the line ending as a string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Lf => "\n",
+        }
+    }
+}
+
+/// Order in which the properties of a calendar or component are written
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PropertyOrder {
+    /// The order properties are stored in.
+    ///
+    /// Calendar properties keep the order they were added in, component properties are
+    /// stored by name and properties that may occur multiple times follow the others.
+    Insertion,
+    /// Sorted by name, value and parameter names, so equal content always serializes the same
+    Canonical,
+    /// The order properties are listed in by the grammar of
+    /// [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545#section-3.6),
+    /// like `DTSTAMP`, `UID` and `DTSTART` first, unknown and `X-` properties last
+    Recommended,
+}
+
+/// Calendar properties in the order of the examples in
+/// [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545#section-3.4)
+const RECOMMENDED_CALENDAR_ORDER: [&str; 4] = ["PRODID", "VERSION", "CALSCALE", "METHOD"];
+
+/// Component properties in the order of the grammars in
+/// [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545#section-3.6)
+const RECOMMENDED_COMPONENT_ORDER: [&str; 40] = [
+    "DTSTAMP",
+    "UID",
+    "TZID",
+    "ACTION",
+    "TRIGGER",
+    "DTSTART",
+    "TZOFFSETTO",
+    "TZOFFSETFROM",
+    "TZNAME",
+    "CLASS",
+    "COMPLETED",
+    "CREATED",
+    "DESCRIPTION",
+    "GEO",
+    "LAST-MODIFIED",
+    "LOCATION",
+    "ORGANIZER",
+    "PERCENT-COMPLETE",
+    "PRIORITY",
+    "SEQUENCE",
+    "STATUS",
+    "SUMMARY",
+    "TRANSP",
+    "URL",
+    "RECURRENCE-ID",
+    "RRULE",
+    "DTEND",
+    "DUE",
+    "DURATION",
+    "REPEAT",
+    "ATTACH",
+    "ATTENDEE",
+    "CATEGORIES",
+    "COMMENT",
+    "CONTACT",
+    "EXDATE",
+    "REQUEST-STATUS",
+    "RELATED-TO",
+    "RESOURCES",
+    "RDATE",
+];
+
+/// Configures how [`Calendar`](crate::Calendar)s and [`Component`](crate::Component)s are serialized.
+///
+/// The default writes what [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545) asks for,
+/// which is also what [`Display`](std::fmt::Display) writes.
+///
+/// ```
+/// # use icalendar::*;
+/// let calendar = Calendar::new()
+///     .name("Work")
+///     .push(Event::new().uid("standup").summary("Standup").timestamp(chrono::Utc::now()).done())
+///     .done();
+///
+/// let options = WriteOptions::new()
+///     .line_ending(LineEnding::Lf)
+///     .property_order(PropertyOrder::Recommended)
+///     .x_wr_duplicates(false);
+/// let ics = calendar.to_string_with(&options);
+/// assert!(ics.starts_with("BEGIN:VCALENDAR\nPRODID:"));
+/// assert!(!ics.contains("X-WR-CALNAME"));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WriteOptions {
+    fold_width: Option<usize>,
+    line_ending: LineEnding,
+    property_order: PropertyOrder,
+    inject_defaults: bool,
+    x_wr_duplicates: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        WriteOptions {
+            fold_width: Some(Self::RFC_FOLD_WIDTH),
+            line_ending: LineEnding::Crlf,
+            property_order: PropertyOrder::Insertion,
+            inject_defaults: true,
+            x_wr_duplicates: true,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Maximum length of a content line in octets, as recommended by
+    /// [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545#section-3.1)
+    pub const RFC_FOLD_WIDTH: usize = 75;
+
+    /// Narrowest fold width, smaller widths are raised to this one
+    pub const MIN_FOLD_WIDTH: usize = 16;
+
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Options for output that is compared or hashed rather than imported:
+    /// canonical order, no folding, `\n` line endings and no generated `DTSTAMP` or `UID`.
+    pub fn canonical() -> Self {
+        WriteOptions {
+            fold_width: None,
+            line_ending: LineEnding::Lf,
+            property_order: PropertyOrder::Canonical,
+            inject_defaults: false,
+            x_wr_duplicates: true,
+        }
+    }
+
+    /// Folds content lines longer than `width` octets, including the leading space of continuation lines.
+    pub fn fold_width(mut self, width: usize) -> Self {
+        self.fold_width = Some(width.max(Self::MIN_FOLD_WIDTH));
+        self
+    }
+
+    /// Writes every content line on a single line, regardless of its length.
+    pub fn no_folding(mut self) -> Self {
+        self.fold_width = None;
+        self
+    }
+
+    /// Sets the line ending.
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Sets the order in which properties are written.
+    pub fn property_order(mut self, property_order: PropertyOrder) -> Self {
+        self.property_order = property_order;
+        self
+    }
+
+    /// Whether a current `DTSTAMP` and random `UID` are written for components lacking them, enabled by default.
+    pub fn inject_defaults(mut self, inject_defaults: bool) -> Self {
+        self.inject_defaults = inject_defaults;
+        self
+    }
+
+    /// Whether [`X-WR-` properties](crate::vendor::wr) are written next to their standard equivalents,
+    /// like `X-WR-CALNAME` next to `NAME`, enabled by default.
+    pub fn x_wr_duplicates(mut self, x_wr_duplicates: bool) -> Self {
+        self.x_wr_duplicates = x_wr_duplicates;
+        self
+    }
+
+    /// Returns the fold width, `None` if lines are not folded.
+    pub fn get_fold_width(&self) -> Option<usize> {
+        self.fold_width
+    }
+
+    /// Returns the line ending.
+    pub fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Returns the property order.
+    pub fn get_property_order(&self) -> PropertyOrder {
+        self.property_order
+    }
+
+    /// Returns whether `DTSTAMP` and `UID` are generated.
+    pub fn get_inject_defaults(&self) -> bool {
+        self.inject_defaults
+    }
+
+    /// Returns whether `X-WR-` duplicates are written.
+    pub fn get_x_wr_duplicates(&self) -> bool {
+        self.x_wr_duplicates
+    }
+
+    /// Writes a content line, folded and terminated as configured.
+    pub(crate) fn write_line<W: Write>(&self, out: &mut W, line: &str) -> fmt::Result {
+        match self.fold_width {
+            Some(width) if line.len() > width => {
+                out.write_str(&fold_line_with(line, width, self.line_ending.as_str()))?
+            }
+            _ => out.write_str(line)?,
+        }
+        out.write_str(self.line_ending.as_str())
+    }
+
+    /// Sorts the properties of a component.
+    pub(crate) fn sort_component_properties<P: Borrow<Property>>(&self, properties: &mut [P]) {
+        self.sort(properties, &RECOMMENDED_COMPONENT_ORDER);
+    }
+
+    /// Sorts and filters the properties of a calendar.
+    pub(crate) fn calendar_properties<'p>(&self, properties: &'p [Property]) -> Vec<&'p Property> {
+        let mut kept = properties
+            .iter()
+            .filter(|property| self.x_wr_duplicates || !is_x_wr_duplicate(property, properties))
+            .collect::<Vec<_>>();
+        self.sort(&mut kept, &RECOMMENDED_CALENDAR_ORDER);
+        kept
+    }
+
+    fn sort<P: Borrow<Property>>(&self, properties: &mut [P], recommended: &[&str]) {
+        match self.property_order {
+            PropertyOrder::Insertion => {}
+            PropertyOrder::Canonical => properties.sort_by(|a, b| {
+                let (a, b) = (a.borrow(), b.borrow());
+                a.key()
+                    .cmp(b.key())
+                    .then_with(|| a.value().cmp(b.value()))
+                    .then_with(|| sorted_params(a).cmp(&sorted_params(b)))
+            }),
+            PropertyOrder::Recommended => properties.sort_by_key(|property| {
+                recommended
+                    .iter()
+                    .position(|key| *key == property.borrow().key())
+                    .unwrap_or(recommended.len())
+            }),
+        }
+    }
+
+    /// Returns `true` if parameters are written sorted by name.
+    pub(crate) fn sorts_parameters(&self) -> bool {
+        self.property_order == PropertyOrder::Canonical
+    }
+}
+
+fn sorted_params(property: &Property) -> Vec<(&str, &[String])> {
+    let mut params = property
+        .params()
+        .values()
+        .map(|param| (param.key(), param.values()))
+        .collect::<Vec<_>>();
+    params.sort_unstable();
+    params
+}
+
+fn is_x_wr_duplicate(property: &Property, properties: &[Property]) -> bool {
+    wr::STANDARD_EQUIVALENTS
+        .iter()
+        .find(|(alias, _)| *alias == property.key())
+        .map_or(false, |(_, standard)| {
+            properties.iter().any(|other| other.key() == *standard)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Calendar, Component, Event};
+
+    fn event() -> Event {
+        Event::new()
+            .uid("lunch")
+            .summary("Lunch")
+            .add_property("DTSTAMP", "20240101T000000Z")
+            .add_property("DTSTART", "20240108T120000Z")
+            .append_multi_property(("ATTENDEE", "mailto:b@example.com"))
+            .append_multi_property(("ATTENDEE", "mailto:a@example.com"))
+            .add_property("X-ROOM", "B")
+            .done()
+    }
+
+    #[test]
+    fn default_matches_display() {
+        let calendar = Calendar::new().name("Work").push(event()).done();
+        assert_eq!(
+            calendar.to_string_with(&WriteOptions::default()),
+            calendar.to_string()
+        );
+    }
+
+    #[test]
+    fn property_orders() {
+        let keys = |options: WriteOptions| {
+            event()
+                .to_string_with(&options.line_ending(LineEnding::Lf))
+                .lines()
+                .map(|line| line.split([':', ';']).next().unwrap_or_default().to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            keys(WriteOptions::new()),
+            [
+                "BEGIN", "DTSTAMP", "DTSTART", "SUMMARY", "UID", "X-ROOM", "ATTENDEE", "ATTENDEE",
+                "END"
+            ]
+        );
+        assert_eq!(
+            keys(WriteOptions::new().property_order(PropertyOrder::Recommended)),
+            [
+                "BEGIN", "DTSTAMP", "UID", "DTSTART", "SUMMARY", "ATTENDEE", "ATTENDEE", "X-ROOM",
+                "END"
+            ]
+        );
+        let canonical = event().to_string_with(&WriteOptions::canonical());
+        assert!(canonical
+            .contains("ATTENDEE:mailto:a@example.com\nATTENDEE:mailto:b@example.com\nDTSTAMP:"));
+    }
+
+    #[test]
+    fn folding_and_line_endings() {
+        let event = Event::new().description(&"long ".repeat(20)).done();
+        let folded = event.to_string_with(&WriteOptions::new().inject_defaults(false));
+        assert!(folded.lines().all(|line| line.len() <= 75));
+        assert!(folded.contains("\r\n "));
+
+        let narrow = event.to_string_with(
+            &WriteOptions::new()
+                .inject_defaults(false)
+                .fold_width(40)
+                .line_ending(LineEnding::Lf),
+        );
+        assert!(!narrow.contains('\r'));
+        assert!(narrow.lines().all(|line| line.len() <= 40));
+
+        let unfolded = event.to_string_with(&WriteOptions::new().no_folding());
+        assert!(unfolded.lines().any(|line| line.len() > 75));
+    }
+
+    #[test]
+    fn no_injected_defaults() {
+        let written = Event::new()
+            .summary("Lunch")
+            .done()
+            .to_string_with(&WriteOptions::new().inject_defaults(false));
+        assert_eq!(written, "BEGIN:VEVENT\r\nSUMMARY:Lunch\r\nEND:VEVENT\r\n");
+    }
+
+    #[test]
+    fn x_wr_duplicates() {
+        let calendar = Calendar::empty()
+            .name("Work")
+            .timezone("Europe/Berlin")
+            .append_property(("X-WR-RELCALID", "work"))
+            .done();
+        let written = calendar.to_string_with(&WriteOptions::new().x_wr_duplicates(false));
+        assert!(written.contains("NAME:Work"));
+        assert!(!written.contains("X-WR-CALNAME"));
+        assert!(written.contains("X-WR-RELCALID:work"));
+        // `TIMEZONE-ID` is not standardized either
+        assert!(written.contains("X-WR-TIMEZONE:Europe/Berlin"));
+    }
+}
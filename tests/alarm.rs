@@ -20,7 +20,7 @@ UID:uid4@example.com\r
 BEGIN:VALARM\r
 ACTION:AUDIO\r
 DTSTAMP:19980130T134500Z\r
-DURATION:PT3600S\r
+DURATION:PT1H\r
 REPEAT:4\r
 TRIGGER;VALUE=DATE-TIME:19980403T120000Z\r
 UID:OverwriteForConsistency\r
@@ -0,0 +1,174 @@
+#![cfg(feature = "cli")]
+use std::{
+    fs,
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+use pretty_assertions::assert_eq;
+
+const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//example//cli test//EN\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTAMP:20240101T080000Z\r
+DTSTART:20240101T090000Z\r
+DTEND:20240101T091500Z\r
+RRULE:FREQ=DAILY;COUNT=3\r
+SUMMARY:Standup\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:report@example.com\r
+DTSTAMP:20240101T080000Z\r
+DUE:20240201T170000Z\r
+SUMMARY:Write the report\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+fn ical(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ical"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // commands that fail on their arguments exit without reading standard input
+    let written = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    if let Err(error) = written {
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn file(name: &str, content: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn validate() {
+    let valid = ical(&["validate"], CALENDAR);
+    assert_eq!(valid.status.code(), Some(0));
+    assert_eq!(stdout(&valid), "-: ok, 0 warnings\n");
+
+    let invalid = CALENDAR.replace("DTEND:20240101T091500Z", "DTEND:20231231T091500Z");
+    let invalid = ical(&["validate"], &invalid);
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(stdout(&invalid).contains("error: VEVENT standup@example.com: DTEND is before DTSTART"));
+
+    let broken = ical(&["validate"], "BEGIN:VCALENDAR\nBEGIN:VEVENT\nEND:VTODO\n");
+    assert_eq!(broken.status.code(), Some(1));
+    assert!(stdout(&broken).starts_with("-: error in line 3:"));
+}
+
+#[test]
+fn format() {
+    let canonical = ical(&["format", "--canonical"], CALENDAR);
+    assert_eq!(canonical.status.code(), Some(0));
+    let canonical = stdout(&canonical);
+    assert!(!canonical.contains('\r'));
+    assert!(canonical.contains("BEGIN:VEVENT\nDTEND:20240101T091500Z\nDTSTAMP"));
+
+    let pretty = stdout(&ical(&["format", "--pretty"], CALENDAR));
+    assert!(pretty.starts_with("VCALENDAR\n  VERSION: 2.0\n"));
+    assert!(pretty.contains("\n  VTODO\n    UID: report@example.com\n"));
+}
+
+#[test]
+fn convert_round_trips() {
+    for format in ["jcal", "json"] {
+        let converted = ical(&["convert", "--to", format], CALENDAR);
+        assert_eq!(converted.status.code(), Some(0));
+        let back = ical(&["convert", "--to", "ics"], &stdout(&converted));
+        assert_eq!(stdout(&back), CALENDAR, "via {}", format);
+    }
+
+    let jcal = stdout(&ical(&["convert", "--to=jcal"], CALENDAR));
+    let jcal: serde_json::Value = serde_json::from_str(&jcal).unwrap();
+    assert_eq!(
+        jcal[2][1][1][2],
+        serde_json::json!(["due", {}, "date-time", "2024-02-01T17:00:00Z"])
+    );
+}
+
+#[test]
+fn expand() {
+    let expanded = ical(
+        &["expand", "--from", "2024-01-02", "--to", "2024-01-31"],
+        CALENDAR,
+    );
+    assert_eq!(
+        stdout(&expanded),
+        "2024-01-02T09:00:00Z\t2024-01-02T09:15:00Z\tVEVENT\tstandup@example.com\tStandup\n\
+         2024-01-03T09:00:00Z\t2024-01-03T09:15:00Z\tVEVENT\tstandup@example.com\tStandup\n"
+    );
+    assert_eq!(
+        ical(&["expand", "--from", "2024-01-02"], CALENDAR)
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn filter() {
+    let by_text = stdout(&ical(&["filter", "--text", "REPORT"], CALENDAR));
+    assert!(by_text.contains("UID:report@example.com"));
+    assert!(!by_text.contains("UID:standup@example.com"));
+
+    let by_time = stdout(&ical(
+        &["filter", "--from", "2024-01-03", "--to", "2024-01-04"],
+        CALENDAR,
+    ));
+    assert!(by_time.contains("UID:standup@example.com"));
+    assert!(!by_time.contains("UID:report@example.com"));
+}
+
+#[test]
+fn diff_and_merge() {
+    let old = file("cli_old.ics", CALENDAR);
+    let new = file(
+        "cli_new.ics",
+        &CALENDAR.replace("SUMMARY:Standup", "SUMMARY:Daily standup\r\nSEQUENCE:1"),
+    );
+    let (old, new) = (old.to_str().unwrap(), new.to_str().unwrap());
+
+    assert_eq!(ical(&["diff", old, old], "").status.code(), Some(0));
+    let diff = ical(&["diff", old, new], "");
+    assert_eq!(diff.status.code(), Some(1));
+    assert_eq!(
+        stdout(&diff),
+        "~ VEVENT standup@example.com\n    ~ SUMMARY: Standup -> Daily standup\n    + SEQUENCE: 1\n"
+    );
+    let ignored = ical(
+        &["diff", "--ignore", "summary", "--ignore=SEQUENCE", old, new],
+        "",
+    );
+    assert_eq!(ignored.status.code(), Some(0));
+
+    let merged = ical(&["merge", old, new], "");
+    assert_eq!(merged.status.code(), Some(0));
+    assert!(stdout(&merged).contains("SUMMARY:Daily standup"));
+    assert!(String::from_utf8_lossy(&merged.stderr).contains("1 duplicates, 1 conflicts"));
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(ical(&[], "").status.code(), Some(2));
+    assert_eq!(ical(&["help"], "").status.code(), Some(0));
+    assert_eq!(ical(&["frobnicate"], "").status.code(), Some(2));
+    assert_eq!(
+        ical(&["format", "--colour"], CALENDAR).status.code(),
+        Some(2)
+    );
+    assert_eq!(ical(&["diff", "only-one.ics"], "").status.code(), Some(2));
+}
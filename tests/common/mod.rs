@@ -0,0 +1,14 @@
+//! Helpers shared by the integration tests
+
+/// Deterministic pseudo random bytes to feed [`arbitrary::Unstructured`]
+pub fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
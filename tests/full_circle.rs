@@ -5,6 +5,9 @@ use chrono::*;
 use icalendar::*;
 // use pretty_assertions::assert_eq;
 
+#[cfg(feature = "arbitrary")]
+mod common;
+
 fn get_summary(calendar: &Calendar) -> &str {
     calendar.components[0]
         .as_event()
@@ -107,3 +110,25 @@ fn reparse_equivalence() {
 
     assert_eq!(get_summary(&built_calendar), get_summary(&from_parsed),);
 }
+
+#[test]
+#[cfg(feature = "arbitrary")]
+fn generated_calendars_round_trip() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    for seed in 1..=64 {
+        let bytes = common::random_bytes(seed, 8192);
+        let generated = Calendar::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+
+        // without injected defaults the parsed calendar is exactly the generated one
+        let options = WriteOptions::default().inject_defaults(false);
+        let parsed = Calendar::from_str(&generated.to_string_with(&options)).unwrap();
+        assert_eq!(generated, parsed, "seed {}", seed);
+
+        // injected defaults are kept from then on
+        let once = Calendar::from_str(&parsed.to_string()).unwrap();
+        let twice = Calendar::from_str(&once.to_string()).unwrap();
+        assert_eq!(once, twice, "seed {}", seed);
+        assert_eq!(once.to_string(), twice.to_string(), "seed {}", seed);
+    }
+}
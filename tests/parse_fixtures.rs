@@ -6,7 +6,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use icalendar::parser::{read_calendar, unfold};
+use icalendar::parser::{read_calendar, read_calendar_folded, unfold};
 
 fn with_all_fixtures<F>(
     sub_folder: impl AsRef<Path>,
@@ -70,3 +70,20 @@ fn parse_fixtures_icalendar_rb_bad_utf8() {
     })
     .unwrap();
 }
+
+#[test]
+fn folded_parser_matches_unfold() {
+    with_all_fixtures("icalendar-rb", |path| {
+        let fixture = std::fs::read_to_string(path).unwrap();
+        let folded = read_calendar_folded(&fixture).unwrap();
+        let unfolded = unfold(&fixture);
+        let reference = read_calendar(&unfolded).unwrap();
+        // `read_calendar()` yields nothing for documents with several root components
+        if !reference.components.is_empty() {
+            assert_eq!(folded.properties, reference.properties);
+            assert_eq!(folded.components, reference.components);
+        }
+        assert!(!folded.components.is_empty());
+    })
+    .unwrap();
+}
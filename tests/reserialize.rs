@@ -2,6 +2,9 @@
 use icalendar::parser::unfold;
 use pretty_assertions::assert_eq;
 
+#[cfg(feature = "arbitrary")]
+mod common;
+
 const SAMPLE: &str = "\
 BEGIN:VCALENDAR\r
 VERSION:2.0\r
@@ -50,3 +53,19 @@ fn reserialization() {
     println!("{}", reserialized);
     assert_eq!(SAMPLE, reserialized);
 }
+
+#[test]
+#[cfg(feature = "arbitrary")]
+fn reserialization_of_generated_calendars() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    for seed in 1..=64 {
+        let bytes = common::random_bytes(seed, 8192);
+        let generated = icalendar::Calendar::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        let serialized = generated.to_string();
+
+        let unfolded = unfold(&serialized);
+        let parsed = icalendar::parser::read_calendar(&unfolded).unwrap();
+        assert_eq!(serialized, parsed.to_string(), "seed {}", seed);
+    }
+}